@@ -0,0 +1,126 @@
+//! Compares the enum based `Number` with the previous struct of twelve `Option` fields.
+//!
+//! Run with `cargo bench -p valu3 --bench number`.
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+use valu3::prelude::*;
+
+const ITERATIONS: usize = 1_000_000;
+
+/// The former layout of `Number`, kept here as the baseline.
+#[derive(Debug, Clone, Default, PartialEq, PartialOrd)]
+struct LegacyNumber {
+    u8: Option<u8>,
+    u16: Option<u16>,
+    u32: Option<u32>,
+    u64: Option<u64>,
+    u128: Option<u128>,
+    i8: Option<i8>,
+    i16: Option<i16>,
+    i32: Option<i32>,
+    i64: Option<i64>,
+    i128: Option<i128>,
+    f32: Option<f32>,
+    f64: Option<f64>,
+}
+
+impl LegacyNumber {
+    fn from_i64(value: i64) -> Self {
+        LegacyNumber {
+            i64: Some(value),
+            ..Default::default()
+        }
+    }
+
+    /// The previous getters walked the fields until one was set.
+    fn as_i64(&self) -> Option<i64> {
+        if self.u8.is_some() {
+            self.u8.map(i64::from)
+        } else if self.u16.is_some() {
+            self.u16.map(i64::from)
+        } else if self.u32.is_some() {
+            self.u32.map(i64::from)
+        } else if self.i8.is_some() {
+            self.i8.map(i64::from)
+        } else if self.i16.is_some() {
+            self.i16.map(i64::from)
+        } else if self.i32.is_some() {
+            self.i32.map(i64::from)
+        } else {
+            self.i64
+        }
+    }
+}
+
+fn as_i64(number: &Number) -> Option<i64> {
+    match *number {
+        Number::U8(value) => Some(value.into()),
+        Number::U16(value) => Some(value.into()),
+        Number::U32(value) => Some(value.into()),
+        Number::I8(value) => Some(value.into()),
+        Number::I16(value) => Some(value.into()),
+        Number::I32(value) => Some(value.into()),
+        Number::I64(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn measure<F: FnMut()>(name: &str, mut run: F) -> Duration {
+    // Warm up caches and the allocator before timing.
+    run();
+
+    let start = Instant::now();
+    run();
+    let elapsed = start.elapsed();
+
+    println!("{:<32} {:>12.3?}", name, elapsed);
+    elapsed
+}
+
+fn main() {
+    println!(
+        "{:<32} {:>12}",
+        "size_of::<LegacyNumber>()",
+        size_of::<LegacyNumber>()
+    );
+    println!("{:<32} {:>12}", "size_of::<Number>()", size_of::<Number>());
+    println!("{:<32} {:>12}", "size_of::<Value>()", size_of::<Value>());
+    println!();
+
+    let numbers: Vec<Number> = (0..ITERATIONS as i64).map(Number::from).collect();
+    let legacy: Vec<LegacyNumber> = (0..ITERATIONS as i64).map(LegacyNumber::from_i64).collect();
+
+    measure("construct legacy", || {
+        let items: Vec<LegacyNumber> = (0..ITERATIONS as i64).map(LegacyNumber::from_i64).collect();
+        black_box(items);
+    });
+    measure("construct enum", || {
+        let items: Vec<Number> = (0..ITERATIONS as i64).map(Number::from).collect();
+        black_box(items);
+    });
+
+    measure("sum legacy", || {
+        let sum: i64 = legacy.iter().filter_map(LegacyNumber::as_i64).sum();
+        black_box(sum);
+    });
+    measure("sum enum", || {
+        let sum: i64 = numbers.iter().filter_map(as_i64).sum();
+        black_box(sum);
+    });
+
+    measure("compare legacy", || {
+        let count = legacy.windows(2).filter(|pair| pair[0] < pair[1]).count();
+        black_box(count);
+    });
+    measure("compare enum", || {
+        let count = numbers.windows(2).filter(|pair| pair[0] < pair[1]).count();
+        black_box(count);
+    });
+
+    measure("sort values", || {
+        let mut values: Vec<Value> = numbers.iter().rev().cloned().map(Value::Number).collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        black_box(values);
+    });
+}
@@ -0,0 +1,445 @@
+//! Structural differences between two values, listed as `Change`s and written as a report
+//! with `DiffReport`, or checked with `assert_value_eq!`.
+//!
+//! Paths are JSON Pointers, the empty path being the whole value. Objects are compared
+//! member by member in key order. Arrays are aligned on their longest common subsequence,
+//! so an insertion is one `Added` change instead of a change for each following element;
+//! elements left over between two aligned ones are compared in pairs.
+//!
+//! The alignment keeps a table as large as the product of the lengths of the differing
+//! parts of both arrays, once their common start and end are left out. Above
+//! `MAX_ALIGNMENT_CELLS`, the arrays are compared index by index instead.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let old = value!({"name": "Ada", "tags": ["a", "b"], "age": 36});
+//! let new = value!({"name": "Grace", "tags": ["a", "x", "b"], "age": "36"});
+//!
+//! let changes = old.diff(&new);
+//! assert_eq!(changes[0].path(), "/age");
+//! println!("{}", DiffReport::new(&changes).colored(true));
+//!
+//! assert_value_eq!(old, old.clone());
+//! ```
+use crate::pointer::escape;
+use crate::prelude::*;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// The largest alignment table, in elements of one array times elements of the other,
+/// built to align two arrays. Larger arrays are compared index by index.
+pub const MAX_ALIGNMENT_CELLS: usize = 1 << 22;
+
+/// A difference between two values at a path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A member or element only in the new value.
+    Added { path: String, value: Value },
+    /// A member or element only in the old value.
+    Removed { path: String, value: Value },
+    /// A value of the same kind with another content, such as two different strings.
+    Changed {
+        path: String,
+        from: Value,
+        to: Value,
+    },
+    /// A value replaced by one of another kind, such as a number by a string.
+    TypeChanged {
+        path: String,
+        from: Value,
+        to: Value,
+    },
+}
+
+impl Change {
+    pub fn path(&self) -> &str {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. }
+            | Change::TypeChanged { path, .. } => path,
+        }
+    }
+}
+
+impl Value {
+    /// Lists the changes turning this value into `other`, empty when they are equal.
+    ///
+    /// Removed array elements have their index in this value, other array changes their
+    /// index in `other`.
+    pub fn diff(&self, other: &Value) -> Vec<Change> {
+        let mut changes = Vec::new();
+        diff(self, other, "", &mut changes);
+        changes
+    }
+}
+
+fn diff(from: &Value, to: &Value, path: &str, changes: &mut Vec<Change>) {
+    if from == to {
+        return;
+    }
+
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            let mut keys: Vec<_> = from.keys().into_iter().chain(to.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let path = format!("{}/{}", path, escape(&key.to_string()));
+                match (from.get_by_key(key), to.get_by_key(key)) {
+                    (Some(from), Some(to)) => diff(from, to, &path, changes),
+                    (Some(value), None) => changes.push(Change::Removed {
+                        path,
+                        value: value.clone(),
+                    }),
+                    (None, Some(value)) => changes.push(Change::Added {
+                        path,
+                        value: value.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => {
+            diff_array(&from.values, &to.values, path, changes)
+        }
+        _ if std::mem::discriminant(from) == std::mem::discriminant(to) => {
+            changes.push(Change::Changed {
+                path: path.to_string(),
+                from: from.clone(),
+                to: to.clone(),
+            })
+        }
+        _ => changes.push(Change::TypeChanged {
+            path: path.to_string(),
+            from: from.clone(),
+            to: to.clone(),
+        }),
+    }
+}
+
+fn diff_array(from: &[Value], to: &[Value], path: &str, changes: &mut Vec<Change>) {
+    for (removed, added) in align(from, to) {
+        for (i, j) in removed.clone().zip(added.clone()) {
+            diff(&from[i], &to[j], &format!("{}/{}", path, j), changes);
+        }
+        let pairs = removed.len().min(added.len());
+        for (i, value) in from
+            .iter()
+            .enumerate()
+            .take(removed.end)
+            .skip(removed.start + pairs)
+        {
+            changes.push(Change::Removed {
+                path: format!("{}/{}", path, i),
+                value: value.clone(),
+            });
+        }
+        for (j, value) in to
+            .iter()
+            .enumerate()
+            .take(added.end)
+            .skip(added.start + pairs)
+        {
+            changes.push(Change::Added {
+                path: format!("{}/{}", path, j),
+                value: value.clone(),
+            });
+        }
+    }
+}
+
+/// Aligns two arrays on their longest common subsequence, and returns the gaps between
+/// aligned elements as the range of elements only in `from` and the range only in `to`.
+///
+/// When the table would hold more than `MAX_ALIGNMENT_CELLS`, everything between the
+/// common start and end is returned as a single gap.
+pub(crate) fn align(from: &[Value], to: &[Value]) -> Vec<(Range<usize>, Range<usize>)> {
+    // The common start and end are aligned as they are, which keeps the table below small
+    // when few elements change.
+    let prefix = from.iter().zip(to).take_while(|(a, b)| a == b).count();
+    let suffix = from[prefix..]
+        .iter()
+        .rev()
+        .zip(to[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (from_end, to_end) = (from.len() - suffix, to.len() - suffix);
+
+    // `lengths[i][j]` is the length of the longest common subsequence of `from[prefix + i..]`
+    // and `to[prefix + j..]`, within the ends.
+    let (rows, columns) = (from_end - prefix, to_end - prefix);
+    match (rows + 1).checked_mul(columns + 1) {
+        Some(cells) if cells <= MAX_ALIGNMENT_CELLS => {}
+        _ => return vec![(prefix..from_end, prefix..to_end)],
+    }
+    let mut lengths = vec![vec![0usize; columns + 1]; rows + 1];
+    for i in (0..rows).rev() {
+        for j in (0..columns).rev() {
+            lengths[i][j] = if from[prefix + i] == to[prefix + j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut gaps = Vec::new();
+    let (mut i, mut j) = (prefix, prefix);
+    let (mut removed, mut added) = (prefix..prefix, prefix..prefix);
+    loop {
+        let aligned = i < from_end && j < to_end && from[i] == to[j];
+        if aligned || (i == from_end && j == to_end) {
+            if !removed.is_empty() || !added.is_empty() {
+                gaps.push((removed, added));
+            }
+            if !aligned {
+                return gaps;
+            }
+            i += 1;
+            j += 1;
+            removed = i..i;
+            added = j..j;
+        } else if j == to_end
+            || (i < from_end
+                && lengths[i - prefix + 1][j - prefix] >= lengths[i - prefix][j - prefix + 1])
+        {
+            i += 1;
+            removed.end = i;
+        } else {
+            j += 1;
+            added.end = j;
+        }
+    }
+}
+
+/// Writes changes as a unified report: a `@@ path @@` header for each change, followed by
+/// the old value on a `-` line and the new value on a `+` line.
+///
+/// Values are written as inline JSON. With `colored(true)`, headers are cyan, old values
+/// red and new values green, with ANSI escape codes.
+#[derive(Debug, Clone)]
+pub struct DiffReport<'c> {
+    changes: &'c [Change],
+    colored: bool,
+}
+
+impl<'c> DiffReport<'c> {
+    pub fn new(changes: &'c [Change]) -> Self {
+        DiffReport {
+            changes,
+            colored: false,
+        }
+    }
+
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+
+    fn line(&self, f: &mut Formatter<'_>, color: &str, text: &str) -> std::fmt::Result {
+        if self.colored {
+            writeln!(f, "\x1b[{}m{}\x1b[0m", color, text)
+        } else {
+            writeln!(f, "{}", text)
+        }
+    }
+}
+
+impl Display for DiffReport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const CYAN: &str = "36";
+        const RED: &str = "31";
+        const GREEN: &str = "32";
+        let json = |value: &Value| value.to_json(JsonMode::Inline);
+
+        self.line(f, RED, "--- left")?;
+        self.line(f, GREEN, "+++ right")?;
+
+        for change in self.changes {
+            let path = match change.path() {
+                "" => "(root)",
+                path => path,
+            };
+            match change {
+                Change::TypeChanged { .. } => {
+                    self.line(f, CYAN, &format!("@@ {} (type changed) @@", path))?
+                }
+                _ => self.line(f, CYAN, &format!("@@ {} @@", path))?,
+            }
+
+            match change {
+                Change::Added { value, .. } => {
+                    self.line(f, GREEN, &format!("+ {}", json(value)))?
+                }
+                Change::Removed { value, .. } => {
+                    self.line(f, RED, &format!("- {}", json(value)))?
+                }
+                Change::Changed { from, to, .. } | Change::TypeChanged { from, to, .. } => {
+                    self.line(f, RED, &format!("- {}", json(from)))?;
+                    self.line(f, GREEN, &format!("+ {}", json(to)))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Asserts that two values are equal, like `assert_eq!`, but panics with a `DiffReport` of
+/// their differences instead of their `Debug` dumps.
+///
+/// Both sides are converted with `ToValueBehavior`, so a `Value` compares with anything
+/// convertible to one. An optional message follows, as with `assert_eq!`.
+///
+/// # Example
+/// ```no_run
+/// use valu3::prelude::*;
+///
+/// assert_value_eq!(value!({"a": [1, 2]}), value!({"a": [1, 2]}));
+/// assert_value_eq!(Value::from(1), 1, "values of {}", "numbers");
+/// ```
+#[macro_export]
+macro_rules! assert_value_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_value_eq!($left, $right, "")
+    };
+    ($left:expr, $right:expr, $($message:tt)+) => {{
+        use $crate::prelude::ToValueBehavior as _;
+        let left = ($left).to_value();
+        let right = ($right).to_value();
+        if left != right {
+            let changes = left.diff(&right);
+            let message = format!($($message)+);
+            panic!(
+                "assertion `left == right` failed{}{}\n{}",
+                if message.is_empty() { "" } else { ": " },
+                message,
+                $crate::prelude::DiffReport::new(&changes)
+            );
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_object_changes() {
+        let old = value!({"name": "Ada", "age": 36, "city": "London", "tags": {"a": 1}});
+        let new = value!({"name": "Grace", "age": "36", "country": "UK", "tags": {"a": 1}});
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                Change::TypeChanged {
+                    path: "/age".to_string(),
+                    from: Value::from(36),
+                    to: Value::from("36"),
+                },
+                Change::Removed {
+                    path: "/city".to_string(),
+                    value: Value::from("London"),
+                },
+                Change::Added {
+                    path: "/country".to_string(),
+                    value: Value::from("UK"),
+                },
+                Change::Changed {
+                    path: "/name".to_string(),
+                    from: Value::from("Ada"),
+                    to: Value::from("Grace"),
+                },
+            ]
+        );
+        assert!(old.diff(&old.clone()).is_empty());
+        assert!(value!(1).diff(&value!(1.0)).is_empty());
+    }
+
+    #[test]
+    fn test_array_alignment() {
+        let paths = |old: Value, new: Value| -> Vec<String> {
+            old.diff(&new)
+                .iter()
+                .map(|change| match change {
+                    Change::Added { path, .. } => format!("+{}", path),
+                    Change::Removed { path, .. } => format!("-{}", path),
+                    Change::Changed { path, .. } | Change::TypeChanged { path, .. } => {
+                        format!("~{}", path)
+                    }
+                })
+                .collect()
+        };
+
+        assert_eq!(paths(value!([1, 2, 3]), value!([0, 1, 2, 3])), vec!["+/0"]);
+        assert_eq!(
+            paths(value!([1, 2, 3, 4]), value!([1, 4])),
+            vec!["-/1", "-/2"]
+        );
+        assert_eq!(
+            paths(
+                value!(["a", {"b": 1}, "c"]),
+                value!(["a", {"b": 2}, "c", "d"])
+            ),
+            vec!["~/1/b", "+/3"]
+        );
+        assert_eq!(
+            paths(value!([1, 2, 3]), value!([3, 2, 1])),
+            vec!["-/0", "-/1", "+/1", "+/2"]
+        );
+    }
+
+    #[test]
+    fn test_large_arrays_are_compared_by_index() {
+        let old = Value::from((0..20_000).collect::<Vec<i32>>());
+        let new = Value::from((-1..19_999).collect::<Vec<i32>>());
+
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 20_000);
+        assert_eq!(
+            changes[0],
+            Change::Changed {
+                path: "/0".to_string(),
+                from: Value::from(0),
+                to: Value::from(-1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let changes = value!({"a": [1, 2], "b": true}).diff(&value!({"a": [1, 3], "c": null}));
+        let report = DiffReport::new(&changes).to_string();
+
+        assert_eq!(
+            report,
+            "--- left\n+++ right\n@@ /a/1 @@\n- 2\n+ 3\n@@ /b @@\n- true\n@@ /c @@\n+ null\n"
+        );
+
+        let colored = DiffReport::new(&changes).colored(true).to_string();
+        assert!(colored.contains("\x1b[31m- 2\x1b[0m"));
+        assert!(colored.contains("\x1b[32m+ 3\x1b[0m"));
+
+        let changes = value!(1).diff(&value!("1"));
+        assert!(DiffReport::new(&changes)
+            .to_string()
+            .contains("@@ (root) (type changed) @@"));
+    }
+
+    #[test]
+    fn test_assert_value_eq() {
+        assert_value_eq!(value!({"a": [1, 2]}), value!({"a": [1, 2]}));
+        assert_value_eq!(Value::from(1), 1, "numbers");
+
+        let panic = std::panic::catch_unwind(|| {
+            assert_value_eq!(value!({"a": 1}), value!({"a": 2}), "case {}", 1)
+        })
+        .unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("assertion `left == right` failed: case 1\n"));
+        assert!(message.contains("@@ /a @@\n- 1\n+ 2\n"));
+    }
+}
@@ -309,6 +309,14 @@ impl NumberBehavior for Value {
         }
     }
 
+    #[cfg(feature = "decimal")]
+    fn set_decimal(&mut self, value: BigDecimal) {
+        match self {
+            Value::Number(n) => n.set_decimal(value),
+            _ => panic!("Unable to set a value other than a number"),
+        }
+    }
+
     fn get_u8(&self) -> Option<u8> {
         match self {
             Value::Number(n) => n.get_u8(),
@@ -393,6 +401,14 @@ impl NumberBehavior for Value {
         }
     }
 
+    #[cfg(feature = "decimal")]
+    fn get_decimal(&self) -> Option<&BigDecimal> {
+        match self {
+            Value::Number(n) => n.get_decimal(),
+            _ => panic!("Unable to get a value other than a number"),
+        }
+    }
+
     fn get_u8_unsafe(&self) -> u8 {
         match self {
             Value::Number(n) => n.get_u8_unsafe(),
@@ -477,6 +493,14 @@ impl NumberBehavior for Value {
         }
     }
 
+    #[cfg(feature = "decimal")]
+    fn get_decimal_unsafe(&self) -> &BigDecimal {
+        match self {
+            Value::Number(n) => n.get_decimal_unsafe(),
+            _ => panic!("Unable to get a value other than a number"),
+        }
+    }
+
     fn is_i8(&self) -> bool {
         match self {
             Value::Number(n) => n.is_i8(),
@@ -561,6 +585,14 @@ impl NumberBehavior for Value {
         }
     }
 
+    #[cfg(feature = "decimal")]
+    fn is_decimal(&self) -> bool {
+        match self {
+            Value::Number(n) => n.is_decimal(),
+            _ => false,
+        }
+    }
+
     fn is_number(&self) -> bool {
         match self {
             Value::Number(_) => true,
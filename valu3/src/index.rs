@@ -0,0 +1,254 @@
+//! `Index` and `IndexMut` for `Value`, `Array` and `Object`.
+//!
+//! Reading a missing key or index, or indexing a value that is not an array or an object,
+//! returns a shared `Value::Undefined` instead of panicking, so paths can be chained:
+//!
+//! ```no_run
+//! let value = Value::payload_to_value(r#"{"users": [{"name": "Ada"}]}"#).unwrap();
+//! assert_eq!(value["users"][0]["name"], Value::from("Ada"));
+//! assert_eq!(value["users"][3]["name"], Value::Undefined);
+//! ```
+//!
+//! Indexing mutably creates what is missing: `Null` and `Undefined` become an empty object
+//! for a string key or an empty array for a number, missing keys are inserted as `Null`,
+//! and arrays are padded with `Null` up to the index.
+//!
+//! ```no_run
+//! let mut value = Value::Null;
+//! value["users"][1]["name"] = Value::from("Ada");
+//! assert_eq!(value["users"][0], Value::Null);
+//! ```
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+static UNDEFINED: Value = Value::Undefined;
+
+/// A type that can index into a `Value`, implemented for `usize`, `&str`, `String` and
+/// `ValueKey`.
+pub trait ValueIndex {
+    /// Returns the indexed value, or `None` when it is missing.
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value>;
+
+    /// Returns the indexed value, creating it and its container when a key is missing.
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value;
+}
+
+impl ValueIndex for usize {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        match value {
+            Value::Array(array) => array.get(*self),
+            Value::Object(object) => object.get_by_key(&ValueKey::Number(*self)),
+            _ => None,
+        }
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        if let Value::Null | Value::Undefined = value {
+            *value = Value::Array(Array::new());
+        }
+
+        match value {
+            Value::Array(array) => array.get_or_insert(*self),
+            Value::Object(object) => &mut object[ValueKey::Number(*self)],
+            _ => panic!("Unable to index a type other than an array or object with a number"),
+        }
+    }
+}
+
+impl ValueIndex for str {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        match value {
+            Value::Object(object) => object.get(self),
+            _ => None,
+        }
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        if let Value::Null | Value::Undefined = value {
+            *value = Value::Object(Object::from(HashMap::<String, Value>::new()));
+        }
+
+        match value {
+            Value::Object(object) => &mut object[self],
+            _ => panic!("Unable to index a type other than an object with a string"),
+        }
+    }
+}
+
+impl ValueIndex for String {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        self.as_str().index_or_insert(value)
+    }
+}
+
+impl ValueIndex for ValueKey {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        match self {
+            ValueKey::String(key) => key.as_str().index_into(value),
+            ValueKey::Number(index) => index.index_into(value),
+        }
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        match self {
+            ValueKey::String(key) => key.as_str().index_or_insert(value),
+            ValueKey::Number(index) => index.index_or_insert(value),
+        }
+    }
+}
+
+impl<T: ValueIndex + ?Sized> ValueIndex for &T {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(value)
+    }
+
+    fn index_or_insert<'v>(&self, value: &'v mut Value) -> &'v mut Value {
+        (**self).index_or_insert(value)
+    }
+}
+
+impl<I: ValueIndex> Index<I> for Value {
+    type Output = Value;
+
+    fn index(&self, index: I) -> &Value {
+        index.index_into(self).unwrap_or(&UNDEFINED)
+    }
+}
+
+impl<I: ValueIndex> IndexMut<I> for Value {
+    fn index_mut(&mut self, index: I) -> &mut Value {
+        index.index_or_insert(self)
+    }
+}
+
+impl Index<usize> for Array {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        self.get(index).unwrap_or(&UNDEFINED)
+    }
+}
+
+impl IndexMut<usize> for Array {
+    fn index_mut(&mut self, index: usize) -> &mut Value {
+        self.get_or_insert(index)
+    }
+}
+
+impl Index<&str> for Object {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).unwrap_or(&UNDEFINED)
+    }
+}
+
+impl IndexMut<&str> for Object {
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        self.entry_or_null(key.to_value_key())
+    }
+}
+
+impl Index<ValueKey> for Object {
+    type Output = Value;
+
+    fn index(&self, key: ValueKey) -> &Value {
+        self.get_by_key(&key).unwrap_or(&UNDEFINED)
+    }
+}
+
+impl IndexMut<ValueKey> for Object {
+    fn index_mut(&mut self, key: ValueKey) -> &mut Value {
+        self.entry_or_null(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn users() -> Value {
+        Value::payload_to_value(r#"{"users": [{"name": "Ada", "tags": ["admin"]}]}"#).unwrap()
+    }
+
+    #[test]
+    fn test_index_paths() {
+        let value = users();
+
+        assert_eq!(value["users"][0]["name"], Value::from("Ada"));
+        assert_eq!(value["users"][0]["tags"][0], Value::from("admin"));
+        assert_eq!(value[String::from("users")].len(), 1);
+        assert_eq!(
+            value[ValueKey::from("users")][ValueKey::Number(0)]["name"],
+            Value::from("Ada")
+        );
+    }
+
+    #[test]
+    fn test_missing_paths_are_undefined() {
+        let value = users();
+
+        assert_eq!(value["missing"], Value::Undefined);
+        assert_eq!(value["users"][5]["name"], Value::Undefined);
+        assert_eq!(value["users"]["name"], Value::Undefined);
+        assert_eq!(value["users"][0]["name"][0], Value::Undefined);
+        assert_eq!(Value::from(1)[0], Value::Undefined);
+    }
+
+    #[test]
+    fn test_index_mut_auto_vivifies() {
+        let mut value = users();
+        value["users"][0]["name"] = Value::from("Grace");
+        value["users"][0]["role"]["name"] = Value::from("admin");
+        value["settings"]["theme"] = Value::from("dark");
+
+        assert_eq!(value["users"][0]["name"], Value::from("Grace"));
+        assert_eq!(value["users"][0]["role"]["name"], Value::from("admin"));
+        assert_eq!(value["settings"]["theme"], Value::from("dark"));
+    }
+
+    #[test]
+    fn test_index_mut_grows_arrays() {
+        let mut value = users();
+        value["users"][2]["name"] = Value::from("Alan");
+        assert_eq!(value["users"][1], Value::Null);
+        assert_eq!(value["users"][2]["name"], Value::from("Alan"));
+
+        let mut value = Value::payload_to_value(r#"{"a": []}"#).unwrap();
+        value["a"][0] = Value::from(1);
+        assert_eq!(value["a"], Value::from(vec![1]));
+
+        let mut value = Value::Null;
+        value[1] = Value::from(true);
+        assert_eq!(value, Value::from(vec![Value::Null, Value::from(true)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to index a type other than an object with a string")]
+    fn test_index_mut_on_scalar_panics() {
+        let mut value = Value::from(1);
+        value["key"] = Value::Null;
+    }
+
+    #[test]
+    fn test_array_and_object_index() {
+        let mut array = Array::from(vec![1, 2]);
+        assert_eq!(array[1], Value::from(2));
+        assert_eq!(array[9], Value::Undefined);
+        array[1] = Value::from(3);
+        assert_eq!(array[1], Value::from(3));
+        array[3] = Value::from(4);
+        assert_eq!(array.len(), 4);
+        assert_eq!(array[2], Value::Null);
+
+        let mut object = Object::default();
+        object["a"] = Value::from(1);
+        assert_eq!(object["a"], Value::from(1));
+        assert_eq!(object[ValueKey::from("b")], Value::Undefined);
+    }
+}
@@ -0,0 +1,1179 @@
+//! JSONPath, RFC 9535, to select nodes of a `Value` with queries such as
+//! `$.store.book[?@.price < 10].title`.
+//!
+//! Supported are the child (`.name`, `.*`, `[...]`) and descendant (`..name`, `..*`,
+//! `..[...]`) segments, and the name, wildcard, index, slice and filter selectors, several of
+//! them separated by commas. Filters combine comparisons (`==`, `!=`, `<`, `<=`, `>`, `>=`),
+//! existence tests, `&&`, `||`, `!` and parentheses, and call the functions `length`,
+//! `count`, `match`, `search` and `value`.
+//!
+//! Members of objects are visited in key order, so results are the same for a `HashMap` and
+//! a `BTreeMap`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let value = Value::payload_to_value(
+//!     r#"{"store": {"book": [{"title": "Sayings", "price": 8.95}, {"title": "Sword", "price": 12.99}]}}"#,
+//! )
+//! .unwrap();
+//!
+//! let matches = value.query("$.store.book[?(@.price < 10)].title").unwrap();
+//! assert_eq!(matches[0].path, "$['store']['book'][0]['title']");
+//! assert_eq!(matches[0].value, &Value::from("Sayings"));
+//! ```
+use crate::prelude::*;
+use regex::Regex;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter, Write};
+
+/// A JSONPath query that could not be parsed, with the character position of the problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPathError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for JsonPathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for JsonPathError {}
+
+/// A node selected by a query, with its normalized path such as `$['users'][0]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryMatch<'v> {
+    pub path: String,
+    pub value: &'v Value,
+}
+
+/// A parsed JSONPath query, to run several times without parsing again.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    query: Query,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<JsonPath, JsonPathError> {
+        let mut parser = Parser::new(path);
+
+        if !parser.eat('$') {
+            return Err(parser.error("A query must start with `$`"));
+        }
+        let segments = parser.segments()?;
+
+        if parser.peek().is_some() {
+            return Err(parser.error("Unexpected character"));
+        }
+
+        Ok(JsonPath {
+            query: Query {
+                relative: false,
+                segments,
+            },
+        })
+    }
+
+    /// Selects the nodes of `value` matching the query, in the order of RFC 9535.
+    pub fn query<'v>(&self, value: &'v Value) -> Vec<QueryMatch<'v>> {
+        self.query
+            .select(value, value)
+            .into_iter()
+            .map(|node| QueryMatch {
+                path: node.normalized_path(),
+                value: node.value,
+            })
+            .collect()
+    }
+}
+
+impl Value {
+    /// Selects the nodes matching a JSONPath query, see `JsonPath`.
+    pub fn query(&self, path: &str) -> Result<Vec<QueryMatch<'_>>, JsonPathError> {
+        Ok(JsonPath::parse(path)?.query(self))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Query {
+    /// Starts at the current node `@` of a filter instead of the root `$`.
+    relative: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    descendant: bool,
+    selectors: Vec<Selector>,
+}
+
+#[derive(Debug, Clone)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Box<Expression>),
+}
+
+#[derive(Debug, Clone)]
+enum Expression {
+    Or(Vec<Expression>),
+    And(Vec<Expression>),
+    Not(Box<Expression>),
+    Exists(Query),
+    Compare(Operand, Comparison, Operand),
+    Function(Function),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+/// A value of a comparison or a function argument.
+#[derive(Debug, Clone)]
+enum Operand {
+    Literal(Value),
+    Query(Query),
+    Function(Function),
+}
+
+#[derive(Debug, Clone)]
+enum Function {
+    Length(Box<Operand>),
+    Count(Query),
+    Value(Query),
+    /// Whether the whole string matches, or a part of it for `search`.
+    Match {
+        whole: bool,
+        subject: Box<Operand>,
+        pattern: Box<Operand>,
+        /// The pattern compiled once when it is a literal.
+        compiled: Option<Regex>,
+    },
+}
+
+impl Function {
+    fn returns_logical(&self) -> bool {
+        matches!(self, Function::Match { .. })
+    }
+}
+
+impl Query {
+    /// Whether the query selects at most one node, made of names and indexes only.
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            !segment.descendant
+                && segment.selectors.len() == 1
+                && matches!(segment.selectors[0], Selector::Name(_) | Selector::Index(_))
+        })
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    position: usize,
+    text: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Parser {
+            chars: text.chars().collect(),
+            position: 0,
+            text,
+        }
+    }
+
+    fn error(&self, message: &str) -> JsonPathError {
+        JsonPathError {
+            position: self.position,
+            message: format!("{} in {:?}", message, self.text),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, text: &str) -> bool {
+        let matches = text
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| self.peek_at(offset) == Some(c));
+        if matches {
+            self.position += text.chars().count();
+        }
+        matches
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), JsonPathError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected `{}`", c)))
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn segments(&mut self) -> Result<Vec<Segment>, JsonPathError> {
+        let mut segments = Vec::new();
+
+        loop {
+            // Blank space may separate segments, but belongs to the filter otherwise.
+            let start = self.position;
+            self.skip_blank();
+
+            let segment = match self.peek() {
+                Some('[') => Segment {
+                    descendant: false,
+                    selectors: self.bracketed()?,
+                },
+                Some('.') if self.peek_at(1) == Some('.') => {
+                    self.position += 2;
+                    let selectors = match self.peek() {
+                        Some('[') => self.bracketed()?,
+                        _ => vec![self.shorthand()?],
+                    };
+                    Segment {
+                        descendant: true,
+                        selectors,
+                    }
+                }
+                Some('.') => {
+                    self.position += 1;
+                    Segment {
+                        descendant: false,
+                        selectors: vec![self.shorthand()?],
+                    }
+                }
+                _ => {
+                    self.position = start;
+                    return Ok(segments);
+                }
+            };
+
+            segments.push(segment);
+        }
+    }
+
+    /// Parses `*` or a member name after `.` or `..`.
+    fn shorthand(&mut self) -> Result<Selector, JsonPathError> {
+        if self.eat('*') {
+            return Ok(Selector::Wildcard);
+        }
+
+        let is_first = |c: char| c.is_ascii_alphabetic() || c == '_' || c as u32 >= 0x80;
+        match self.peek() {
+            Some(c) if is_first(c) => {
+                let start = self.position;
+                while matches!(self.peek(), Some(c) if is_first(c) || c.is_ascii_digit()) {
+                    self.position += 1;
+                }
+                Ok(Selector::Name(
+                    self.chars[start..self.position].iter().collect(),
+                ))
+            }
+            _ => Err(self.error("Expected a member name or `*`")),
+        }
+    }
+
+    fn bracketed(&mut self) -> Result<Vec<Selector>, JsonPathError> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+
+        loop {
+            self.skip_blank();
+            selectors.push(self.selector()?);
+            self.skip_blank();
+
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, JsonPathError> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.position += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.position += 1;
+                self.skip_blank();
+                Ok(Selector::Filter(Box::new(self.or()?)))
+            }
+            _ => {
+                let start = self.integer()?;
+                self.skip_blank();
+                if !self.eat(':') {
+                    return match start {
+                        Some(index) => Ok(Selector::Index(index)),
+                        None => Err(self.error("Expected a selector")),
+                    };
+                }
+
+                self.skip_blank();
+                let end = self.integer()?;
+                self.skip_blank();
+                let step = if self.eat(':') {
+                    self.skip_blank();
+                    self.integer()?
+                } else {
+                    None
+                };
+
+                Ok(Selector::Slice { start, end, step })
+            }
+        }
+    }
+
+    /// Parses an integer without leading zeros within the exact range of `f64`.
+    fn integer(&mut self) -> Result<Option<i64>, JsonPathError> {
+        let start = self.position;
+        self.eat('-');
+        let digits = self.position;
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.position += 1;
+        }
+
+        let text: String = self.chars[start..self.position].iter().collect();
+        if self.position == digits {
+            self.position = start;
+            return Ok(None);
+        }
+        if text == "-0" || (self.position - digits > 1 && self.chars[digits] == '0') {
+            return Err(self.error("Invalid integer"));
+        }
+
+        const LIMIT: i64 = (1 << 53) - 1;
+        match text.parse::<i64>() {
+            Ok(value) if (-LIMIT..=LIMIT).contains(&value) => Ok(Some(value)),
+            _ => Err(self.error("Integer out of range")),
+        }
+    }
+
+    /// Parses a single or double quoted string with JSON escapes.
+    fn string(&mut self) -> Result<String, JsonPathError> {
+        let quote = self.peek().unwrap_or('\'');
+        self.position += 1;
+        let mut text = String::new();
+
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error("Unterminated string"))?;
+            self.position += 1;
+
+            match c {
+                c if c == quote => return Ok(text),
+                '\\' => {
+                    let escaped = self
+                        .peek()
+                        .ok_or_else(|| self.error("Unterminated string"))?;
+                    self.position += 1;
+                    match escaped {
+                        'b' => text.push('\u{8}'),
+                        'f' => text.push('\u{c}'),
+                        'n' => text.push('\n'),
+                        'r' => text.push('\r'),
+                        't' => text.push('\t'),
+                        '/' | '\\' => text.push(escaped),
+                        c if c == quote => text.push(c),
+                        'u' => text.push(self.unicode_escape()?),
+                        _ => return Err(self.error("Invalid escape")),
+                    }
+                }
+                c if (c as u32) < 0x20 => return Err(self.error("Control character in string")),
+                c => text.push(c),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, JsonPathError> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("Invalid escape"));
+        }
+
+        if !self.eat_str("\\u") {
+            return Err(self.error("Expected a low surrogate"));
+        }
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("Expected a low surrogate"));
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| self.error("Invalid escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonPathError> {
+        let digits: String = self.chars.iter().skip(self.position).take(4).collect();
+        match u32::from_str_radix(&digits, 16) {
+            Ok(value) if digits.len() == 4 => {
+                self.position += 4;
+                Ok(value)
+            }
+            _ => Err(self.error("Expected four hexadecimal digits")),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expression, JsonPathError> {
+        let mut operands = vec![self.and()?];
+        loop {
+            let start = self.position;
+            self.skip_blank();
+            if !self.eat_str("||") {
+                self.position = start;
+                break;
+            }
+            self.skip_blank();
+            operands.push(self.and()?);
+        }
+
+        Ok(match operands.len() {
+            1 => operands.remove(0),
+            _ => Expression::Or(operands),
+        })
+    }
+
+    fn and(&mut self) -> Result<Expression, JsonPathError> {
+        let mut operands = vec![self.basic()?];
+        loop {
+            let start = self.position;
+            self.skip_blank();
+            if !self.eat_str("&&") {
+                self.position = start;
+                break;
+            }
+            self.skip_blank();
+            operands.push(self.basic()?);
+        }
+
+        Ok(match operands.len() {
+            1 => operands.remove(0),
+            _ => Expression::And(operands),
+        })
+    }
+
+    fn basic(&mut self) -> Result<Expression, JsonPathError> {
+        if self.peek() == Some('!') && self.peek_at(1) != Some('=') {
+            self.position += 1;
+            self.skip_blank();
+            let negated = match self.peek() {
+                Some('(') => self.parenthesized()?,
+                _ => self.test()?,
+            };
+            return Ok(Expression::Not(Box::new(negated)));
+        }
+
+        if self.peek() == Some('(') {
+            return self.parenthesized();
+        }
+
+        let start = self.position;
+        let left = self.operand()?;
+
+        let after_operand = self.position;
+        self.skip_blank();
+        let Some(comparison) = self.comparison() else {
+            self.position = after_operand;
+            return match left {
+                Operand::Query(query) => Ok(Expression::Exists(query)),
+                Operand::Function(function) if function.returns_logical() => {
+                    Ok(Expression::Function(function))
+                }
+                _ => {
+                    self.position = start;
+                    Err(self.error("Expected a comparison or a test"))
+                }
+            };
+        };
+
+        self.skip_blank();
+        let right = self.operand()?;
+        for operand in [&left, &right] {
+            self.check_comparable(operand, start)?;
+        }
+
+        Ok(Expression::Compare(left, comparison, right))
+    }
+
+    /// Parses a test, a query or a function returning a logical value, after `!`.
+    fn test(&mut self) -> Result<Expression, JsonPathError> {
+        match self.operand()? {
+            Operand::Query(query) => Ok(Expression::Exists(query)),
+            Operand::Function(function) if function.returns_logical() => {
+                Ok(Expression::Function(function))
+            }
+            _ => Err(self.error("Expected a query or a test function after `!`")),
+        }
+    }
+
+    fn parenthesized(&mut self) -> Result<Expression, JsonPathError> {
+        self.expect('(')?;
+        self.skip_blank();
+        let expression = self.or()?;
+        self.skip_blank();
+        self.expect(')')?;
+        Ok(expression)
+    }
+
+    fn comparison(&mut self) -> Option<Comparison> {
+        let comparisons = [
+            ("==", Comparison::Equal),
+            ("!=", Comparison::NotEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+        ];
+
+        comparisons
+            .into_iter()
+            .find(|(text, _)| self.eat_str(text))
+            .map(|(_, comparison)| comparison)
+    }
+
+    /// Checks that an operand is a single value: a literal, a singular query, or a function
+    /// returning a value.
+    fn check_comparable(&self, operand: &Operand, start: usize) -> Result<(), JsonPathError> {
+        let comparable = match operand {
+            Operand::Literal(_) => true,
+            Operand::Query(query) => query.is_singular(),
+            Operand::Function(function) => !function.returns_logical(),
+        };
+
+        if comparable {
+            Ok(())
+        } else {
+            Err(JsonPathError {
+                position: start,
+                ..self.error("Only literals, singular queries and value functions compare")
+            })
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, JsonPathError> {
+        match self.peek() {
+            Some('@') | Some('$') => {
+                let relative = self.peek() == Some('@');
+                self.position += 1;
+                Ok(Operand::Query(Query {
+                    relative,
+                    segments: self.segments()?,
+                }))
+            }
+            Some('\'' | '"') => Ok(Operand::Literal(Value::from(self.string()?))),
+            Some('-' | '0'..='9') => Ok(Operand::Literal(self.number()?)),
+            Some('a'..='z') => {
+                let start = self.position;
+                while matches!(self.peek(), Some('a'..='z' | '0'..='9' | '_')) {
+                    self.position += 1;
+                }
+                let name: String = self.chars[start..self.position].iter().collect();
+
+                match name.as_str() {
+                    _ if self.peek() == Some('(') => self.function(&name, start),
+                    "true" => Ok(Operand::Literal(Value::Boolean(true))),
+                    "false" => Ok(Operand::Literal(Value::Boolean(false))),
+                    "null" => Ok(Operand::Literal(Value::Null)),
+                    _ => {
+                        self.position = start;
+                        Err(self.error("Unknown literal"))
+                    }
+                }
+            }
+            _ => Err(self.error("Expected a literal, a query or a function")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, JsonPathError> {
+        let start = self.position;
+        self.eat('-');
+        // Signs only follow the exponent marker.
+        while let Some(c) = self.peek() {
+            let after_exponent = matches!(self.chars[self.position - 1], 'e' | 'E');
+            if !(c.is_ascii_digit()
+                || matches!(c, '.' | 'e' | 'E')
+                || (matches!(c, '+' | '-') && after_exponent))
+            {
+                break;
+            }
+            self.position += 1;
+        }
+
+        let text: String = self.chars[start..self.position].iter().collect();
+        let digits = text.trim_start_matches('-');
+        let leading_zero =
+            digits.starts_with('0') && digits.as_bytes().get(1).is_some_and(u8::is_ascii_digit);
+        let valid = digits.starts_with(|c: char| c.is_ascii_digit())
+            && !leading_zero
+            && !text.ends_with(['.', 'e', 'E', '+', '-']);
+
+        let number = if !valid {
+            None
+        } else if text.contains(['.', 'e', 'E']) {
+            text.parse::<f64>().ok().map(Number::from)
+        } else {
+            text.parse::<i64>().ok().map(Number::from)
+        };
+
+        number.map(Value::Number).ok_or_else(|| {
+            self.position = start;
+            self.error("Invalid number")
+        })
+    }
+
+    fn function(&mut self, name: &str, start: usize) -> Result<Operand, JsonPathError> {
+        self.expect('(')?;
+        let mut arguments = Vec::new();
+        self.skip_blank();
+        if !self.eat(')') {
+            loop {
+                self.skip_blank();
+                arguments.push(self.operand()?);
+                self.skip_blank();
+                if self.eat(')') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+
+        let error = |parser: &Self, message: &str| JsonPathError {
+            position: start,
+            ..parser.error(message)
+        };
+        let arity = match name {
+            "length" | "count" | "value" => 1,
+            "match" | "search" => 2,
+            _ => return Err(error(self, "Unknown function")),
+        };
+        if arguments.len() != arity {
+            return Err(error(self, "Wrong number of arguments"));
+        }
+
+        let mut arguments = arguments.into_iter();
+        let mut next = || arguments.next().expect("Unable to read a counted argument");
+        let function = match name {
+            "length" => Function::Length(Box::new(self.value_argument(next(), start)?)),
+            "count" | "value" => {
+                let Operand::Query(query) = next() else {
+                    return Err(error(self, "Expected a query argument"));
+                };
+                if name == "count" {
+                    Function::Count(query)
+                } else {
+                    Function::Value(query)
+                }
+            }
+            _ => {
+                let subject = self.value_argument(next(), start)?;
+                let pattern = self.value_argument(next(), start)?;
+                let compiled = match &pattern {
+                    Operand::Literal(Value::String(pattern)) => {
+                        compile(pattern.as_str(), name == "match")
+                    }
+                    _ => None,
+                };
+                Function::Match {
+                    whole: name == "match",
+                    subject: Box::new(subject),
+                    pattern: Box::new(pattern),
+                    compiled,
+                }
+            }
+        };
+
+        Ok(Operand::Function(function))
+    }
+
+    fn value_argument(&self, operand: Operand, start: usize) -> Result<Operand, JsonPathError> {
+        self.check_comparable(&operand, start)?;
+        Ok(operand)
+    }
+}
+
+/// Compiles an I-Regexp, anchored at both ends for `match`.
+fn compile(pattern: &str, whole: bool) -> Option<Regex> {
+    if whole {
+        Regex::new(&format!("^(?:{})$", pattern)).ok()
+    } else {
+        Regex::new(pattern).ok()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Step<'v> {
+    Key(&'v ValueKey),
+    Index(usize),
+}
+
+#[derive(Clone)]
+struct Node<'v> {
+    path: Vec<Step<'v>>,
+    value: &'v Value,
+}
+
+impl<'v> Node<'v> {
+    fn child(&self, step: Step<'v>, value: &'v Value) -> Node<'v> {
+        let mut path = self.path.clone();
+        path.push(step);
+        Node { path, value }
+    }
+
+    /// Children in document order, object members sorted by key.
+    fn children(&self) -> Vec<Node<'v>> {
+        match self.value {
+            Value::Array(array) => array
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| self.child(Step::Index(index), value))
+                .collect(),
+            Value::Object(object) => {
+                let mut entries: Vec<_> = object.iter().collect();
+                entries.sort_by(|left, right| left.0.cmp(right.0));
+                entries
+                    .into_iter()
+                    .map(|(key, value)| self.child(Step::Key(key), value))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn normalized_path(&self) -> String {
+        let mut path = String::from("$");
+        for step in &self.path {
+            match step {
+                Step::Index(index) => write!(path, "[{}]", index).unwrap(),
+                Step::Key(key) => {
+                    path.push_str("['");
+                    for c in key.to_string().chars() {
+                        match c {
+                            '\u{8}' => path.push_str("\\b"),
+                            '\u{c}' => path.push_str("\\f"),
+                            '\n' => path.push_str("\\n"),
+                            '\r' => path.push_str("\\r"),
+                            '\t' => path.push_str("\\t"),
+                            '\'' => path.push_str("\\'"),
+                            '\\' => path.push_str("\\\\"),
+                            c if (c as u32) < 0x20 => write!(path, "\\u{:04x}", c as u32).unwrap(),
+                            c => path.push(c),
+                        }
+                    }
+                    path.push_str("']");
+                }
+            }
+        }
+        path
+    }
+}
+
+impl Query {
+    fn select<'v>(&self, root: &'v Value, current: &'v Value) -> Vec<Node<'v>> {
+        let start = if self.relative { current } else { root };
+        let mut nodes = vec![Node {
+            path: Vec::new(),
+            value: start,
+        }];
+
+        for segment in &self.segments {
+            let mut selected = Vec::new();
+            for node in &nodes {
+                if segment.descendant {
+                    let mut pending = vec![node.clone()];
+                    // Visits each node before its descendants, in document order.
+                    while let Some(visited) = pending.pop() {
+                        for selector in &segment.selectors {
+                            selector.select(root, &visited, &mut selected);
+                        }
+                        pending.extend(visited.children().into_iter().rev());
+                    }
+                } else {
+                    for selector in &segment.selectors {
+                        selector.select(root, node, &mut selected);
+                    }
+                }
+            }
+            nodes = selected;
+        }
+
+        nodes
+    }
+}
+
+impl Selector {
+    fn select<'v>(&self, root: &'v Value, node: &Node<'v>, selected: &mut Vec<Node<'v>>) {
+        match (self, node.value) {
+            (Selector::Name(name), Value::Object(object)) => {
+                let key = ValueKey::from(name.as_str());
+                if let Some((key, value)) = object.iter().find(|(candidate, _)| **candidate == key)
+                {
+                    selected.push(node.child(Step::Key(key), value));
+                }
+            }
+            (Selector::Wildcard, _) => selected.extend(node.children()),
+            (Selector::Index(index), Value::Array(array)) => {
+                let len = array.len() as i64;
+                let index = if *index < 0 { len + index } else { *index };
+                if (0..len).contains(&index) {
+                    let index = index as usize;
+                    selected.push(node.child(Step::Index(index), &array.values[index]));
+                }
+            }
+            (Selector::Slice { start, end, step }, Value::Array(array)) => {
+                for index in slice(array.len() as i64, *start, *end, step.unwrap_or(1)) {
+                    let index = index as usize;
+                    selected.push(node.child(Step::Index(index), &array.values[index]));
+                }
+            }
+            (Selector::Filter(expression), Value::Array(_) | Value::Object(_)) => {
+                selected.extend(
+                    node.children()
+                        .into_iter()
+                        .filter(|child| expression.test(root, child.value)),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The indexes selected by a slice, following section 2.3.4.2.2 of RFC 9535.
+fn slice(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<i64> {
+    let normalize = |index: i64| if index >= 0 { index } else { len + index };
+    let mut indexes = Vec::new();
+
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+        let mut index = lower;
+        while index < upper {
+            indexes.push(index);
+            index += step;
+        }
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = normalize(end.unwrap_or(-len - 1)).clamp(-1, len - 1);
+        let mut index = upper;
+        while lower < index {
+            indexes.push(index);
+            index += step;
+        }
+    }
+
+    indexes
+}
+
+impl Expression {
+    fn test(&self, root: &Value, current: &Value) -> bool {
+        match self {
+            Expression::Or(operands) => operands.iter().any(|operand| operand.test(root, current)),
+            Expression::And(operands) => operands.iter().all(|operand| operand.test(root, current)),
+            Expression::Not(operand) => !operand.test(root, current),
+            Expression::Exists(query) => !query.select(root, current).is_empty(),
+            Expression::Function(function) => function
+                .evaluate(root, current)
+                .is_some_and(|value| value == Cow::Owned(Value::Boolean(true))),
+            Expression::Compare(left, comparison, right) => {
+                let left = left.evaluate(root, current);
+                let right = right.evaluate(root, current);
+                let (left, right) = (left.as_deref(), right.as_deref());
+
+                match comparison {
+                    Comparison::Equal => equal(left, right),
+                    Comparison::NotEqual => !equal(left, right),
+                    Comparison::Less => less(left, right),
+                    Comparison::LessOrEqual => less(left, right) || equal(left, right),
+                    Comparison::Greater => less(right, left),
+                    Comparison::GreaterOrEqual => less(right, left) || equal(left, right),
+                }
+            }
+        }
+    }
+}
+
+/// Compares two values, `None` standing for an empty result, equal only to itself.
+fn equal(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (Some(left), Some(right)) => left == right,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Orders numbers with numbers and strings with strings, anything else is never less.
+fn less(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (Some(Value::Number(left)), Some(Value::Number(right))) => left < right,
+        (Some(Value::String(left)), Some(Value::String(right))) => left < right,
+        _ => false,
+    }
+}
+
+impl Operand {
+    fn evaluate<'v>(&'v self, root: &'v Value, current: &'v Value) -> Option<Cow<'v, Value>> {
+        match self {
+            Operand::Literal(value) => Some(Cow::Borrowed(value)),
+            Operand::Query(query) => {
+                let nodes = query.select(root, current);
+                match nodes.as_slice() {
+                    [node] => Some(Cow::Borrowed(node.value)),
+                    _ => None,
+                }
+            }
+            Operand::Function(function) => function.evaluate(root, current),
+        }
+    }
+}
+
+impl Function {
+    fn evaluate<'v>(&'v self, root: &'v Value, current: &'v Value) -> Option<Cow<'v, Value>> {
+        let owned = |value: Value| Some(Cow::Owned(value));
+
+        match self {
+            Function::Length(argument) => match argument.evaluate(root, current)?.as_ref() {
+                Value::String(string) => owned(Value::from(string.as_str().chars().count())),
+                Value::Array(array) => owned(Value::from(array.len())),
+                Value::Object(object) => owned(Value::from(object.len())),
+                _ => None,
+            },
+            Function::Count(query) => owned(Value::from(query.select(root, current).len())),
+            Function::Value(query) => match query.select(root, current).as_slice() {
+                [node] => Some(Cow::Borrowed(node.value)),
+                _ => None,
+            },
+            Function::Match {
+                whole,
+                subject,
+                pattern,
+                compiled,
+            } => {
+                let subject = subject.evaluate(root, current);
+                let matched = match (subject.as_deref(), compiled) {
+                    (Some(Value::String(subject)), Some(regex)) => regex.is_match(subject.as_str()),
+                    (Some(Value::String(subject)), None) => {
+                        match pattern.evaluate(root, current).as_deref() {
+                            Some(Value::String(pattern)) => compile(pattern.as_str(), *whole)
+                                .is_some_and(|regex| regex.is_match(subject.as_str())),
+                            _ => false,
+                        }
+                    }
+                    _ => false,
+                };
+                owned(Value::Boolean(matched))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn store() -> Value {
+        Value::payload_to_value(
+            r#"{
+                "store": {
+                    "book": [
+                        {"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8.95},
+                        {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12.99},
+                        {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+                        {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99}
+                    ],
+                    "bicycle": {"color": "red", "price": 399}
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn paths(value: &Value, query: &str) -> Vec<String> {
+        value
+            .query(query)
+            .unwrap()
+            .into_iter()
+            .map(|found| found.path)
+            .collect()
+    }
+
+    fn values(value: &Value, query: &str) -> Vec<Value> {
+        value
+            .query(query)
+            .unwrap()
+            .into_iter()
+            .map(|found| found.value.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_child_and_descendant_segments() {
+        let value = store();
+
+        assert_eq!(values(&value, "$.store.book[*].author").len(), 4);
+        assert_eq!(
+            values(&value, "$..author")[3],
+            Value::from("J. R. R. Tolkien")
+        );
+        assert_eq!(
+            paths(&value, "$.store.*"),
+            vec!["$['store']['bicycle']", "$['store']['book']"]
+        );
+        assert_eq!(values(&value, "$.store..price").len(), 5);
+        assert_eq!(
+            paths(&value, "$['store'][\"bicycle\"].color"),
+            vec!["$['store']['bicycle']['color']"]
+        );
+        assert_eq!(values(&value, "$..*").len(), 27);
+    }
+
+    #[test]
+    fn test_index_slice_and_union() {
+        let value = Value::payload_to_value(r#"["a", "b", "c", "d", "e", "f", "g"]"#).unwrap();
+        let letters = |query: &str| -> String {
+            values(&value, query)
+                .iter()
+                .map(|value| value.as_string())
+                .collect()
+        };
+
+        assert_eq!(letters("$[1]"), "b");
+        assert_eq!(letters("$[-2]"), "f");
+        assert_eq!(letters("$[7]"), "");
+        assert_eq!(letters("$[1:3]"), "bc");
+        assert_eq!(letters("$[5:]"), "fg");
+        assert_eq!(letters("$[1:5:2]"), "bd");
+        assert_eq!(letters("$[5:1:-2]"), "fd");
+        assert_eq!(letters("$[::-1]"), "gfedcba");
+        assert_eq!(letters("$[::0]"), "");
+        assert_eq!(letters("$[0, 3, 0:2]"), "adab");
+        assert_eq!(paths(&value, "$[-1]"), vec!["$[6]"]);
+    }
+
+    #[test]
+    fn test_filters() {
+        let value = store();
+
+        assert_eq!(
+            values(&value, "$.store.book[?(@.price < 10)].title"),
+            vec![
+                Value::from("Sayings of the Century"),
+                Value::from("Moby Dick")
+            ]
+        );
+        assert_eq!(values(&value, "$..book[?@.isbn]").len(), 2);
+        assert_eq!(values(&value, "$..book[?!@.isbn]").len(), 2);
+        assert_eq!(
+            values(
+                &value,
+                "$..book[?@.category == 'fiction' && @.price >= 12.99].title"
+            ),
+            vec![
+                Value::from("Sword of Honour"),
+                Value::from("The Lord of the Rings")
+            ]
+        );
+        assert_eq!(
+            values(
+                &value,
+                "$..book[?(@.price > 20 || @.author == \"Nigel Rees\")].price"
+            ),
+            vec![Value::from(8.95), Value::from(22.99)]
+        );
+        assert_eq!(
+            values(&value, "$..book[?@.price < $.store.bicycle.price]").len(),
+            4
+        );
+        assert_eq!(values(&value, "$..book[?@.missing == @.other]").len(), 4);
+        assert_eq!(values(&value, "$..book[?@.price < 'text']").len(), 0);
+    }
+
+    #[test]
+    fn test_functions() {
+        let value = store();
+
+        assert_eq!(
+            values(&value, "$..book[?length(@.title) >= 15].price"),
+            vec![Value::from(8.95), Value::from(12.99), Value::from(22.99)]
+        );
+        assert_eq!(values(&value, "$.store[?count(@.*) == 2]").len(), 1);
+        assert_eq!(
+            values(&value, "$..book[?match(@.author, 'H.*')].title"),
+            vec![Value::from("Moby Dick")]
+        );
+        assert_eq!(
+            values(&value, "$..book[?search(@.title, 'of')].price").len(),
+            3
+        );
+        assert_eq!(
+            values(&value, "$.store[?value(@..color) == 'red'].price"),
+            vec![Value::from(399)]
+        );
+    }
+
+    #[test]
+    fn test_normalized_paths_escape_names() {
+        let value = Value::from(vec![("it's", 1), ("a\\b", 2)]);
+
+        assert_eq!(paths(&value, "$['it\\'s']"), vec!["$['it\\'s']"]);
+        assert_eq!(paths(&value, "$[\"a\\\\b\"]"), vec!["$['a\\\\b']"]);
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        let value = store();
+
+        for query in [
+            "store",
+            "$.",
+            "$[",
+            "$[01]",
+            "$[-0]",
+            "$['a'",
+            "$[?@.a == 1",
+            "$[?@.* == 1]",
+            "$[?1]",
+            "$[?length(@.*) > 1]",
+            "$[?match(@.a)]",
+            "$[?unknown(@)]",
+            "$.a b",
+        ] {
+            assert!(value.query(query).is_err(), "{}", query);
+        }
+    }
+}
@@ -34,6 +34,13 @@ pub mod types;
 pub mod value;
 pub mod impls;
 pub mod to_value;
+pub mod index;
+pub mod pointer;
+pub mod patch;
+pub mod merge;
+pub mod diff;
+pub mod schema;
+pub mod json_path;
 
 #[cfg(feature = "parser")]
 #[macro_use]
@@ -49,6 +56,8 @@ pub enum Error {
     #[cfg(feature = "parser")]
     NonParseble,
     NotNumber,
+    /// A NaN or infinite number where only finite numbers are accepted.
+    NotFinite,
 }
 
 #[cfg(test)]
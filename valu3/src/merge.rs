@@ -0,0 +1,418 @@
+//! Merging of values: JSON Merge Patch, RFC 7396, and a deep merge configured with
+//! `MergeOptions`, to layer defaults, configuration files and overrides.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let mut config = value!({"port": 80, "hosts": ["a"], "debug": true});
+//!
+//! config.merge_patch(&value!({"port": 8080, "debug": null}));
+//! assert_eq!(config, value!({"port": 8080, "hosts": ["a"]}));
+//!
+//! let options = MergeOptions::new().arrays(ArrayMerge::Union);
+//! config.merge(&value!({"hosts": ["a", "b"]}), &options).unwrap();
+//! assert_eq!(config, value!({"port": 8080, "hosts": ["a", "b"]}));
+//! ```
+use crate::pointer::escape;
+use crate::prelude::*;
+use std::fmt::{Display, Formatter};
+
+/// How two arrays merge.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ArrayMerge {
+    /// The incoming array replaces the existing one.
+    #[default]
+    Replace,
+    /// The incoming elements are appended.
+    Concat,
+    /// The incoming elements not already present are appended.
+    Union,
+    /// Elements at the same index merge, the extra incoming elements are appended.
+    ByIndex,
+    /// Objects with an equal value for this key merge, the other incoming elements are
+    /// appended.
+    ByKey(String),
+}
+
+/// What an incoming `null` does to an object member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullMerge {
+    /// The member is set to `null`.
+    #[default]
+    Overwrite,
+    /// The member is removed, as in a merge patch.
+    Delete,
+}
+
+/// What happens when the existing and incoming values are of different kinds, such as an
+/// object and a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TypeConflict {
+    /// The incoming value replaces the existing one.
+    #[default]
+    Overwrite,
+    /// The merge fails with a `MergeError`.
+    Error,
+}
+
+/// Options of `Value::merge` and `Object::merge`.
+///
+/// Built by chaining methods from `MergeOptions::new()`, which replaces arrays, writes
+/// `null` and lets incoming values of another kind overwrite.
+///
+/// Objects always merge member by member. Incoming `Undefined` values are skipped, and
+/// existing `Null` or `Undefined` values never conflict.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MergeOptions {
+    arrays: ArrayMerge,
+    nulls: NullMerge,
+    conflicts: TypeConflict,
+}
+
+impl MergeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn arrays(mut self, arrays: ArrayMerge) -> Self {
+        self.arrays = arrays;
+        self
+    }
+
+    pub fn nulls(mut self, nulls: NullMerge) -> Self {
+        self.nulls = nulls;
+        self
+    }
+
+    pub fn conflicts(mut self, conflicts: TypeConflict) -> Self {
+        self.conflicts = conflicts;
+        self
+    }
+}
+
+/// A merge met values of different kinds with `TypeConflict::Error`, at this JSON Pointer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeError {
+    pub path: String,
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unable to merge values of different types at {:?}",
+            self.path
+        )
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+impl Value {
+    /// Applies a JSON Merge Patch: objects merge member by member, `null` members are
+    /// removed, and any other value replaces the target.
+    pub fn merge_patch(&mut self, patch: &Value) {
+        let Value::Object(patch) = patch else {
+            *self = patch.clone();
+            return;
+        };
+
+        if !matches!(self, Value::Object(_)) {
+            *self = Value::Object(Object::default());
+        }
+        if let Value::Object(target) = self {
+            for (key, value) in patch.iter() {
+                if let Value::Null = value {
+                    target.remove_by_key(key);
+                } else {
+                    target.entry_or_null(key.clone()).merge_patch(value);
+                }
+            }
+        }
+    }
+
+    /// Merges `other` into this value, all of it or nothing: on a `MergeError` the value is
+    /// left as it was.
+    pub fn merge(&mut self, other: &Value, options: &MergeOptions) -> Result<(), MergeError> {
+        let mut merged = self.clone();
+        merge_value(&mut merged, other, options, &mut String::new())?;
+        *self = merged;
+        Ok(())
+    }
+}
+
+impl Object {
+    /// Merges the members of `other` into this object, see `Value::merge`.
+    pub fn merge(&mut self, other: &Object, options: &MergeOptions) -> Result<(), MergeError> {
+        let mut merged = self.clone();
+        merge_object(&mut merged, other, options, &mut String::new())?;
+        *self = merged;
+        Ok(())
+    }
+}
+
+fn merge_value(
+    target: &mut Value,
+    other: &Value,
+    options: &MergeOptions,
+    path: &mut String,
+) -> Result<(), MergeError> {
+    let conflict = !matches!(target, Value::Null | Value::Undefined)
+        && !matches!(other, Value::Null | Value::Undefined)
+        && std::mem::discriminant(&*target) != std::mem::discriminant(other);
+    if conflict && options.conflicts == TypeConflict::Error {
+        return Err(MergeError { path: path.clone() });
+    }
+
+    match (&mut *target, other) {
+        (_, Value::Undefined) => {}
+        (Value::Object(target), Value::Object(other)) => {
+            merge_object(target, other, options, path)?
+        }
+        (Value::Array(target), Value::Array(other)) => merge_array(target, other, options, path)?,
+        // Merging into nothing still applies the options to the members of objects.
+        (Value::Null | Value::Undefined, Value::Object(_)) => {
+            *target = Value::Object(Object::default());
+            merge_value(target, other, options, path)?;
+        }
+        _ => *target = other.clone(),
+    }
+
+    Ok(())
+}
+
+fn merge_object(
+    target: &mut Object,
+    other: &Object,
+    options: &MergeOptions,
+    path: &mut String,
+) -> Result<(), MergeError> {
+    let mut entries: Vec<_> = other.iter().collect();
+    entries.sort_by(|left, right| left.0.cmp(right.0));
+
+    for (key, value) in entries {
+        if let (Value::Null, NullMerge::Delete) = (value, options.nulls) {
+            target.remove_by_key(key);
+            continue;
+        }
+        if let (Value::Undefined, None) = (value, target.get_by_key(key)) {
+            continue;
+        }
+
+        let len = path.len();
+        path.push('/');
+        path.push_str(&escape(&key.to_string()));
+        let result = merge_value(target.entry_or_null(key.clone()), value, options, path);
+        path.truncate(len);
+        result?;
+    }
+
+    Ok(())
+}
+
+fn merge_array(
+    target: &mut Array,
+    other: &Array,
+    options: &MergeOptions,
+    path: &mut String,
+) -> Result<(), MergeError> {
+    match &options.arrays {
+        ArrayMerge::Replace => *target = other.clone(),
+        ArrayMerge::Concat => target.values.extend(other.values.iter().cloned()),
+        ArrayMerge::Union => {
+            for value in &other.values {
+                if !target.values.contains(value) {
+                    target.values.push(value.clone());
+                }
+            }
+        }
+        ArrayMerge::ByIndex => {
+            for (index, value) in other.values.iter().enumerate() {
+                match target.values.get_mut(index) {
+                    Some(existing) => merge_element(existing, value, index, options, path)?,
+                    None => target.values.push(value.clone()),
+                }
+            }
+        }
+        ArrayMerge::ByKey(field) => {
+            let id = |value: &Value| match value {
+                Value::Object(object) => object.get(field.as_str()).cloned(),
+                _ => None,
+            };
+
+            for value in &other.values {
+                let position = id(value).and_then(|incoming| {
+                    target
+                        .values
+                        .iter()
+                        .position(|existing| id(existing).as_ref() == Some(&incoming))
+                });
+                match position {
+                    Some(index) => {
+                        merge_element(&mut target.values[index], value, index, options, path)?
+                    }
+                    None => target.values.push(value.clone()),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn merge_element(
+    target: &mut Value,
+    other: &Value,
+    index: usize,
+    options: &MergeOptions,
+    path: &mut String,
+) -> Result<(), MergeError> {
+    let len = path.len();
+    path.push_str(&format!("/{}", index));
+    let result = merge_value(target, other, options, path);
+    path.truncate(len);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_merge_patch() {
+        // The examples of RFC 7396, appendix A.
+        let cases = [
+            (value!({"a": "b"}), value!({"a": "c"}), value!({"a": "c"})),
+            (
+                value!({"a": "b"}),
+                value!({"b": "c"}),
+                value!({"a": "b", "b": "c"}),
+            ),
+            (value!({"a": "b"}), value!({"a": null}), value!({})),
+            (
+                value!({"a": "b", "b": "c"}),
+                value!({"a": null}),
+                value!({"b": "c"}),
+            ),
+            (value!({"a": ["b"]}), value!({"a": "c"}), value!({"a": "c"})),
+            (
+                value!({"a": "c"}),
+                value!({"a": ["b"]}),
+                value!({"a": ["b"]}),
+            ),
+            (
+                value!({"a": {"b": "c"}}),
+                value!({"a": {"b": "d", "c": null}}),
+                value!({"a": {"b": "d"}}),
+            ),
+            (
+                value!({"a": [{"b": "c"}]}),
+                value!({"a": [1]}),
+                value!({"a": [1]}),
+            ),
+            (value!(["a", "b"]), value!(["c", "d"]), value!(["c", "d"])),
+            (value!({"a": "b"}), value!(["c"]), value!(["c"])),
+            (value!({"a": "foo"}), value!(null), value!(null)),
+            (value!({"a": "foo"}), value!("bar"), value!("bar")),
+            (
+                value!({"e": null}),
+                value!({"a": 1}),
+                value!({"e": null, "a": 1}),
+            ),
+            (
+                value!([1, 2]),
+                value!({"a": "b", "c": null}),
+                value!({"a": "b"}),
+            ),
+            (
+                value!({}),
+                value!({"a": {"bb": {"ccc": null}}}),
+                value!({"a": {"bb": {}}}),
+            ),
+        ];
+
+        for (mut target, patch, expected) in cases {
+            target.merge_patch(&patch);
+            assert_eq!(target, expected, "{}", patch);
+        }
+    }
+
+    #[test]
+    fn test_merge_arrays() {
+        let merged = |arrays: ArrayMerge, target: Value, other: Value| {
+            let mut target = target;
+            target
+                .merge(&other, &MergeOptions::new().arrays(arrays))
+                .unwrap();
+            target
+        };
+
+        assert_eq!(
+            merged(ArrayMerge::Replace, value!([1, 2]), value!([3])),
+            value!([3])
+        );
+        assert_eq!(
+            merged(ArrayMerge::Concat, value!([1, 2]), value!([2, 3])),
+            value!([1, 2, 2, 3])
+        );
+        assert_eq!(
+            merged(ArrayMerge::Union, value!([1, 2]), value!([2, 3, 3])),
+            value!([1, 2, 3])
+        );
+        assert_eq!(
+            merged(
+                ArrayMerge::ByIndex,
+                value!([{"a": 1}, 2]),
+                value!([{"b": 1}, 3, 4])
+            ),
+            value!([{"a": 1, "b": 1}, 3, 4])
+        );
+        assert_eq!(
+            merged(
+                ArrayMerge::ByKey("id".to_string()),
+                value!([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]),
+                value!([{"id": 2, "admin": true}, {"id": 3}, "text"])
+            ),
+            value!([
+                {"id": 1, "name": "a"},
+                {"id": 2, "name": "b", "admin": true},
+                {"id": 3},
+                "text"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merge_nulls_and_conflicts() {
+        let defaults = value!({"server": {"port": 80, "tls": {"cert": "a.pem"}}, "debug": false});
+        let overrides = value!({"server": {"tls": null, "port": "8080"}, "name": undefined});
+
+        let mut config = defaults.clone();
+        config.merge(&overrides, &MergeOptions::new()).unwrap();
+        assert_eq!(
+            config,
+            value!({"server": {"port": "8080", "tls": null}, "debug": false})
+        );
+
+        let mut config = defaults.clone();
+        let options = MergeOptions::new().nulls(NullMerge::Delete);
+        config.merge(&overrides, &options).unwrap();
+        assert_eq!(config, value!({"server": {"port": "8080"}, "debug": false}));
+
+        let mut config = defaults.clone();
+        let options = MergeOptions::new().conflicts(TypeConflict::Error);
+        assert_eq!(
+            config.merge(&overrides, &options),
+            Err(MergeError {
+                path: "/server/port".to_string()
+            })
+        );
+        assert_eq!(config, defaults);
+
+        let mut object = Object::from(vec![("a", 1)]);
+        object
+            .merge(&Object::from(vec![("b", 2)]), &MergeOptions::new())
+            .unwrap();
+        assert_eq!(Value::Object(object), value!({"a": 1, "b": 2}));
+    }
+}
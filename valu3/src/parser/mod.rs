@@ -8,21 +8,55 @@ struct JSONParser;
 
 use pest::iterators::Pair;
 
+/// Options of `Value::payload_to_value_with`, the defaults only accept standard JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    /// Accepts number literals as read by `Number::from_literal`, such as `0xFF`,
+    /// `1_000_000`, `+5` or `10u8`.
+    pub relaxed_numbers: bool,
+    /// Reads numbers with a fraction or an exponent as exact decimals instead of `f64`.
+    #[cfg(feature = "decimal")]
+    pub decimal_numbers: bool,
+}
+
+impl ParseOptions {
+    /// Enables every relaxed option.
+    pub fn relaxed() -> Self {
+        ParseOptions {
+            relaxed_numbers: true,
+            #[cfg(feature = "decimal")]
+            decimal_numbers: false,
+        }
+    }
+
+    /// The default options, reading fractional numbers as exact decimals.
+    #[cfg(feature = "decimal")]
+    pub fn decimal() -> Self {
+        ParseOptions {
+            relaxed_numbers: false,
+            decimal_numbers: true,
+        }
+    }
+}
+
 impl Value {
     pub fn payload_to_value(str: &str) -> Result<Value, Error> {
-        let value = match JSONParser::parse(Rule::json, str.trim()) {
+        Self::payload_to_value_with(str, &ParseOptions::default())
+    }
+
+    pub fn payload_to_value_with(str: &str, options: &ParseOptions) -> Result<Value, Error> {
+        match JSONParser::parse(Rule::json, str.trim()) {
             Ok(mut pairs) => match pairs.next() {
-                Some(pair) => Self::parse_value(pair),
-                None => return Err(Error::NonParseble),
+                Some(pair) => Self::parse_value(pair, options),
+                None => Err(Error::NonParseble),
             },
-            Err(msg) => return Err(Error::NonParsebleMsg(msg.to_string())),
-        };
-        Ok(value)
+            Err(msg) => Err(Error::NonParsebleMsg(msg.to_string())),
+        }
     }
 
     /// Parses a `Pair` from `pest` to a `Value`.
-    fn parse_value(pair: Pair<Rule>) -> Self {
-        match pair.as_rule() {
+    fn parse_value(pair: Pair<Rule>, options: &ParseOptions) -> Result<Self, Error> {
+        let value = match pair.as_rule() {
             Rule::object => {
                 let map = pair
                     .into_inner()
@@ -36,16 +70,33 @@ impl Value {
                             .unwrap()
                             .as_str()
                             .to_string();
-                        let value = Self::parse_value(inner_rules.next().unwrap());
-                        (name, value)
+                        let value = Self::parse_value(inner_rules.next().unwrap(), options)?;
+                        Ok((name, value))
                     })
-                    .collect::<HashMap<String, Value>>();
+                    .collect::<Result<HashMap<String, Value>, Error>>()?;
 
                 Self::from(map)
             }
-            Rule::array => Self::from(pair.into_inner().map(Self::parse_value).collect::<Vec<_>>()),
+            Rule::array => Self::from(
+                pair.into_inner()
+                    .map(|pair| Self::parse_value(pair, options))
+                    .collect::<Result<Vec<_>, Error>>()?,
+            ),
             Rule::string => Self::from(StringB::from(pair.into_inner().next().unwrap().as_str())),
+            #[cfg(feature = "decimal")]
+            Rule::number if options.decimal_numbers && pair.as_str().contains(['.', 'e', 'E']) => {
+                Self::from(Number::decimal_from_str(pair.as_str())?)
+            }
             Rule::number => Self::from(Number::try_from(pair.as_str()).unwrap()),
+            Rule::relaxed_number if options.relaxed_numbers => {
+                Self::from(Number::from_literal(pair.as_str())?)
+            }
+            Rule::relaxed_number => {
+                return Err(Error::NonParsebleMsg(format!(
+                    "Unable to parse the number {} without relaxed numbers",
+                    pair.as_str()
+                )))
+            }
             Rule::boolean => Self::Boolean(pair.as_str().parse().unwrap()),
             Rule::null => Self::Null,
             Rule::json
@@ -55,7 +106,9 @@ impl Value {
             | Rule::inner
             | Rule::char
             | Rule::WHITESPACE => Self::Undefined,
-        }
+        };
+
+        Ok(value)
     }
 }
 
@@ -153,4 +206,20 @@ mod tests {
         assert_eq!(null, Value::Null);
         assert_eq!(string, "123".to_value());
     }
+
+    #[test]
+    fn relaxed_numbers() {
+        let raw = "{\"mode\": 0o755, \"mask\": 0xFF, \"limit\": 1_000_000, \"items\": [+5, 10u8, 2.5f32]}";
+        let value = Value::payload_to_value_with(raw, &ParseOptions::relaxed()).unwrap();
+
+        assert_eq!(value.get("mode"), Some(&Value::from(493)));
+        assert_eq!(value.get("mask"), Some(&Value::from(255)));
+        assert_eq!(value.get("limit"), Some(&Value::from(1_000_000)));
+        assert!(value.get("items").unwrap().get(1).unwrap().is_u8());
+        assert!(value.get("items").unwrap().get(2).unwrap().is_f32());
+
+        assert!(Value::payload_to_value(raw).is_err());
+        assert!(Value::payload_to_value("[1, 0x10]").is_err());
+        assert!(Value::payload_to_value_with("[1, 300u8]", &ParseOptions::relaxed()).is_err());
+    }
 }
@@ -0,0 +1,419 @@
+//! JSON Patch, RFC 6902, to apply a list of changes to a `Value` and to compute the changes
+//! between two values.
+//!
+//! A patch is an array of operations such as
+//! `{"op": "replace", "path": "/name", "value": "Grace"}`, where `op` is one of `add`,
+//! `remove`, `replace`, `move`, `copy` and `test`, and `path` and `from` are JSON Pointers.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let mut value = value!({"name": "Ada", "tags": ["admin"]});
+//! let patch = value!([
+//!     {"op": "test", "path": "/name", "value": "Ada"},
+//!     {"op": "add", "path": "/tags/-", "value": "owner"},
+//!     {"op": "replace", "path": "/name", "value": "Grace"}
+//! ]);
+//!
+//! value.apply_patch(&patch).unwrap();
+//! assert_eq!(value, value!({"name": "Grace", "tags": ["admin", "owner"]}));
+//!
+//! let patch = Value::diff_patch(&value!({"a": 1}), &value!({"a": 2}));
+//! assert_eq!(patch, value!([{"op": "replace", "path": "/a", "value": 2}]));
+//! ```
+use crate::diff::align;
+use crate::pointer::{array_index, escape, object_key, parse, path, walk_mut};
+use crate::prelude::*;
+use std::fmt::{Display, Formatter};
+
+/// Why a patch could not be applied, with the position of the failing operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// The patch is not an array of operations, or an operation has an unknown `op` or
+    /// misses a member.
+    Invalid { operation: usize, message: String },
+    /// A `path` or `from` of the operation could not be followed.
+    Pointer {
+        operation: usize,
+        error: PointerError,
+    },
+    /// A `test` operation found a different value at `path`.
+    TestFailed { operation: usize, path: String },
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchError::Invalid { operation, message } => {
+                write!(f, "Invalid operation {}: {}", operation, message)
+            }
+            PatchError::Pointer { operation, error } => {
+                write!(f, "Operation {} failed: {}", operation, error)
+            }
+            PatchError::TestFailed { operation, path } => {
+                write!(f, "Operation {} failed: test of {:?}", operation, path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Reads a string member of an operation.
+fn member<'p>(operation: &'p Object, name: &str, position: usize) -> Result<&'p str, PatchError> {
+    match operation.get(name) {
+        Some(Value::String(string)) => Ok(string.as_str()),
+        _ => Err(PatchError::Invalid {
+            operation: position,
+            message: format!("Expected a string `{}`", name),
+        }),
+    }
+}
+
+/// Inserts a value at a pointer whose parent exists, shifting array elements up.
+fn add(target: &mut Value, pointer: &str, value: Value) -> Result<(), PointerError> {
+    let tokens = parse(pointer)?;
+    let Some((token, parents)) = tokens.split_last() else {
+        *target = value;
+        return Ok(());
+    };
+
+    match walk_mut(target, parents)? {
+        Value::Object(object) => {
+            let key = object_key(object, token).unwrap_or_else(|| ValueKey::from(token.as_str()));
+            *object.entry_or_null(key) = value;
+            Ok(())
+        }
+        Value::Array(array) => {
+            let index = array_index(token, array.len(), &tokens, tokens.len())?;
+            if index > array.len() {
+                return Err(PointerError::IndexOutOfRange(path(&tokens, tokens.len())));
+            }
+            array.values.insert(index, value);
+            Ok(())
+        }
+        _ => Err(PointerError::NotContainer(path(&tokens, tokens.len()))),
+    }
+}
+
+impl Value {
+    /// Applies a JSON Patch, all of it or nothing: when an operation fails, the value is left
+    /// as it was and the error tells which operation failed.
+    pub fn apply_patch(&mut self, patch: &Value) -> Result<(), PatchError> {
+        let Value::Array(operations) = patch else {
+            return Err(PatchError::Invalid {
+                operation: 0,
+                message: "Expected an array of operations".to_string(),
+            });
+        };
+
+        let mut patched = self.clone();
+        for (position, operation) in operations.into_iter().enumerate() {
+            patched.apply_operation(operation, position)?;
+        }
+
+        *self = patched;
+        Ok(())
+    }
+
+    fn apply_operation(&mut self, operation: &Value, position: usize) -> Result<(), PatchError> {
+        let Value::Object(operation) = operation else {
+            return Err(PatchError::Invalid {
+                operation: position,
+                message: "Expected an object".to_string(),
+            });
+        };
+
+        let op = member(operation, "op", position)?;
+        let target = member(operation, "path", position)?;
+        let value = || {
+            operation.get("value").cloned().ok_or(PatchError::Invalid {
+                operation: position,
+                message: "Expected a `value`".to_string(),
+            })
+        };
+        let pointer_error = |error| PatchError::Pointer {
+            operation: position,
+            error,
+        };
+
+        match op {
+            "add" => add(self, target, value()?).map_err(pointer_error),
+            "remove" => self
+                .remove_pointer(target)
+                .map(|_| ())
+                .map_err(pointer_error),
+            "replace" => {
+                let value = value()?;
+                *self.pointer_mut(target).map_err(pointer_error)? = value;
+                Ok(())
+            }
+            "move" => {
+                let from = member(operation, "from", position)?;
+                if target.starts_with(from) && target[from.len()..].starts_with('/') {
+                    return Err(PatchError::Invalid {
+                        operation: position,
+                        message: "Unable to move a value into one of its children".to_string(),
+                    });
+                }
+                let moved = self.remove_pointer(from).map_err(pointer_error)?;
+                add(self, target, moved).map_err(pointer_error)
+            }
+            "copy" => {
+                let from = member(operation, "from", position)?;
+                let copied = self.pointer(from).map_err(pointer_error)?.clone();
+                add(self, target, copied).map_err(pointer_error)
+            }
+            "test" => {
+                let expected = value()?;
+                if *self.pointer(target).map_err(pointer_error)? == expected {
+                    Ok(())
+                } else {
+                    Err(PatchError::TestFailed {
+                        operation: position,
+                        path: target.to_string(),
+                    })
+                }
+            }
+            _ => Err(PatchError::Invalid {
+                operation: position,
+                message: format!("Unknown op {:?}", op),
+            }),
+        }
+    }
+
+    /// Computes a JSON Patch turning `from` into `to`.
+    ///
+    /// Objects are compared member by member. Arrays are aligned on their longest common
+    /// subsequence like `Value::diff`, and the elements left over between two aligned ones
+    /// are compared in pairs, so an unchanged value yields an empty patch and each inserted
+    /// or removed element anywhere in an array yields a single operation. Arrays too large
+    /// to align within `diff::MAX_ALIGNMENT_CELLS` are compared index by index.
+    pub fn diff_patch(from: &Value, to: &Value) -> Value {
+        let mut operations = Vec::new();
+        diff(from, to, "", &mut operations);
+        Value::from(operations)
+    }
+}
+
+fn diff(from: &Value, to: &Value, pointer: &str, operations: &mut Vec<Value>) {
+    if from == to {
+        return;
+    }
+
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            let mut removed: Vec<_> = from
+                .iter()
+                .filter(|(key, _)| to.get_by_key(key).is_none())
+                .map(|(key, _)| key)
+                .collect();
+            removed.sort();
+            for key in removed {
+                let path = format!("{}/{}", pointer, escape(&key.to_string()));
+                operations.push(value!({"op": "remove", "path": path}));
+            }
+
+            let mut entries: Vec<_> = to.iter().collect();
+            entries.sort_by(|left, right| left.0.cmp(right.0));
+            for (key, value) in entries {
+                let path = format!("{}/{}", pointer, escape(&key.to_string()));
+                match from.get_by_key(key) {
+                    Some(previous) => diff(previous, value, &path, operations),
+                    None => operations.push(value!({"op": "add", "path": path, "value": value})),
+                }
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => {
+            let (from, to) = (&from.values, &to.values);
+
+            // Before each gap the array already holds `to` up to `added.start`, followed by
+            // the rest of `from` from `removed.start`.
+            for (removed, added) in align(from, to) {
+                let pairs = removed.len().min(added.len());
+                for offset in 0..pairs {
+                    let path = format!("{}/{}", pointer, added.start + offset);
+                    diff(
+                        &from[removed.start + offset],
+                        &to[added.start + offset],
+                        &path,
+                        operations,
+                    );
+                }
+                // Removing at the same index shifts the next removed element into it.
+                for _ in pairs..removed.len() {
+                    let path = format!("{}/{}", pointer, added.start + pairs);
+                    operations.push(value!({"op": "remove", "path": path}));
+                }
+                for (index, value) in to
+                    .iter()
+                    .enumerate()
+                    .take(added.end)
+                    .skip(added.start + pairs)
+                {
+                    let path = format!("{}/{}", pointer, index);
+                    operations.push(value!({"op": "add", "path": path, "value": value}));
+                }
+            }
+        }
+        _ => operations.push(value!({"op": "replace", "path": pointer, "value": to})),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn patched(value: Value, patch: Value) -> Result<Value, PatchError> {
+        let mut value = value;
+        value.apply_patch(&patch)?;
+        Ok(value)
+    }
+
+    #[test]
+    fn test_operations() {
+        assert_eq!(
+            patched(
+                value!({"foo": "bar"}),
+                value!([{"op": "add", "path": "/baz", "value": "qux"}])
+            ),
+            Ok(value!({"foo": "bar", "baz": "qux"}))
+        );
+        assert_eq!(
+            patched(
+                value!({"foo": ["bar", "baz"]}),
+                value!([{"op": "add", "path": "/foo/1", "value": "qux"}])
+            ),
+            Ok(value!({"foo": ["bar", "qux", "baz"]}))
+        );
+        assert_eq!(
+            patched(
+                value!({"foo": ["bar", "qux", "baz"]}),
+                value!([{"op": "remove", "path": "/foo/1"}])
+            ),
+            Ok(value!({"foo": ["bar", "baz"]}))
+        );
+        assert_eq!(
+            patched(
+                value!({"baz": "qux", "foo": "bar"}),
+                value!([{"op": "replace", "path": "/baz", "value": "boo"}])
+            ),
+            Ok(value!({"baz": "boo", "foo": "bar"}))
+        );
+        assert_eq!(
+            patched(
+                value!({"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}),
+                value!([{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}])
+            ),
+            Ok(value!({"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}))
+        );
+        assert_eq!(
+            patched(
+                value!({"foo": ["all", "grass", "cows", "eat"]}),
+                value!([{"op": "move", "from": "/foo/1", "path": "/foo/3"}])
+            ),
+            Ok(value!({"foo": ["all", "cows", "eat", "grass"]}))
+        );
+        assert_eq!(
+            patched(
+                value!({"foo": [1]}),
+                value!([{"op": "copy", "from": "/foo", "path": "/bar"}])
+            ),
+            Ok(value!({"foo": [1], "bar": [1]}))
+        );
+        assert_eq!(
+            patched(
+                value!({"foo": 1}),
+                value!([{"op": "replace", "path": "", "value": [1]}])
+            ),
+            Ok(value!([1]))
+        );
+    }
+
+    #[test]
+    fn test_failures_roll_back() {
+        let original = value!({"baz": "qux", "foo": ["a", 2, "c"]});
+        let mut value = original.clone();
+
+        let result = value.apply_patch(&value!([
+            {"op": "remove", "path": "/baz"},
+            {"op": "test", "path": "/foo/1", "value": 2.0},
+            {"op": "test", "path": "/foo/1", "value": "b"}
+        ]));
+        assert_eq!(
+            result,
+            Err(PatchError::TestFailed {
+                operation: 2,
+                path: "/foo/1".to_string()
+            })
+        );
+        assert_eq!(value, original);
+
+        for patch in [
+            value!({"op": "add", "path": "/a", "value": 1}),
+            value!([{"op": "add", "path": "/baz/bat", "value": "qux"}]),
+            value!([{"op": "add", "path": "/foo/4", "value": 1}]),
+            value!([{"op": "add", "path": "/foo", "value": 1, "extra": null}, {"op": "remove", "path": "/missing"}]),
+            value!([{"op": "replace", "path": "/missing", "value": 1}]),
+            value!([{"op": "move", "from": "/foo", "path": "/foo/0"}]),
+            value!([{"op": "add", "path": "/a"}]),
+            value!([{"op": "unknown", "path": "/a"}]),
+        ] {
+            assert!(value.apply_patch(&patch).is_err(), "{}", patch);
+            assert_eq!(value, original);
+        }
+    }
+
+    #[test]
+    fn test_diff_patch() {
+        let cases = [
+            (value!({"a": 1}), value!({"a": 1})),
+            (value!({"a": 1, "b": 2}), value!({"a": 3, "c": {"d": [1]}})),
+            (value!([1, 2, 3]), value!([0, 1, 2, 3])),
+            (value!([1, 2, 3, 4]), value!([1, 4])),
+            (value!([1, {"a": 1}, 3]), value!([1, {"a": 2}, 3, 4, 5])),
+            (value!({"a/b": {"c~d": 1}}), value!({"a/b": {"c~d": 2}})),
+            (value!({"a": [1]}), value!("text")),
+            (value!([1, 2, 3, 4]), value!([2, 3, 4, 5])),
+            (value!([1, 2, 3, 4, 5]), value!([0, 2, 3, 6, 7, 4])),
+            (value!(["a", "b", "c"]), value!(["c", "b", "a"])),
+        ];
+
+        for (from, to) in cases {
+            let patch = Value::diff_patch(&from, &to);
+            assert_eq!(
+                patched(from.clone(), patch.clone()),
+                Ok(to.clone()),
+                "{}",
+                patch
+            );
+        }
+
+        assert_eq!(Value::diff_patch(&value!([1]), &value!([1])), value!([]));
+        assert_eq!(
+            Value::diff_patch(&value!([1, 2, 3, 4]), &value!([1, 4])),
+            value!([{"op": "remove", "path": "/1"}, {"op": "remove", "path": "/1"}])
+        );
+        assert_eq!(
+            Value::diff_patch(&value!([1, 2, 3, 4]), &value!([2, 3, 4, 5])),
+            value!([
+                {"op": "remove", "path": "/0"},
+                {"op": "add", "path": "/3", "value": 5}
+            ])
+        );
+        assert_eq!(
+            Value::diff_patch(&value!({"a/b": {"c~d": 1}}), &value!({"a/b": {"c~d": 2}})),
+            value!([{"op": "replace", "path": "/a~1b/c~0d", "value": 2}])
+        );
+    }
+
+    #[test]
+    fn test_diff_patch_large_arrays() {
+        let from = Value::from((0..20_000).collect::<Vec<i32>>());
+        let to = Value::from((-1..19_999).collect::<Vec<i32>>());
+
+        let patch = Value::diff_patch(&from, &to);
+        assert_eq!(patch.len(), 20_000);
+        assert_eq!(patched(from, patch), Ok(to));
+    }
+}
@@ -0,0 +1,467 @@
+//! JSON Pointer, RFC 6901, to read and change nested values by a path such as `/users/0/name`.
+//!
+//! Each token after a `/` is an object key or an array index, with `~1` standing for `/` and
+//! `~0` for `~` inside keys. The empty pointer is the whole value. On arrays, `-` names the
+//! position after the last element, where `set_pointer` appends.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let mut value = Value::payload_to_value(r#"{"users": [{"name": "Ada"}], "a/b": 1}"#).unwrap();
+//!
+//! assert_eq!(value.pointer("/users/0/name"), Ok(&Value::from("Ada")));
+//! assert_eq!(value.pointer("/a~1b"), Ok(&Value::from(1)));
+//!
+//! value.set_pointer("/users/-/name", Value::from("Grace")).unwrap();
+//! value.remove_pointer("/users/0").unwrap();
+//! assert_eq!(value.pointer("/users/0/name"), Ok(&Value::from("Grace")));
+//! ```
+use crate::prelude::*;
+use std::fmt::{Display, Formatter};
+
+/// Why a JSON Pointer could not be followed.
+///
+/// Each variant holds the pointer up to the token that failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerError {
+    /// The pointer does not start with `/`, or has a `~` not followed by `0` or `1`.
+    Syntax(String),
+    /// An object has no such key.
+    MissingKey(String),
+    /// An array index is past the last element, or `-` where an element must exist.
+    IndexOutOfRange(String),
+    /// A token applied to an array is not an index, such as `name` or `01`.
+    InvalidIndex(String),
+    /// A value along the way is neither an object nor an array.
+    NotContainer(String),
+}
+
+impl Display for PointerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PointerError::Syntax(pointer) => write!(f, "Invalid JSON Pointer {:?}", pointer),
+            PointerError::MissingKey(pointer) => write!(f, "No value at {:?}", pointer),
+            PointerError::IndexOutOfRange(pointer) => {
+                write!(f, "Array index out of range at {:?}", pointer)
+            }
+            PointerError::InvalidIndex(pointer) => {
+                write!(f, "Invalid array index at {:?}", pointer)
+            }
+            PointerError::NotContainer(pointer) => {
+                write!(f, "Neither an object nor an array before {:?}", pointer)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PointerError {}
+
+/// Splits a pointer into its unescaped tokens.
+pub(crate) fn parse(pointer: &str) -> Result<Vec<String>, PointerError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let Some(tokens) = pointer.strip_prefix('/') else {
+        return Err(PointerError::Syntax(pointer.to_string()));
+    };
+
+    tokens
+        .split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => unescaped.push('~'),
+                    Some('1') => unescaped.push('/'),
+                    _ => return Err(PointerError::Syntax(pointer.to_string())),
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+/// Writes the pointer of the first `count` tokens.
+pub(crate) fn path(tokens: &[String], count: usize) -> String {
+    tokens[..count]
+        .iter()
+        .map(|token| format!("/{}", escape(token)))
+        .collect()
+}
+
+/// Escapes `~` and `/` in a token.
+pub(crate) fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Reads an array index, `-` being `len`.
+pub(crate) fn array_index(
+    token: &str,
+    len: usize,
+    tokens: &[String],
+    count: usize,
+) -> Result<usize, PointerError> {
+    if token == "-" {
+        return Ok(len);
+    }
+    if !is_index(token) {
+        return Err(PointerError::InvalidIndex(path(tokens, count)));
+    }
+    token
+        .parse()
+        .map_err(|_| PointerError::IndexOutOfRange(path(tokens, count)))
+}
+
+/// Checks that a token is `0` or digits without a leading zero.
+fn is_index(token: &str) -> bool {
+    !token.is_empty()
+        && token.bytes().all(|byte| byte.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'))
+}
+
+/// Finds the key of an object named by a token, a string key or else a number key.
+pub(crate) fn object_key(object: &Object, token: &str) -> Option<ValueKey> {
+    let key = ValueKey::from(token);
+    if object.get_by_key(&key).is_some() {
+        return Some(key);
+    }
+
+    let key = ValueKey::Number(token.parse().ok().filter(|_| is_index(token))?);
+    object.get_by_key(&key).map(|_| key)
+}
+
+fn walk<'v>(value: &'v Value, tokens: &[String]) -> Result<&'v Value, PointerError> {
+    let mut current = value;
+
+    for (position, token) in tokens.iter().enumerate() {
+        let count = position + 1;
+        current = match current {
+            Value::Object(object) => object_key(object, token)
+                .and_then(|key| object.get_by_key(&key))
+                .ok_or_else(|| PointerError::MissingKey(path(tokens, count)))?,
+            Value::Array(array) => {
+                let index = array_index(token, array.len(), tokens, count)?;
+                array
+                    .get(index)
+                    .ok_or_else(|| PointerError::IndexOutOfRange(path(tokens, count)))?
+            }
+            _ => return Err(PointerError::NotContainer(path(tokens, count))),
+        };
+    }
+
+    Ok(current)
+}
+
+pub(crate) fn walk_mut<'v>(
+    value: &'v mut Value,
+    tokens: &[String],
+) -> Result<&'v mut Value, PointerError> {
+    let mut current = value;
+
+    for (position, token) in tokens.iter().enumerate() {
+        let count = position + 1;
+        current = match current {
+            Value::Object(object) => match object_key(object, token) {
+                Some(key) => object.entry_or_null(key),
+                None => return Err(PointerError::MissingKey(path(tokens, count))),
+            },
+            Value::Array(array) => {
+                let index = array_index(token, array.len(), tokens, count)?;
+                array
+                    .get_mut(index)
+                    .ok_or_else(|| PointerError::IndexOutOfRange(path(tokens, count)))?
+            }
+            _ => return Err(PointerError::NotContainer(path(tokens, count))),
+        };
+    }
+
+    Ok(current)
+}
+
+/// Checks that `set_pointer` can follow the tokens, before it changes anything.
+///
+/// `None` stands for a value that is missing or `Null`, which becomes an empty container.
+fn check_set(value: &Value, tokens: &[String]) -> Result<(), PointerError> {
+    let mut current = Some(value);
+
+    for (position, token) in tokens.iter().enumerate() {
+        let count = position + 1;
+        current = match current {
+            None | Some(Value::Null) | Some(Value::Undefined) => {
+                // A new array is empty, so only its end can be set.
+                if (token == "-" || is_index(token)) && array_index(token, 0, tokens, count)? > 0 {
+                    return Err(PointerError::IndexOutOfRange(path(tokens, count)));
+                }
+                None
+            }
+            Some(Value::Object(object)) => {
+                object_key(object, token).and_then(|key| object.get_by_key(&key))
+            }
+            Some(Value::Array(array)) => {
+                let index = array_index(token, array.len(), tokens, count)?;
+                if index > array.len() {
+                    return Err(PointerError::IndexOutOfRange(path(tokens, count)));
+                }
+                array.get(index)
+            }
+            Some(_) => return Err(PointerError::NotContainer(path(tokens, count))),
+        };
+    }
+
+    Ok(())
+}
+
+impl Value {
+    /// Returns the value at a JSON Pointer.
+    pub fn pointer(&self, pointer: &str) -> Result<&Value, PointerError> {
+        walk(self, &parse(pointer)?)
+    }
+
+    /// Returns a mutable reference to the value at a JSON Pointer.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Result<&mut Value, PointerError> {
+        walk_mut(self, &parse(pointer)?)
+    }
+
+    /// Sets the value at a JSON Pointer and returns the value it replaced, if any.
+    ///
+    /// Missing keys are inserted, and `Null` or `Undefined` along the way become an array
+    /// when the next token is an index or `-`, or an object otherwise. An array index must
+    /// name an element or the end of the array, which `-` also names, to append. The value
+    /// is left unchanged when the pointer cannot be followed.
+    pub fn set_pointer(
+        &mut self,
+        pointer: &str,
+        value: Value,
+    ) -> Result<Option<Value>, PointerError> {
+        let tokens = parse(pointer)?;
+        check_set(self, &tokens)?;
+
+        let mut current = self;
+        let mut existed = true;
+
+        for (position, token) in tokens.iter().enumerate() {
+            let count = position + 1;
+
+            if let Value::Null | Value::Undefined = current {
+                *current = if token == "-" || is_index(token) {
+                    Value::Array(Array::new())
+                } else {
+                    Value::Object(Object::default())
+                };
+            }
+
+            current = match current {
+                Value::Object(object) => {
+                    let key = object_key(object, token);
+                    existed = key.is_some();
+                    object.entry_or_null(key.unwrap_or_else(|| ValueKey::from(token.as_str())))
+                }
+                Value::Array(array) => {
+                    let index = array_index(token, array.len(), &tokens, count)?;
+                    existed = index < array.len();
+                    if !existed {
+                        array.push(Value::Null);
+                    }
+                    &mut array.values[index]
+                }
+                _ => return Err(PointerError::NotContainer(path(&tokens, count))),
+            };
+        }
+
+        let previous = std::mem::replace(current, value);
+        Ok(if existed { Some(previous) } else { None })
+    }
+
+    /// Removes the value at a JSON Pointer and returns it.
+    ///
+    /// Elements after a removed array element shift down. The empty pointer takes the whole
+    /// value and leaves `Undefined`.
+    pub fn remove_pointer(&mut self, pointer: &str) -> Result<Value, PointerError> {
+        let tokens = parse(pointer)?;
+        let Some((token, parents)) = tokens.split_last() else {
+            return Ok(std::mem::replace(self, Value::Undefined));
+        };
+
+        match walk_mut(self, parents)? {
+            Value::Object(object) => object_key(object, token)
+                .and_then(|key| object.remove_by_key(&key))
+                .ok_or_else(|| PointerError::MissingKey(path(&tokens, tokens.len()))),
+            Value::Array(array) => {
+                let index = array_index(token, array.len(), &tokens, tokens.len())?;
+                if index < array.len() {
+                    Ok(array.values.remove(index))
+                } else {
+                    Err(PointerError::IndexOutOfRange(path(&tokens, tokens.len())))
+                }
+            }
+            _ => Err(PointerError::NotContainer(path(&tokens, tokens.len()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn sample() -> Value {
+        Value::payload_to_value(
+            r#"{"users": [{"name": "Ada"}, {"name": "Alan"}], "a/b": 1, "m~n": 2, "": 3}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pointer() {
+        let value = sample();
+
+        assert_eq!(value.pointer(""), Ok(&value));
+        assert_eq!(value.pointer("/users/1/name"), Ok(&Value::from("Alan")));
+        assert_eq!(value.pointer("/a~1b"), Ok(&Value::from(1)));
+        assert_eq!(value.pointer("/m~0n"), Ok(&Value::from(2)));
+        assert_eq!(value.pointer("/"), Ok(&Value::from(3)));
+    }
+
+    #[test]
+    fn test_pointer_errors() {
+        let value = sample();
+
+        assert_eq!(
+            value.pointer("/users/0/age"),
+            Err(PointerError::MissingKey("/users/0/age".to_string()))
+        );
+        assert_eq!(
+            value.pointer("/users/2/name"),
+            Err(PointerError::IndexOutOfRange("/users/2".to_string()))
+        );
+        assert_eq!(
+            value.pointer("/users/-"),
+            Err(PointerError::IndexOutOfRange("/users/-".to_string()))
+        );
+        assert_eq!(
+            value.pointer("/users/01"),
+            Err(PointerError::InvalidIndex("/users/01".to_string()))
+        );
+        assert_eq!(
+            value.pointer("/a~1b/c"),
+            Err(PointerError::NotContainer("/a~1b/c".to_string()))
+        );
+        assert_eq!(
+            value.pointer("users"),
+            Err(PointerError::Syntax("users".to_string()))
+        );
+        assert_eq!(
+            value.pointer("/m~2n"),
+            Err(PointerError::Syntax("/m~2n".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_pointer_mut() {
+        let mut value = sample();
+        *value.pointer_mut("/users/0/name").unwrap() = Value::from("Grace");
+
+        assert_eq!(value["users"][0]["name"], Value::from("Grace"));
+        assert!(value.pointer_mut("/users/5").is_err());
+    }
+
+    #[test]
+    fn test_set_pointer() {
+        let mut value = sample();
+
+        assert_eq!(
+            value.set_pointer("/users/0/name", Value::from("Grace")),
+            Ok(Some(Value::from("Ada")))
+        );
+        assert_eq!(
+            value.set_pointer("/users/-/name", Value::from("Edsger")),
+            Ok(None)
+        );
+        assert_eq!(
+            value.set_pointer("/settings/tags/0", Value::from("x")),
+            Ok(None)
+        );
+        assert_eq!(
+            value.set_pointer("/settings/tags/1", Value::from("y")),
+            Ok(None)
+        );
+        assert_eq!(
+            value.set_pointer("/a~1b/c", Value::Null),
+            Err(PointerError::NotContainer("/a~1b/c".to_string()))
+        );
+
+        assert_eq!(value["users"][2]["name"], Value::from("Edsger"));
+        assert_eq!(
+            value["settings"]["tags"],
+            Value::from(vec![Value::from("x"), Value::from("y")])
+        );
+    }
+
+    #[test]
+    fn test_set_pointer_out_of_range() {
+        let mut value = sample();
+
+        assert_eq!(
+            value.set_pointer("/users/3", Value::Null),
+            Err(PointerError::IndexOutOfRange("/users/3".to_string()))
+        );
+        assert_eq!(
+            value.set_pointer("/users/99999999999", Value::Null),
+            Err(PointerError::IndexOutOfRange(
+                "/users/99999999999".to_string()
+            ))
+        );
+        assert_eq!(
+            value.set_pointer("/settings/tags/1", Value::Null),
+            Err(PointerError::IndexOutOfRange(
+                "/settings/tags/1".to_string()
+            ))
+        );
+        assert_eq!(
+            value.set_pointer("/created/-/name", Value::from("Ada")),
+            Ok(None)
+        );
+        assert_eq!(value["created"], value!([{"name": "Ada"}]));
+
+        let original = value.clone();
+        // Nothing is created when a later token fails.
+        assert_eq!(
+            value.set_pointer("/partial/list/2", Value::Null),
+            Err(PointerError::IndexOutOfRange("/partial/list/2".to_string()))
+        );
+        assert_eq!(
+            value.set_pointer("/users/-/1", Value::Null),
+            Err(PointerError::IndexOutOfRange("/users/-/1".to_string()))
+        );
+        assert_eq!(
+            value.set_pointer("/a~1b/c/d", Value::Null),
+            Err(PointerError::NotContainer("/a~1b/c".to_string()))
+        );
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn test_remove_pointer() {
+        let mut value = sample();
+
+        assert_eq!(
+            value.remove_pointer("/users/0"),
+            Ok(Value::from(vec![("name", "Ada")]))
+        );
+        assert_eq!(value.pointer("/users/0/name"), Ok(&Value::from("Alan")));
+        assert_eq!(value.remove_pointer("/m~0n"), Ok(Value::from(2)));
+        assert_eq!(
+            value.remove_pointer("/m~0n"),
+            Err(PointerError::MissingKey("/m~0n".to_string()))
+        );
+        assert_eq!(
+            value.remove_pointer("/users/3"),
+            Err(PointerError::IndexOutOfRange("/users/3".to_string()))
+        );
+    }
+}
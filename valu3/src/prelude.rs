@@ -1,17 +1,37 @@
 #![allow(unused_imports)]
 pub use crate::types::stringb::*;
 pub use crate::types::number::*;
+pub use crate::types::number_conversion::*;
+pub use crate::types::number_format::*;
+#[cfg(feature = "decimal")]
+pub use crate::types::decimal::*;
 pub use crate::types::array::*;
 pub use crate::types::object::*;
 pub use crate::types::datetime::*;
+pub use crate::types::hashable::*;
+pub use crate::types::ord_value::*;
 pub use crate::types::value_key::*;
 pub use crate::traits::*;
 pub use crate::to_value::*;
+pub use crate::value;
 pub use crate::to::json::*;
 pub use crate::to::yaml::*;
 pub use crate::value::*;
 pub use crate::Error;
 pub use crate::impls::*;
+pub use crate::index::ValueIndex;
+pub use crate::pointer::PointerError;
+pub use crate::patch::PatchError;
+pub use crate::diff::{Change, DiffReport};
+pub use crate::assert_value_eq;
+pub use crate::schema::{
+    ArrayStats, InferredSchema, JsonSchema, NumberStats, ObjectStats, SchemaBehavior, SchemaError,
+    SchemaResolver, StringStats, ValidationError,
+};
+pub use crate::merge::{ArrayMerge, MergeError, MergeOptions, NullMerge, TypeConflict};
+pub use crate::json_path::{JsonPath, JsonPathError, QueryMatch};
+#[cfg(feature = "parser")]
+pub use crate::parser::ParseOptions;
 #[cfg(feature = "cstring")]
 pub use std::ffi::CString;
 #[cfg(feature = "derive")]
@@ -0,0 +1,175 @@
+//! JSON Schemas describing Rust types, as produced by `#[derive(Schema)]`.
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// A trait for describing the values of a type as a JSON Schema.
+///
+/// The schema describes the `Value` produced by `ToValueBehavior` and accepted by
+/// `FromValueBehavior`, so it can be published as a contract or compiled with
+/// `JsonSchema::compile` to check documents before converting them.
+///
+/// # Example
+/// ```no_run
+/// use valu3::prelude::*;
+///
+/// #[derive(ToValue, FromValue, Schema)]
+/// #[valu3(rename_all = "camelCase")]
+/// struct User {
+///     user_name: String,
+///     age: Option<u8>,
+/// }
+///
+/// let schema = JsonSchema::compile(&User::schema()).unwrap();
+/// assert!(schema.is_valid(&value!({"userName": "ada"})));
+/// ```
+pub trait SchemaBehavior {
+    /// Returns the JSON Schema of the values of this type.
+    fn schema() -> Value;
+}
+
+/// Builds a schema object from its keywords.
+fn keywords(entries: Vec<(&str, Value)>) -> Value {
+    Value::from(entries)
+}
+
+macro_rules! integer_schema {
+    ($($ty:ty),*) => {
+        $(
+            impl SchemaBehavior for $ty {
+                fn schema() -> Value {
+                    keywords(vec![
+                        ("type", Value::from("integer")),
+                        ("minimum", Value::from(<$ty>::MIN)),
+                        ("maximum", Value::from(<$ty>::MAX)),
+                    ])
+                }
+            }
+        )*
+    };
+}
+
+integer_schema!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! simple_schema {
+    ($name:literal: $($ty:ty),*) => {
+        $(
+            impl SchemaBehavior for $ty {
+                fn schema() -> Value {
+                    keywords(vec![("type", Value::from($name))])
+                }
+            }
+        )*
+    };
+}
+
+simple_schema!("number": f32, f64);
+simple_schema!("string": str, &str, String);
+simple_schema!("boolean": bool);
+
+impl SchemaBehavior for Value {
+    fn schema() -> Value {
+        Value::Boolean(true)
+    }
+}
+
+impl<T> SchemaBehavior for Option<T>
+where
+    T: SchemaBehavior,
+{
+    fn schema() -> Value {
+        let null = keywords(vec![("type", Value::from("null"))]);
+        keywords(vec![(
+            "anyOf",
+            Value::Array(Array::from(vec![T::schema(), null])),
+        )])
+    }
+}
+
+impl<T> SchemaBehavior for Vec<T>
+where
+    T: SchemaBehavior,
+{
+    fn schema() -> Value {
+        keywords(vec![("type", Value::from("array")), ("items", T::schema())])
+    }
+}
+
+/// The schema of a map: an object whose members all follow the schema of `T`.
+fn map_schema<T: SchemaBehavior>() -> Value {
+    keywords(vec![
+        ("type", Value::from("object")),
+        ("additionalProperties", T::schema()),
+    ])
+}
+
+impl<T> SchemaBehavior for HashMap<String, T>
+where
+    T: SchemaBehavior,
+{
+    fn schema() -> Value {
+        map_schema::<T>()
+    }
+}
+
+impl<T> SchemaBehavior for BTreeMap<String, T>
+where
+    T: SchemaBehavior,
+{
+    fn schema() -> Value {
+        map_schema::<T>()
+    }
+}
+
+#[cfg(feature = "cstring")]
+impl<T> SchemaBehavior for HashMap<CString, T>
+where
+    T: SchemaBehavior,
+{
+    fn schema() -> Value {
+        map_schema::<T>()
+    }
+}
+
+#[cfg(feature = "cstring")]
+impl<T> SchemaBehavior for BTreeMap<CString, T>
+where
+    T: SchemaBehavior,
+{
+    fn schema() -> Value {
+        map_schema::<T>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_primitive_schemas() {
+        assert_eq!(
+            u8::schema(),
+            value!({"type": "integer", "minimum": 0, "maximum": 255})
+        );
+        assert_eq!(f64::schema(), value!({"type": "number"}));
+        assert_eq!(String::schema(), value!({"type": "string"}));
+        assert_eq!(bool::schema(), value!({"type": "boolean"}));
+        assert_eq!(Value::schema(), value!(true));
+    }
+
+    #[test]
+    fn test_collection_schemas() {
+        assert_eq!(
+            Vec::<Option<bool>>::schema(),
+            value!({
+                "type": "array",
+                "items": {"anyOf": [{"type": "boolean"}, {"type": "null"}]}
+            })
+        );
+
+        let schema = JsonSchema::compile(&BTreeMap::<String, i8>::schema()).unwrap();
+        assert!(schema.is_valid(&value!({"a": 1, "b": -128})));
+        assert!(!schema.is_valid(&value!({"a": 128})));
+        assert!(!schema.is_valid(&value!({"a": "1"})));
+    }
+}
@@ -0,0 +1,120 @@
+//! The `format` values checked by `JsonSchema`, other formats are accepted as annotations.
+use chrono::{DateTime as ChDateTime, NaiveDate};
+use regex::Regex;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::OnceLock;
+
+/// Checks a string against a format, `None` for formats without a check.
+pub(crate) fn check(format: &str, text: &str) -> Option<bool> {
+    let valid = match format {
+        "date-time" => is_date_time(text),
+        "date" => is_date(text),
+        "time" => is_date_time(&format!("1970-01-01T{}", text)),
+        "email" => is_email(text),
+        "uri" => is_uri(text),
+        "uri-reference" => is_uri_reference(text),
+        "uuid" => is_uuid(text),
+        "ipv4" => text.parse::<Ipv4Addr>().is_ok(),
+        "ipv6" => text.parse::<Ipv6Addr>().is_ok(),
+        _ => return None,
+    };
+    Some(valid)
+}
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("Unable to compile a format pattern"))
+}
+
+/// An RFC 3339 date and time with an offset, such as `2023-04-05T10:00:00Z`.
+fn is_date_time(text: &str) -> bool {
+    static SHAPE: OnceLock<Regex> = OnceLock::new();
+    let shape = regex(
+        &SHAPE,
+        r"^\d{4}-\d{2}-\d{2}[Tt]\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:\d{2})$",
+    );
+    shape.is_match(text) && ChDateTime::parse_from_rfc3339(text).is_ok()
+}
+
+/// An RFC 3339 full date, such as `2023-04-05`.
+fn is_date(text: &str) -> bool {
+    static SHAPE: OnceLock<Regex> = OnceLock::new();
+    regex(&SHAPE, r"^\d{4}-\d{2}-\d{2}$").is_match(text)
+        && NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok()
+}
+
+/// An address such as `name@example.com`, with a dot-atom local part and a host name.
+fn is_email(text: &str) -> bool {
+    static SHAPE: OnceLock<Regex> = OnceLock::new();
+    let shape = regex(
+        &SHAPE,
+        r"^[A-Za-z0-9!#$%&'*+/=?^_`{|}~-]+(\.[A-Za-z0-9!#$%&'*+/=?^_`{|}~-]+)*@[A-Za-z0-9]([A-Za-z0-9-]*[A-Za-z0-9])?(\.[A-Za-z0-9]([A-Za-z0-9-]*[A-Za-z0-9])?)*$",
+    );
+    shape.is_match(text)
+}
+
+/// An absolute RFC 3986 URI, which starts with a scheme.
+fn is_uri(text: &str) -> bool {
+    static SCHEME: OnceLock<Regex> = OnceLock::new();
+    regex(&SCHEME, r"^[A-Za-z][A-Za-z0-9+.-]*:").is_match(text) && is_uri_reference(text)
+}
+
+/// An RFC 3986 URI or relative reference: allowed characters and valid percent escapes.
+fn is_uri_reference(text: &str) -> bool {
+    static SHAPE: OnceLock<Regex> = OnceLock::new();
+    let shape = regex(
+        &SHAPE,
+        r"^([A-Za-z0-9\-._~:/?#\[\]@!$&'()*+,;=]|%[0-9A-Fa-f]{2})*$",
+    );
+    shape.is_match(text)
+}
+
+/// An RFC 4122 UUID in its hyphenated form.
+fn is_uuid(text: &str) -> bool {
+    static SHAPE: OnceLock<Regex> = OnceLock::new();
+    let shape = regex(
+        &SHAPE,
+        r"^[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}$",
+    );
+    shape.is_match(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check;
+
+    #[test]
+    fn test_formats() {
+        let valid = [
+            ("date-time", "2023-04-05T10:20:30.5+02:00"),
+            ("date", "2024-02-29"),
+            ("time", "23:59:59Z"),
+            ("email", "ada.lovelace+test@example.co.uk"),
+            ("uri", "https://example.com/a?b=1#c"),
+            ("uri-reference", "../a%20b"),
+            ("uuid", "123e4567-e89b-12d3-a456-426614174000"),
+            ("ipv4", "192.168.0.1"),
+            ("ipv6", "::1"),
+        ];
+        let invalid = [
+            ("date-time", "2023-04-05 10:20:30"),
+            ("date-time", "2023-02-30T10:20:30Z"),
+            ("date", "2023-2-5"),
+            ("time", "24:00:00Z"),
+            ("email", "ada@"),
+            ("email", ".ada@example.com"),
+            ("uri", "example.com/a"),
+            ("uri", "https://exa mple.com"),
+            ("uuid", "123e4567e89b12d3a456426614174000"),
+            ("ipv4", "256.0.0.1"),
+            ("ipv6", "1:::2"),
+        ];
+
+        for (format, text) in valid {
+            assert_eq!(check(format, text), Some(true), "{} {}", format, text);
+        }
+        for (format, text) in invalid {
+            assert_eq!(check(format, text), Some(false), "{} {}", format, text);
+        }
+        assert_eq!(check("hostname", "anything"), None);
+    }
+}
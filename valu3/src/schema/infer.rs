@@ -0,0 +1,442 @@
+//! Schemas inferred from sample values, to document payloads that come without one.
+//!
+//! Every sample is observed into an `InferredSchema`, which keeps for each path the kinds of
+//! values seen there: how many nulls and booleans, the range of the numbers and whether they
+//! were integers or floats, the formats of the strings, the lengths and items of the arrays
+//! and the members of the objects. `InferredSchema::to_schema` exports it as a JSON Schema
+//! that accepts every sample.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let samples = vec![
+//!     value!({"id": 1, "email": "ada@example.com", "tags": ["a"]}),
+//!     value!({"id": 2.5, "email": null, "tags": []}),
+//!     value!({"id": 3, "created": "2023-04-05T10:00:00Z"}),
+//! ];
+//!
+//! let inferred = InferredSchema::from_samples(&samples);
+//! let id = inferred.get("/id").unwrap();
+//! assert_eq!(id.numbers.as_ref().unwrap().floats, 1);
+//! assert!(inferred.get("/email").unwrap().is_nullable());
+//! assert_eq!(inferred.objects.as_ref().unwrap().required(), vec!["id"]);
+//!
+//! let schema = JsonSchema::compile(&inferred.to_schema()).unwrap();
+//! assert!(samples.iter().all(|sample| schema.is_valid(sample)));
+//! ```
+use super::{format, text};
+use crate::pointer::escape;
+use crate::prelude::*;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// The string formats recognized in samples, the first matching one being recorded.
+const FORMATS: [&str; 8] = [
+    "date-time",
+    "date",
+    "time",
+    "email",
+    "uuid",
+    "ipv4",
+    "ipv6",
+    "uri",
+];
+
+/// The values observed at a path of the samples.
+///
+/// `Value::Undefined` is not observed, so an undefined member counts as a missing one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InferredSchema {
+    /// The number of values observed at this path.
+    pub samples: usize,
+    pub nulls: usize,
+    pub booleans: usize,
+    pub numbers: Option<NumberStats>,
+    pub strings: Option<StringStats>,
+    pub arrays: Option<ArrayStats>,
+    pub objects: Option<ObjectStats>,
+}
+
+/// The numbers observed at a path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberStats {
+    pub count: usize,
+    /// The numbers stored as integers, the others being floats or decimals.
+    pub integers: usize,
+    pub floats: usize,
+    /// The smallest and largest numbers, NaN being left out.
+    pub minimum: Option<Number>,
+    pub maximum: Option<Number>,
+}
+
+/// The strings observed at a path, `DateTime` values included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StringStats {
+    pub count: usize,
+    /// The number of strings matching each recognized format.
+    pub formats: BTreeMap<String, usize>,
+}
+
+/// The arrays observed at a path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayStats {
+    pub count: usize,
+    pub min_items: usize,
+    pub max_items: usize,
+    /// The elements of every array, observed together.
+    pub items: Box<InferredSchema>,
+}
+
+/// The objects observed at a path.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectStats {
+    pub count: usize,
+    /// The members of every object, a member being observed once for each object holding it.
+    pub properties: BTreeMap<String, InferredSchema>,
+}
+
+impl ObjectStats {
+    /// Returns the keys present in every object.
+    pub fn required(&self) -> Vec<&str> {
+        self.properties
+            .iter()
+            .filter(|(_, property)| property.samples == self.count)
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+}
+
+impl StringStats {
+    /// Returns the format matched by every string, if any.
+    pub fn format(&self) -> Option<&str> {
+        self.formats
+            .iter()
+            .find(|(_, count)| **count == self.count)
+            .map(|(format, _)| format.as_str())
+    }
+}
+
+impl InferredSchema {
+    /// Infers the schema of a set of samples.
+    pub fn from_samples<'a, I>(samples: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Value>,
+    {
+        let mut inferred = InferredSchema::default();
+        for sample in samples {
+            inferred.observe(sample);
+        }
+        inferred
+    }
+
+    /// Adds a sample to the observed values.
+    pub fn observe(&mut self, value: &Value) {
+        if let Value::Undefined = value {
+            return;
+        }
+        self.samples += 1;
+
+        match value {
+            Value::Null => self.nulls += 1,
+            Value::Boolean(_) => self.booleans += 1,
+            Value::Number(number) => {
+                let stats = self.numbers.get_or_insert(NumberStats {
+                    count: 0,
+                    integers: 0,
+                    floats: 0,
+                    minimum: None,
+                    maximum: None,
+                });
+                stats.count += 1;
+                if number.is_integer() {
+                    stats.integers += 1;
+                } else {
+                    stats.floats += 1;
+                }
+                let beyond = |bound: &Option<Number>, ordering| match bound {
+                    Some(bound) => number.partial_cmp(bound) == Some(ordering),
+                    None => !number.is_nan(),
+                };
+                if beyond(&stats.minimum, Ordering::Less) {
+                    stats.minimum = Some(number.clone());
+                }
+                if beyond(&stats.maximum, Ordering::Greater) {
+                    stats.maximum = Some(number.clone());
+                }
+            }
+            Value::String(_) | Value::DateTime(_) => {
+                let stats = self.strings.get_or_insert_with(StringStats::default);
+                stats.count += 1;
+                let text = text(value).unwrap_or_default();
+                let matched = FORMATS
+                    .iter()
+                    .find(|name| format::check(name, &text) == Some(true));
+                if let Some(name) = matched {
+                    *stats.formats.entry(name.to_string()).or_insert(0) += 1;
+                }
+            }
+            Value::Array(array) => {
+                let length = array.len();
+                let stats = self.arrays.get_or_insert_with(|| ArrayStats {
+                    count: 0,
+                    min_items: length,
+                    max_items: length,
+                    items: Box::default(),
+                });
+                stats.count += 1;
+                stats.min_items = stats.min_items.min(length);
+                stats.max_items = stats.max_items.max(length);
+                for item in array.into_iter() {
+                    stats.items.observe(item);
+                }
+            }
+            Value::Object(object) => {
+                let stats = self.objects.get_or_insert_with(ObjectStats::default);
+                stats.count += 1;
+                for (key, member) in object.iter() {
+                    if let Value::Undefined = member {
+                        continue;
+                    }
+                    stats
+                        .properties
+                        .entry(key.to_string())
+                        .or_default()
+                        .observe(member);
+                }
+            }
+            Value::Undefined => {}
+        }
+    }
+
+    /// Returns `true` when null was observed at this path.
+    pub fn is_nullable(&self) -> bool {
+        self.nulls > 0
+    }
+
+    /// Returns the JSON Schema types observed at this path, `integer` standing for numbers
+    /// that were all integers.
+    pub fn types(&self) -> Vec<&'static str> {
+        let mut types = Vec::new();
+        if self.nulls > 0 {
+            types.push("null");
+        }
+        if self.booleans > 0 {
+            types.push("boolean");
+        }
+        if let Some(numbers) = &self.numbers {
+            types.push(if numbers.floats == 0 {
+                "integer"
+            } else {
+                "number"
+            });
+        }
+        if self.strings.is_some() {
+            types.push("string");
+        }
+        if self.arrays.is_some() {
+            types.push("array");
+        }
+        if self.objects.is_some() {
+            types.push("object");
+        }
+        types
+    }
+
+    /// Returns the values observed at a JSON Pointer, where the token `*` stands for the
+    /// elements of an array.
+    pub fn get(&self, path: &str) -> Option<&InferredSchema> {
+        self.paths()
+            .into_iter()
+            .find(|(candidate, _)| candidate == path)
+            .map(|(_, inferred)| inferred)
+    }
+
+    /// Lists every observed path with its values, depth first from the root `""`.
+    ///
+    /// Paths are JSON Pointers, with the token `*` for the elements of an array.
+    pub fn paths(&self) -> Vec<(String, &InferredSchema)> {
+        let mut paths = Vec::new();
+        self.collect_paths(String::new(), &mut paths);
+        paths
+    }
+
+    fn collect_paths<'s>(&'s self, path: String, paths: &mut Vec<(String, &'s InferredSchema)>) {
+        paths.push((path.clone(), self));
+        if let Some(objects) = &self.objects {
+            for (key, property) in &objects.properties {
+                property.collect_paths(format!("{}/{}", path, escape(key)), paths);
+            }
+        }
+        if let Some(arrays) = &self.arrays {
+            arrays.items.collect_paths(format!("{}/*", path), paths);
+        }
+    }
+
+    /// Exports the observed values as a JSON Schema, draft 2020-12, accepting every sample.
+    ///
+    /// Each kind of value becomes an alternative of `anyOf`, or the whole schema when only
+    /// one kind was observed. Numbers keep their range, strings the format matched by all
+    /// of them, arrays the schema of their items and objects the keys present in all of
+    /// them as `required`. A path where nothing was observed accepts any value.
+    pub fn to_schema(&self) -> Value {
+        let mut schema = match self.schema() {
+            Value::Object(object) => object,
+            other => return other,
+        };
+        schema.insert(
+            "$schema",
+            Value::from("https://json-schema.org/draft/2020-12/schema"),
+        );
+        Value::Object(schema)
+    }
+
+    fn schema(&self) -> Value {
+        if self.samples == 0 {
+            return Value::Boolean(true);
+        }
+
+        let kind = |name: &str| vec![("type", Value::from(name))];
+        let mut alternatives = Vec::new();
+
+        if self.nulls > 0 {
+            alternatives.push(kind("null"));
+        }
+        if self.booleans > 0 {
+            alternatives.push(kind("boolean"));
+        }
+        if let Some(numbers) = &self.numbers {
+            let mut schema = kind(if numbers.floats == 0 {
+                "integer"
+            } else {
+                "number"
+            });
+            if let Some(minimum) = &numbers.minimum {
+                schema.push(("minimum", Value::Number(minimum.clone())));
+            }
+            if let Some(maximum) = &numbers.maximum {
+                schema.push(("maximum", Value::Number(maximum.clone())));
+            }
+            alternatives.push(schema);
+        }
+        if let Some(strings) = &self.strings {
+            let mut schema = kind("string");
+            if let Some(format) = strings.format() {
+                schema.push(("format", Value::from(format)));
+            }
+            alternatives.push(schema);
+        }
+        if let Some(arrays) = &self.arrays {
+            let mut schema = kind("array");
+            if arrays.items.samples > 0 {
+                schema.push(("items", arrays.items.schema()));
+            }
+            alternatives.push(schema);
+        }
+        if let Some(objects) = &self.objects {
+            let mut properties = Object::default();
+            for (key, property) in &objects.properties {
+                properties.insert(key.as_str(), property.schema());
+            }
+            let mut schema = kind("object");
+            schema.push(("properties", Value::Object(properties)));
+            let required = objects.required();
+            if !required.is_empty() {
+                let required = required.into_iter().map(Value::from).collect::<Vec<_>>();
+                schema.push(("required", Value::Array(Array::from(required))));
+            }
+            alternatives.push(schema);
+        }
+
+        let mut alternatives = alternatives
+            .into_iter()
+            .map(Value::from)
+            .collect::<Vec<_>>();
+        if alternatives.len() == 1 {
+            return alternatives.remove(0);
+        }
+        Value::from(vec![("anyOf", Value::Array(Array::from(alternatives)))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_observed_values() {
+        let samples = vec![
+            value!({"id": 1, "email": "ada@example.com", "tags": ["a"]}),
+            value!({"id": 2.5, "email": null, "tags": []}),
+            value!({"id": -3, "created": "2023-04-05T10:00:00Z", "tags": ["b", "c"]}),
+        ];
+        let inferred = InferredSchema::from_samples(&samples);
+
+        let paths = inferred
+            .paths()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec!["", "/created", "/email", "/id", "/tags", "/tags/*"]
+        );
+
+        let id = inferred.get("/id").unwrap();
+        let numbers = id.numbers.as_ref().unwrap();
+        assert_eq!((numbers.integers, numbers.floats), (2, 1));
+        assert_eq!(numbers.minimum, Some(Number::from(-3)));
+        assert_eq!(numbers.maximum, Some(Number::from(2.5)));
+        assert_eq!(id.types(), vec!["number"]);
+
+        let email = inferred.get("/email").unwrap();
+        assert!(email.is_nullable());
+        assert_eq!(email.types(), vec!["null", "string"]);
+        assert_eq!(email.strings.as_ref().unwrap().format(), Some("email"));
+
+        let created = inferred.get("/created").unwrap();
+        assert_eq!(created.samples, 1);
+        assert_eq!(
+            created.strings.as_ref().unwrap().format(),
+            Some("date-time")
+        );
+
+        let tags = inferred.get("/tags").unwrap().arrays.as_ref().unwrap();
+        assert_eq!((tags.min_items, tags.max_items), (0, 2));
+        assert_eq!(tags.items.types(), vec!["string"]);
+
+        let objects = inferred.objects.as_ref().unwrap();
+        assert_eq!(objects.required(), vec!["id", "tags"]);
+        assert_eq!(inferred.get("/missing"), None);
+    }
+
+    #[test]
+    fn test_to_schema() {
+        let samples = vec![
+            value!({"id": "123e4567-e89b-12d3-a456-426614174000", "count": 3, "items": [1, "a"]}),
+            value!({"id": "123e4567-e89b-12d3-a456-426614174001", "count": 10, "items": []}),
+        ];
+        let inferred = InferredSchema::from_samples(&samples);
+
+        assert_eq!(
+            inferred.to_schema(),
+            value!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "count": {"type": "integer", "minimum": 3, "maximum": 10},
+                    "id": {"type": "string", "format": "uuid"},
+                    "items": {
+                        "type": "array",
+                        "items": {"anyOf": [{"type": "integer", "minimum": 1, "maximum": 1}, {"type": "string"}]}
+                    }
+                },
+                "required": ["count", "id", "items"]
+            })
+        );
+
+        let schema = JsonSchema::compile(&inferred.to_schema()).unwrap();
+        assert!(samples.iter().all(|sample| schema.is_valid(sample)));
+        assert!(!schema.is_valid(&value!({"id": "x", "count": 3, "items": []})));
+        assert_eq!(InferredSchema::default().to_schema(), value!(true));
+    }
+}
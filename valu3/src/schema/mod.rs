@@ -0,0 +1,1554 @@
+//! JSON Schema, draft 2020-12, to validate documents against a schema `Value`.
+//!
+//! A schema is compiled once with `JsonSchema::compile`, which checks its keywords, compiles
+//! its patterns and resolves its references, then validates any number of documents. Every
+//! failure is collected as a `ValidationError` with the JSON Pointer of the instance and of
+//! the failing schema keyword.
+//!
+//! References are resolved against `$id`, `$anchor` and JSON Pointer fragments within the
+//! schema. Nothing is fetched: a reference to another document fails to compile unless
+//! `JsonSchema::compile_with` is given a `SchemaResolver` for it. `$dynamicRef` and
+//! `$dynamicAnchor` are read as `$ref` and `$anchor`.
+//!
+//! The formats `date-time`, `date`, `time`, `email`, `uri`, `uri-reference`, `uuid`, `ipv4`
+//! and `ipv6` are checked, other formats are accepted. Patterns use the syntax of the
+//! `regex` crate, close to the ECMA-262 syntax of the specification for common patterns.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let schema = JsonSchema::compile(&value!({
+//!     "type": "object",
+//!     "required": ["id", "email"],
+//!     "properties": {
+//!         "id": {"type": "integer", "minimum": 1},
+//!         "email": {"type": "string", "format": "email"},
+//!         "tags": {"type": "array", "items": {"$ref": "#/$defs/tag"}}
+//!     },
+//!     "$defs": {"tag": {"type": "string", "maxLength": 16}}
+//! }))
+//! .unwrap();
+//!
+//! assert!(schema.is_valid(&value!({"id": 1, "email": "ada@example.com"})));
+//!
+//! let errors = schema.validate(&value!({"id": 0, "tags": [1]})).unwrap_err();
+//! assert_eq!(errors.len(), 3);
+//! assert_eq!(errors[0].schema_path, "/required");
+//! assert_eq!(errors[1].instance_path, "/id");
+//! assert_eq!(errors[1].schema_path, "/properties/id/minimum");
+//! ```
+mod describe;
+mod format;
+mod infer;
+
+use crate::pointer::escape;
+use crate::prelude::*;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+pub use describe::SchemaBehavior;
+pub use infer::{ArrayStats, InferredSchema, NumberStats, ObjectStats, StringStats};
+
+/// References are followed at most this many times in a row at the same instance location,
+/// so that a schema referring to itself without moving into the instance fails instead of
+/// overflowing the stack. Moving into a member or an element starts the count again.
+const MAX_DEPTH: usize = 256;
+
+/// Provides the documents of references to other schemas, by their absolute URI without
+/// fragment.
+///
+/// Closures taking a `&str` and returning an `Option<Value>` are resolvers.
+pub trait SchemaResolver {
+    fn resolve(&self, uri: &str) -> Option<Value>;
+}
+
+impl<F> SchemaResolver for F
+where
+    F: Fn(&str) -> Option<Value>,
+{
+    fn resolve(&self, uri: &str) -> Option<Value> {
+        self(uri)
+    }
+}
+
+/// A schema that could not be compiled, with the JSON Pointer of the problem in the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    pub schema_path: String,
+    pub message: String,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {:?}", self.message, self.schema_path)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// A failed check of a document.
+///
+/// `instance_path` points into the document, and `schema_path` to the keyword that failed,
+/// following references as `$ref` in the path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub instance_path: String,
+    pub schema_path: String,
+    pub message: String,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {:?} (schema {:?})",
+            self.message, self.instance_path, self.schema_path
+        )
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Where a schema is: a document and a JSON Pointer in it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Location {
+    document: usize,
+    pointer: String,
+}
+
+/// A compiled JSON Schema, see the module documentation.
+#[derive(Debug, Clone)]
+pub struct JsonSchema {
+    /// The schema first, then the documents provided by the resolver.
+    documents: Vec<Value>,
+    /// The locations of resources by URI, and of anchors by URI with a `#name` fragment.
+    locations: HashMap<String, Location>,
+    /// The base URI of each schema, after its `$id`.
+    bases: HashMap<Location, String>,
+    patterns: HashMap<String, Regex>,
+}
+
+impl JsonSchema {
+    /// Compiles a schema whose references all point within it.
+    pub fn compile(schema: &Value) -> Result<JsonSchema, SchemaError> {
+        Compiler::new(None).compile(schema)
+    }
+
+    /// Compiles a schema, asking `resolver` for the documents of references to other schemas.
+    pub fn compile_with(
+        schema: &Value,
+        resolver: &dyn SchemaResolver,
+    ) -> Result<JsonSchema, SchemaError> {
+        Compiler::new(Some(resolver)).compile(schema)
+    }
+
+    /// Validates a document, returning every error found.
+    pub fn validate(&self, instance: &Value) -> Result<(), Vec<ValidationError>> {
+        let root = Location {
+            document: 0,
+            pointer: String::new(),
+        };
+        let mut errors = Vec::new();
+        let context = Context {
+            base: &self.bases[&root],
+            instance_path: String::new(),
+            schema_path: String::new(),
+            depth: 0,
+        };
+        self.node(&self.documents[0], instance, &context, &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        self.validate(instance).is_ok()
+    }
+}
+
+impl Value {
+    /// Validates this value against a compiled schema, see `JsonSchema::validate`.
+    pub fn validate(&self, schema: &JsonSchema) -> Result<(), Vec<ValidationError>> {
+        schema.validate(self)
+    }
+}
+
+/// Resolves a URI reference against a base URI, both possibly relative.
+fn resolve_uri(base: &str, reference: &str) -> String {
+    let base = base.split('#').next().unwrap_or_default();
+    let has_scheme = |uri: &str| {
+        uri.split_once(':').is_some_and(|(scheme, _)| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-'))
+        })
+    };
+
+    if reference.is_empty() {
+        return base.to_string();
+    }
+    if reference.starts_with('#') {
+        return format!("{}{}", base, reference);
+    }
+    if has_scheme(reference) {
+        return reference.to_string();
+    }
+
+    let scheme_end = if has_scheme(base) {
+        base.find(':').map_or(0, |index| index + 1)
+    } else {
+        0
+    };
+    if reference.starts_with("//") {
+        return format!("{}{}", &base[..scheme_end], reference);
+    }
+
+    let authority_end = match base[scheme_end..].strip_prefix("//") {
+        Some(rest) => scheme_end + 2 + rest.find('/').unwrap_or(rest.len()),
+        None => scheme_end,
+    };
+    if reference.starts_with('/') {
+        return format!("{}{}", &base[..authority_end], reference);
+    }
+
+    let directory = base[authority_end..]
+        .rfind('/')
+        .map_or(authority_end, |index| authority_end + index + 1);
+    let path = format!("{}{}", &base[authority_end..directory], reference);
+
+    // Removes the `.` and `..` segments of the merged path.
+    let mut segments: Vec<&str> = Vec::new();
+    let parts: Vec<&str> = path.split('/').collect();
+    for (index, segment) in parts.iter().enumerate() {
+        let last = index == parts.len() - 1;
+        match *segment {
+            "." if last => segments.push(""),
+            "." => {}
+            ".." => {
+                if segments.len() > 1 || segments.first().is_some_and(|first| !first.is_empty()) {
+                    segments.pop();
+                }
+                if last {
+                    segments.push("");
+                }
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    format!("{}{}", &base[..authority_end], segments.join("/"))
+}
+
+/// Decodes the `%XX` escapes of a URI fragment.
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = text.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+/// The base URI of a schema, its `$id` resolved against the base of its parent.
+fn schema_base(parent: &str, schema: &Value) -> String {
+    let id = match schema {
+        Value::Object(object) => object.get("$id"),
+        _ => None,
+    };
+    match id {
+        Some(Value::String(id)) => resolve_uri(parent, id.as_str())
+            .trim_end_matches('#')
+            .to_string(),
+        _ => parent.split('#').next().unwrap_or_default().to_string(),
+    }
+}
+
+/// Keywords holding a single subschema.
+const SCHEMA_KEYWORDS: [&str; 10] = [
+    "additionalProperties",
+    "propertyNames",
+    "items",
+    "contains",
+    "not",
+    "if",
+    "then",
+    "else",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+];
+
+/// Keywords holding an array of subschemas.
+const ARRAY_KEYWORDS: [&str; 4] = ["allOf", "anyOf", "oneOf", "prefixItems"];
+
+/// Keywords holding an object of subschemas.
+const OBJECT_KEYWORDS: [&str; 5] = [
+    "properties",
+    "patternProperties",
+    "$defs",
+    "definitions",
+    "dependentSchemas",
+];
+
+const TYPES: [&str; 7] = [
+    "null", "boolean", "object", "array", "number", "string", "integer",
+];
+
+struct Compiler<'r> {
+    resolver: Option<&'r dyn SchemaResolver>,
+    schema: JsonSchema,
+    /// The references found, with the pointer of their keyword.
+    references: Vec<(String, String)>,
+}
+
+impl<'r> Compiler<'r> {
+    fn new(resolver: Option<&'r dyn SchemaResolver>) -> Self {
+        Compiler {
+            resolver,
+            schema: JsonSchema {
+                documents: Vec::new(),
+                locations: HashMap::new(),
+                bases: HashMap::new(),
+                patterns: HashMap::new(),
+            },
+            references: Vec::new(),
+        }
+    }
+
+    fn compile(mut self, schema: &Value) -> Result<JsonSchema, SchemaError> {
+        self.add_document(schema.clone(), "")?;
+
+        let mut index = 0;
+        while index < self.references.len() {
+            let (uri, schema_path) = self.references[index].clone();
+            self.check_reference(&uri, &schema_path)?;
+            index += 1;
+        }
+
+        Ok(self.schema)
+    }
+
+    fn add_document(&mut self, document: Value, uri: &str) -> Result<(), SchemaError> {
+        let index = self.schema.documents.len();
+        self.schema.locations.insert(
+            uri.to_string(),
+            Location {
+                document: index,
+                pointer: String::new(),
+            },
+        );
+        self.schema.documents.push(document.clone());
+        self.scan(&document, index, String::new(), uri)
+    }
+
+    fn check_reference(&mut self, uri: &str, schema_path: &str) -> Result<(), SchemaError> {
+        let error = |message: String| SchemaError {
+            schema_path: schema_path.to_string(),
+            message,
+        };
+        let (resource, fragment) = uri.split_once('#').unwrap_or((uri, ""));
+
+        if !self.schema.locations.contains_key(resource) {
+            let document = self
+                .resolver
+                .and_then(|resolver| resolver.resolve(resource))
+                .ok_or_else(|| error(format!("Unable to resolve the reference {:?}", uri)))?;
+            self.add_document(document, resource)?;
+        }
+
+        let found = if fragment.is_empty() || fragment.starts_with('/') {
+            self.schema.find(uri).is_some()
+        } else {
+            self.schema.locations.contains_key(uri)
+        };
+        if found {
+            Ok(())
+        } else {
+            Err(error(format!("Unable to find the target of {:?}", uri)))
+        }
+    }
+
+    /// Registers the identifiers, anchors, references and patterns of a schema and its
+    /// subschemas, checking their keywords.
+    fn scan(
+        &mut self,
+        schema: &Value,
+        document: usize,
+        pointer: String,
+        parent: &str,
+    ) -> Result<(), SchemaError> {
+        let error = |path: &str, message: &str| SchemaError {
+            schema_path: format!("{}{}", pointer, path),
+            message: message.to_string(),
+        };
+
+        let object = match schema {
+            Value::Boolean(_) => {
+                let location = Location { document, pointer };
+                self.schema.bases.insert(location, parent.to_string());
+                return Ok(());
+            }
+            Value::Object(object) => object,
+            _ => return Err(error("", "A schema must be an object or a boolean")),
+        };
+
+        let base = schema_base(parent, schema);
+        let location = Location {
+            document,
+            pointer: pointer.clone(),
+        };
+        if let Some(id) = object.get("$id") {
+            if !matches!(id, Value::String(_)) {
+                return Err(error("/$id", "Expected a string"));
+            }
+            self.schema.locations.insert(base.clone(), location.clone());
+        }
+        self.schema.bases.insert(location.clone(), base.clone());
+
+        for keyword in ["$anchor", "$dynamicAnchor"] {
+            match object.get(keyword) {
+                Some(Value::String(anchor)) => {
+                    let uri = format!("{}#{}", base, anchor.as_str());
+                    self.schema.locations.insert(uri, location.clone());
+                }
+                Some(_) => return Err(error(&format!("/{}", keyword), "Expected a string")),
+                None => {}
+            }
+        }
+        for keyword in ["$ref", "$dynamicRef"] {
+            match object.get(keyword) {
+                Some(Value::String(reference)) => {
+                    let uri = resolve_uri(&base, reference.as_str());
+                    self.references
+                        .push((uri, format!("{}/{}", pointer, keyword)));
+                }
+                Some(_) => return Err(error(&format!("/{}", keyword), "Expected a string")),
+                None => {}
+            }
+        }
+
+        self.check_keywords(object, &pointer)?;
+
+        for keyword in SCHEMA_KEYWORDS {
+            if let Some(subschema) = object.get(keyword) {
+                self.scan(
+                    subschema,
+                    document,
+                    format!("{}/{}", pointer, keyword),
+                    &base,
+                )?;
+            }
+        }
+        for keyword in ARRAY_KEYWORDS {
+            match object.get(keyword) {
+                Some(Value::Array(subschemas)) if !subschemas.is_empty() => {
+                    for (index, subschema) in subschemas.into_iter().enumerate() {
+                        let path = format!("{}/{}/{}", pointer, keyword, index);
+                        self.scan(subschema, document, path, &base)?;
+                    }
+                }
+                Some(_) => {
+                    return Err(error(
+                        &format!("/{}", keyword),
+                        "Expected a non-empty array of schemas",
+                    ))
+                }
+                None => {}
+            }
+        }
+        for keyword in OBJECT_KEYWORDS {
+            match object.get(keyword) {
+                Some(Value::Object(subschemas)) => {
+                    for (key, subschema) in subschemas.iter() {
+                        let key = key.to_string();
+                        if keyword == "patternProperties" {
+                            self.pattern(&key, &format!("{}/{}", pointer, keyword))?;
+                        }
+                        let path = format!("{}/{}/{}", pointer, keyword, escape(&key));
+                        self.scan(subschema, document, path, &base)?;
+                    }
+                }
+                Some(_) => {
+                    return Err(error(
+                        &format!("/{}", keyword),
+                        "Expected an object of schemas",
+                    ))
+                }
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn pattern(&mut self, pattern: &str, schema_path: &str) -> Result<(), SchemaError> {
+        if !self.schema.patterns.contains_key(pattern) {
+            let regex = Regex::new(pattern).map_err(|_| SchemaError {
+                schema_path: schema_path.to_string(),
+                message: format!("Invalid regular expression {:?}", pattern),
+            })?;
+            self.schema.patterns.insert(pattern.to_string(), regex);
+        }
+        Ok(())
+    }
+
+    /// Checks the values of the assertion keywords.
+    fn check_keywords(&mut self, object: &Object, pointer: &str) -> Result<(), SchemaError> {
+        let error = |keyword: &str, message: &str| SchemaError {
+            schema_path: format!("{}/{}", pointer, keyword),
+            message: message.to_string(),
+        };
+        let is_count = |value: &Value| match value {
+            Value::Number(number) => {
+                matches!(
+                    number.widen(),
+                    Some(Widened::Unsigned(_)) | Some(Widened::Signed(0..))
+                ) || matches!(number.widen(), Some(Widened::Float(f)) if f >= 0.0 && f.fract() == 0.0)
+            }
+            _ => false,
+        };
+
+        match object.get("type") {
+            Some(Value::String(name)) if TYPES.contains(&name.as_str()) => {}
+            Some(Value::Array(names))
+                if names.into_iter().all(
+                    |name| matches!(name, Value::String(name) if TYPES.contains(&name.as_str())),
+                ) => {}
+            Some(_) => return Err(error("type", "Expected a type name or an array of them")),
+            None => {}
+        }
+
+        if let Some(value) = object.get("enum") {
+            if !matches!(value, Value::Array(_)) {
+                return Err(error("enum", "Expected an array"));
+            }
+        }
+
+        for keyword in ["maximum", "exclusiveMaximum", "minimum", "exclusiveMinimum"] {
+            if let Some(value) = object.get(keyword) {
+                if !matches!(value, Value::Number(_)) {
+                    return Err(error(keyword, "Expected a number"));
+                }
+            }
+        }
+        if let Some(value) = object.get("multipleOf") {
+            if !matches!(value, Value::Number(number) if to_f64(number).is_some_and(|f| f > 0.0)) {
+                return Err(error("multipleOf", "Expected a number greater than zero"));
+            }
+        }
+
+        for keyword in [
+            "maxLength",
+            "minLength",
+            "maxItems",
+            "minItems",
+            "maxContains",
+            "minContains",
+            "maxProperties",
+            "minProperties",
+        ] {
+            if let Some(value) = object.get(keyword) {
+                if !is_count(value) {
+                    return Err(error(keyword, "Expected a non-negative integer"));
+                }
+            }
+        }
+
+        if let Some(value) = object.get("uniqueItems") {
+            if !matches!(value, Value::Boolean(_)) {
+                return Err(error("uniqueItems", "Expected a boolean"));
+            }
+        }
+
+        match object.get("pattern") {
+            Some(Value::String(pattern)) => {
+                self.pattern(pattern.as_str(), &format!("{}/pattern", pointer))?
+            }
+            Some(_) => return Err(error("pattern", "Expected a string")),
+            None => {}
+        }
+        if let Some(value) = object.get("format") {
+            if !matches!(value, Value::String(_)) {
+                return Err(error("format", "Expected a string"));
+            }
+        }
+
+        let is_names = |value: &Value| match value {
+            Value::Array(names) => names
+                .into_iter()
+                .all(|name| matches!(name, Value::String(_))),
+            _ => false,
+        };
+        if let Some(value) = object.get("required") {
+            if !is_names(value) {
+                return Err(error("required", "Expected an array of strings"));
+            }
+        }
+        match object.get("dependentRequired") {
+            Some(Value::Object(dependencies))
+                if dependencies.iter().all(|(_, names)| is_names(names)) => {}
+            Some(_) => {
+                return Err(error(
+                    "dependentRequired",
+                    "Expected an object of arrays of strings",
+                ))
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+fn to_f64(number: &Number) -> Option<f64> {
+    match number.widen()? {
+        Widened::Signed(value) => Some(value as f64),
+        Widened::Unsigned(value) => Some(value as f64),
+        Widened::Float(value) => Some(value),
+    }
+}
+
+fn is_integral(number: &Number) -> bool {
+    match number.widen() {
+        Some(Widened::Signed(_) | Widened::Unsigned(_)) => true,
+        Some(Widened::Float(value)) => value.is_finite() && value.fract() == 0.0,
+        None => false,
+    }
+}
+
+fn is_multiple_of(number: &Number, divisor: &Number) -> bool {
+    let as_i128 = |widened| match widened {
+        Some(Widened::Signed(value)) => Some(value),
+        Some(Widened::Unsigned(value)) => i128::try_from(value).ok(),
+        _ => None,
+    };
+    if let (Some(value), Some(divisor)) = (as_i128(number.widen()), as_i128(divisor.widen())) {
+        return value % divisor == 0;
+    }
+
+    match (to_f64(number), to_f64(divisor)) {
+        (Some(value), Some(divisor)) => {
+            let quotient = value / divisor;
+            quotient.is_finite()
+                && (quotient - quotient.round()).abs() <= f64::EPSILON * quotient.abs().max(1.0)
+        }
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Boolean(_) => "boolean",
+        Value::Number(number) if is_integral(number) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) | Value::DateTime(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+        Value::Undefined => "undefined",
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    let actual = type_name(value);
+    actual == name || (name == "number" && actual == "integer")
+}
+
+/// The text of a string instance, `DateTime` values being written as in JSON.
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.as_string()),
+        Value::DateTime(datetime) => Some(datetime.to_string()),
+        _ => None,
+    }
+}
+
+/// The properties and items a schema evaluated successfully, for `unevaluatedProperties`
+/// and `unevaluatedItems`.
+#[derive(Default)]
+struct Evaluated {
+    properties: HashSet<String>,
+    items: HashSet<usize>,
+}
+
+impl Evaluated {
+    fn extend(&mut self, other: Evaluated) {
+        self.properties.extend(other.properties);
+        self.items.extend(other.items);
+    }
+}
+
+struct Context<'c> {
+    base: &'c str,
+    instance_path: String,
+    schema_path: String,
+    depth: usize,
+}
+
+impl Context<'_> {
+    fn error(&self, keyword: &str, message: String) -> ValidationError {
+        ValidationError {
+            instance_path: self.instance_path.clone(),
+            schema_path: format!("{}/{}", self.schema_path, keyword),
+            message,
+        }
+    }
+}
+
+impl JsonSchema {
+    /// Finds the schema at an absolute URI with an optional fragment.
+    fn find(&self, uri: &str) -> Option<(&Value, Location)> {
+        let (resource, fragment) = uri.split_once('#').unwrap_or((uri, ""));
+
+        let location = if fragment.is_empty() || fragment.starts_with('/') {
+            let resource = self.locations.get(resource)?;
+            Location {
+                document: resource.document,
+                pointer: format!("{}{}", resource.pointer, percent_decode(fragment)?),
+            }
+        } else {
+            self.locations.get(uri)?.clone()
+        };
+
+        let schema = self.documents[location.document]
+            .pointer(&location.pointer)
+            .ok()?;
+        matches!(schema, Value::Object(_) | Value::Boolean(_)).then_some((schema, location))
+    }
+
+    /// Validates an instance against a subschema at `path` from the current schema.
+    fn child(
+        &self,
+        schema: &Value,
+        instance: &Value,
+        context: &Context,
+        path: &str,
+        instance_token: Option<&str>,
+        errors: &mut Vec<ValidationError>,
+    ) -> Evaluated {
+        let base = schema_base(context.base, schema);
+        let (instance_path, depth) = match instance_token {
+            Some(token) => (format!("{}/{}", context.instance_path, escape(token)), 0),
+            None => (context.instance_path.clone(), context.depth),
+        };
+        let context = Context {
+            base: &base,
+            instance_path,
+            schema_path: format!("{}/{}", context.schema_path, path),
+            depth,
+        };
+        self.node(schema, instance, &context, errors)
+    }
+
+    /// Whether an instance is valid against a subschema, discarding the errors.
+    fn matches(&self, schema: &Value, instance: &Value, context: &Context) -> Option<Evaluated> {
+        let mut errors = Vec::new();
+        let evaluated = self.child(schema, instance, context, "", None, &mut errors);
+        errors.is_empty().then_some(evaluated)
+    }
+
+    fn node(
+        &self,
+        schema: &Value,
+        instance: &Value,
+        context: &Context,
+        errors: &mut Vec<ValidationError>,
+    ) -> Evaluated {
+        let object = match schema {
+            Value::Boolean(true) => return Evaluated::default(),
+            Value::Boolean(false) => {
+                errors.push(ValidationError {
+                    instance_path: context.instance_path.clone(),
+                    schema_path: context.schema_path.clone(),
+                    message: "No value is allowed".to_string(),
+                });
+                return Evaluated::default();
+            }
+            Value::Object(object) => object,
+            _ => return Evaluated::default(),
+        };
+
+        let before = errors.len();
+        let mut evaluated = Evaluated::default();
+
+        self.references(object, instance, context, errors, &mut evaluated);
+        self.assertions(object, instance, context, errors);
+        self.applicators(object, instance, context, errors, &mut evaluated);
+        match instance {
+            Value::Array(array) => self.items(object, array, context, errors, &mut evaluated),
+            Value::Object(members) => {
+                self.properties(object, members, context, errors, &mut evaluated)
+            }
+            _ => {}
+        }
+
+        if errors.len() == before {
+            evaluated
+        } else {
+            Evaluated::default()
+        }
+    }
+
+    fn references(
+        &self,
+        object: &Object,
+        instance: &Value,
+        context: &Context,
+        errors: &mut Vec<ValidationError>,
+        evaluated: &mut Evaluated,
+    ) {
+        for keyword in ["$ref", "$dynamicRef"] {
+            let Some(Value::String(reference)) = object.get(keyword) else {
+                continue;
+            };
+            if context.depth >= MAX_DEPTH {
+                errors.push(context.error(keyword, "Too many nested references".to_string()));
+                continue;
+            }
+
+            let uri = resolve_uri(context.base, reference.as_str());
+            let (target, location) = self
+                .find(&uri)
+                .expect("Unable to find a reference checked by the compiler");
+            let context = Context {
+                base: &self.bases[&location],
+                instance_path: context.instance_path.clone(),
+                schema_path: format!("{}/{}", context.schema_path, keyword),
+                depth: context.depth + 1,
+            };
+            evaluated.extend(self.node(target, instance, &context, errors));
+        }
+    }
+
+    /// The keywords checking the instance itself.
+    fn assertions(
+        &self,
+        object: &Object,
+        instance: &Value,
+        context: &Context,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        match object.get("type") {
+            Some(Value::String(name)) if !has_type(instance, name.as_str()) => {
+                errors.push(context.error(
+                    "type",
+                    format!("Expected {}, found {}", name.as_str(), type_name(instance)),
+                ))
+            }
+            Some(Value::Array(names))
+                if !names
+                    .into_iter()
+                    .any(|name| has_type(instance, &name.as_string())) =>
+            {
+                let names: Vec<String> = names.into_iter().map(|name| name.as_string()).collect();
+                errors.push(context.error(
+                    "type",
+                    format!(
+                        "Expected one of {}, found {}",
+                        names.join(", "),
+                        type_name(instance)
+                    ),
+                ))
+            }
+            _ => {}
+        }
+
+        if let Some(Value::Array(values)) = object.get("enum") {
+            if !values.into_iter().any(|value| value == instance) {
+                errors.push(
+                    context.error("enum", "Expected one of the enumerated values".to_string()),
+                );
+            }
+        }
+        if let Some(value) = object.get("const") {
+            if value != instance {
+                errors.push(context.error(
+                    "const",
+                    format!("Expected {}", value.to_json(JsonMode::Inline)),
+                ));
+            }
+        }
+
+        if let Value::Number(number) = instance {
+            let bound = |keyword: &str| match object.get(keyword) {
+                Some(Value::Number(bound)) => Some(bound),
+                _ => None,
+            };
+            for (keyword, relation) in [
+                ("maximum", "at most"),
+                ("exclusiveMaximum", "less than"),
+                ("minimum", "at least"),
+                ("exclusiveMinimum", "greater than"),
+            ] {
+                let Some(bound) = bound(keyword) else {
+                    continue;
+                };
+                let valid = match keyword {
+                    "maximum" => number <= bound,
+                    "exclusiveMaximum" => number < bound,
+                    "minimum" => number >= bound,
+                    _ => number > bound,
+                };
+                if !valid {
+                    errors.push(
+                        context.error(keyword, format!("Expected a number {} {}", relation, bound)),
+                    );
+                }
+            }
+            if let Some(divisor) = bound("multipleOf") {
+                if !is_multiple_of(number, divisor) {
+                    errors.push(
+                        context.error("multipleOf", format!("Expected a multiple of {}", divisor)),
+                    );
+                }
+            }
+        }
+
+        if let Some(text) = text(instance) {
+            let length = text.chars().count();
+            if let Some(max) = count(object, "maxLength") {
+                if length > max {
+                    errors.push(
+                        context.error("maxLength", format!("Expected at most {} characters", max)),
+                    );
+                }
+            }
+            if let Some(min) = count(object, "minLength") {
+                if length < min {
+                    errors.push(
+                        context.error("minLength", format!("Expected at least {} characters", min)),
+                    );
+                }
+            }
+            if let Some(Value::String(pattern)) = object.get("pattern") {
+                if !self.patterns[pattern.as_str()].is_match(&text) {
+                    errors.push(context.error(
+                        "pattern",
+                        format!("Expected to match {:?}", pattern.as_str()),
+                    ));
+                }
+            }
+            if let Some(Value::String(format)) = object.get("format") {
+                if format::check(format.as_str(), &text) == Some(false) {
+                    errors.push(
+                        context.error("format", format!("Expected a valid {}", format.as_str())),
+                    );
+                }
+            }
+        }
+    }
+
+    /// The keywords combining subschemas.
+    fn applicators(
+        &self,
+        object: &Object,
+        instance: &Value,
+        context: &Context,
+        errors: &mut Vec<ValidationError>,
+        evaluated: &mut Evaluated,
+    ) {
+        if let Some(Value::Array(schemas)) = object.get("allOf") {
+            for (index, schema) in schemas.into_iter().enumerate() {
+                let path = format!("allOf/{}", index);
+                evaluated.extend(self.child(schema, instance, context, &path, None, errors));
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = object.get("anyOf") {
+            let mut matched = false;
+            for (index, schema) in schemas.into_iter().enumerate() {
+                let path = format!("anyOf/{}", index);
+                let context = Context {
+                    base: context.base,
+                    instance_path: context.instance_path.clone(),
+                    schema_path: format!("{}/{}", context.schema_path, path),
+                    depth: context.depth,
+                };
+                if let Some(found) = self.matches(schema, instance, &context) {
+                    evaluated.extend(found);
+                    matched = true;
+                }
+            }
+            if !matched {
+                errors.push(
+                    context.error("anyOf", "Expected to match at least one schema".to_string()),
+                );
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = object.get("oneOf") {
+            let found: Vec<Evaluated> = schemas
+                .into_iter()
+                .filter_map(|schema| self.matches(schema, instance, context))
+                .collect();
+            if found.len() == 1 {
+                found.into_iter().for_each(|found| evaluated.extend(found));
+            } else {
+                errors.push(context.error(
+                    "oneOf",
+                    format!(
+                        "Expected to match exactly one schema, matched {}",
+                        found.len()
+                    ),
+                ));
+            }
+        }
+
+        if let Some(schema) = object.get("not") {
+            if self.matches(schema, instance, context).is_some() {
+                errors.push(context.error("not", "Expected not to match the schema".to_string()));
+            }
+        }
+
+        if let Some(condition) = object.get("if") {
+            let branch = match self.matches(condition, instance, context) {
+                Some(found) => {
+                    evaluated.extend(found);
+                    "then"
+                }
+                None => "else",
+            };
+            if let Some(schema) = object.get(branch) {
+                evaluated.extend(self.child(schema, instance, context, branch, None, errors));
+            }
+        }
+
+        if let (Some(Value::Object(dependencies)), Value::Object(members)) =
+            (object.get("dependentSchemas"), instance)
+        {
+            for (name, schema) in dependencies.iter() {
+                let name = name.to_string();
+                if members.get(name.as_str()).is_some() {
+                    let path = format!("dependentSchemas/{}", escape(&name));
+                    evaluated.extend(self.child(schema, instance, context, &path, None, errors));
+                }
+            }
+        }
+    }
+
+    fn items(
+        &self,
+        object: &Object,
+        array: &Array,
+        context: &Context,
+        errors: &mut Vec<ValidationError>,
+        evaluated: &mut Evaluated,
+    ) {
+        let values = &array.values;
+
+        if let Some(max) = count(object, "maxItems") {
+            if values.len() > max {
+                errors.push(context.error("maxItems", format!("Expected at most {} items", max)));
+            }
+        }
+        if let Some(min) = count(object, "minItems") {
+            if values.len() < min {
+                errors.push(context.error("minItems", format!("Expected at least {} items", min)));
+            }
+        }
+        if let Some(Value::Boolean(true)) = object.get("uniqueItems") {
+            let duplicate =
+                (0..values.len()).any(|i| (i + 1..values.len()).any(|j| values[i] == values[j]));
+            if duplicate {
+                errors.push(context.error("uniqueItems", "Expected unique items".to_string()));
+            }
+        }
+
+        let mut prefix = 0;
+        if let Some(Value::Array(schemas)) = object.get("prefixItems") {
+            for (index, (schema, value)) in schemas.into_iter().zip(values).enumerate() {
+                let path = format!("prefixItems/{}", index);
+                let token = index.to_string();
+                self.child(schema, value, context, &path, Some(&token), errors);
+                evaluated.items.insert(index);
+                prefix = index + 1;
+            }
+        }
+        if let Some(schema) = object.get("items") {
+            for (index, value) in values.iter().enumerate().skip(prefix) {
+                let token = index.to_string();
+                self.child(schema, value, context, "items", Some(&token), errors);
+                evaluated.items.insert(index);
+            }
+        }
+
+        if let Some(schema) = object.get("contains") {
+            let matching: Vec<usize> = values
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| self.matches(schema, value, context).is_some())
+                .map(|(index, _)| index)
+                .collect();
+            let min = count(object, "minContains").unwrap_or(1);
+
+            if matching.len() < min {
+                let keyword = if object.get("minContains").is_some() {
+                    "minContains"
+                } else {
+                    "contains"
+                };
+                errors.push(context.error(
+                    keyword,
+                    format!(
+                        "Expected at least {} matching items, found {}",
+                        min,
+                        matching.len()
+                    ),
+                ));
+            }
+            if let Some(max) = count(object, "maxContains") {
+                if matching.len() > max {
+                    errors.push(context.error(
+                        "maxContains",
+                        format!(
+                            "Expected at most {} matching items, found {}",
+                            max,
+                            matching.len()
+                        ),
+                    ));
+                }
+            }
+            evaluated.items.extend(matching);
+        }
+
+        if let Some(schema) = object.get("unevaluatedItems") {
+            for (index, value) in values.iter().enumerate() {
+                if !evaluated.items.contains(&index) {
+                    let token = index.to_string();
+                    self.child(
+                        schema,
+                        value,
+                        context,
+                        "unevaluatedItems",
+                        Some(&token),
+                        errors,
+                    );
+                }
+            }
+            evaluated.items.extend(0..values.len());
+        }
+    }
+
+    fn properties(
+        &self,
+        object: &Object,
+        members: &Object,
+        context: &Context,
+        errors: &mut Vec<ValidationError>,
+        evaluated: &mut Evaluated,
+    ) {
+        let mut entries: Vec<(String, &Value)> = members
+            .iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        entries.sort_by(|left, right| left.0.cmp(&right.0));
+
+        if let Some(max) = count(object, "maxProperties") {
+            if entries.len() > max {
+                errors.push(context.error(
+                    "maxProperties",
+                    format!("Expected at most {} properties", max),
+                ));
+            }
+        }
+        if let Some(min) = count(object, "minProperties") {
+            if entries.len() < min {
+                errors.push(context.error(
+                    "minProperties",
+                    format!("Expected at least {} properties", min),
+                ));
+            }
+        }
+
+        let missing = |names: &Value| -> Vec<String> {
+            match names {
+                Value::Array(names) => names
+                    .into_iter()
+                    .map(|name| name.as_string())
+                    .filter(|name| members.get(name.as_str()).is_none())
+                    .collect(),
+                _ => Vec::new(),
+            }
+        };
+        if let Some(required) = object.get("required") {
+            for name in missing(required) {
+                errors.push(context.error(
+                    "required",
+                    format!("Missing the required property {:?}", name),
+                ));
+            }
+        }
+        if let Some(Value::Object(dependencies)) = object.get("dependentRequired") {
+            for (name, required) in dependencies.iter() {
+                let name = name.to_string();
+                if members.get(name.as_str()).is_none() {
+                    continue;
+                }
+                for dependent in missing(required) {
+                    errors.push(context.error(
+                        &format!("dependentRequired/{}", escape(&name)),
+                        format!(
+                            "Missing the property {:?} required by {:?}",
+                            dependent, name
+                        ),
+                    ));
+                }
+            }
+        }
+
+        let mut matched = HashSet::new();
+        if let Some(Value::Object(schemas)) = object.get("properties") {
+            for (name, value) in &entries {
+                if let Some(schema) = schemas.get(name.as_str()) {
+                    let path = format!("properties/{}", escape(name));
+                    self.child(schema, value, context, &path, Some(name), errors);
+                    matched.insert(name.clone());
+                }
+            }
+        }
+        if let Some(Value::Object(schemas)) = object.get("patternProperties") {
+            for (pattern, schema) in schemas.iter() {
+                let pattern = pattern.to_string();
+                let regex = &self.patterns[&pattern];
+                for (name, value) in &entries {
+                    if regex.is_match(name) {
+                        let path = format!("patternProperties/{}", escape(&pattern));
+                        self.child(schema, value, context, &path, Some(name), errors);
+                        matched.insert(name.clone());
+                    }
+                }
+            }
+        }
+        if let Some(schema) = object.get("additionalProperties") {
+            for (name, value) in &entries {
+                if !matched.contains(name) {
+                    self.child(
+                        schema,
+                        value,
+                        context,
+                        "additionalProperties",
+                        Some(name),
+                        errors,
+                    );
+                    matched.insert(name.clone());
+                }
+            }
+        }
+        evaluated.properties.extend(matched);
+
+        if let Some(schema) = object.get("propertyNames") {
+            for (name, _) in &entries {
+                let key = Value::from(name.as_str());
+                let context = Context {
+                    base: context.base,
+                    instance_path: format!("{}/{}", context.instance_path, escape(name)),
+                    schema_path: context.schema_path.clone(),
+                    depth: 0,
+                };
+                self.child(schema, &key, &context, "propertyNames", None, errors);
+            }
+        }
+
+        if let Some(schema) = object.get("unevaluatedProperties") {
+            for (name, value) in &entries {
+                if !evaluated.properties.contains(name) {
+                    self.child(
+                        schema,
+                        value,
+                        context,
+                        "unevaluatedProperties",
+                        Some(name),
+                        errors,
+                    );
+                }
+            }
+            evaluated
+                .properties
+                .extend(entries.iter().map(|(name, _)| name.clone()));
+        }
+    }
+}
+
+/// Reads a non-negative integer keyword.
+fn count(object: &Object, keyword: &str) -> Option<usize> {
+    match object.get(keyword) {
+        Some(Value::Number(number)) => to_f64(number).map(|value| value as usize),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn errors(schema: Value, instance: Value) -> Vec<(String, String)> {
+        JsonSchema::compile(&schema)
+            .unwrap()
+            .validate(&instance)
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|error| (error.instance_path, error.schema_path))
+            .collect()
+    }
+
+    fn valid(schema: Value, instance: Value) -> bool {
+        JsonSchema::compile(&schema).unwrap().is_valid(&instance)
+    }
+
+    #[test]
+    fn test_assertions() {
+        assert!(valid(value!({"type": "integer"}), value!(1.0)));
+        assert!(!valid(value!({"type": "integer"}), value!(1.5)));
+        assert!(valid(value!({"type": ["string", "null"]}), value!(null)));
+        assert!(valid(
+            value!({"type": "string"}),
+            value!(datetime("2023-04-05"))
+        ));
+        assert!(valid(value!({"enum": [1, "a", [true]]}), value!([true])));
+        assert!(!valid(value!({"const": {"a": 1}}), value!({"a": 2})));
+        assert!(valid(
+            value!({"minimum": 1, "exclusiveMaximum": 3}),
+            value!(2.5)
+        ));
+        assert!(!valid(value!({"exclusiveMinimum": 1}), value!(1)));
+        assert!(valid(value!({"multipleOf": 0.1}), value!(0.3)));
+        assert!(!valid(value!({"multipleOf": 3}), value!(10)));
+        assert!(valid(value!({"maxLength": 2}), value!("éé")));
+        assert!(!valid(value!({"pattern": "^a+$"}), value!("ab")));
+        assert!(!valid(value!({"format": "uuid"}), value!("not-a-uuid")));
+        assert!(valid(value!({"format": "unknown"}), value!("anything")));
+        assert!(!valid(value!({"uniqueItems": true}), value!([1, 1.0])));
+        assert!(!valid(value!(false), value!(1)));
+        assert!(valid(value!(true), value!(1)));
+    }
+
+    #[test]
+    fn test_objects_and_arrays() {
+        let schema = value!({
+            "type": "object",
+            "required": ["id"],
+            "properties": {"id": {"type": "integer"}, "tags": {"type": "array", "items": {"type": "string"}}},
+            "patternProperties": {"^x-": {"type": "string"}},
+            "additionalProperties": false,
+            "dependentRequired": {"tags": ["id"]}
+        });
+
+        assert!(valid(
+            schema.clone(),
+            value!({"id": 1, "tags": ["a"], "x-note": "n"})
+        ));
+        assert_eq!(
+            errors(
+                schema,
+                value!({"tags": ["a", 2], "x-note": 1, "other": null})
+            ),
+            vec![
+                ("".to_string(), "/required".to_string()),
+                ("".to_string(), "/dependentRequired/tags".to_string()),
+                (
+                    "/tags/1".to_string(),
+                    "/properties/tags/items/type".to_string()
+                ),
+                (
+                    "/x-note".to_string(),
+                    "/patternProperties/^x-/type".to_string()
+                ),
+                ("/other".to_string(), "/additionalProperties".to_string()),
+            ]
+        );
+
+        let tuple = value!({
+            "prefixItems": [{"type": "string"}, {"type": "integer"}],
+            "items": false,
+            "contains": {"type": "integer"},
+            "maxContains": 1
+        });
+        assert!(valid(tuple.clone(), value!(["a", 1])));
+        assert!(!valid(tuple.clone(), value!(["a", 1, 2])));
+        assert!(!valid(tuple, value!(["a"])));
+        assert!(!valid(
+            value!({"propertyNames": {"maxLength": 3}}),
+            value!({"long": 1})
+        ));
+    }
+
+    #[test]
+    fn test_applicators() {
+        let schema = value!({
+            "anyOf": [{"type": "string"}, {"type": "number"}],
+            "oneOf": [{"minimum": 0}, {"maximum": 10}],
+            "not": {"const": 5}
+        });
+        assert!(valid(schema.clone(), value!(20)));
+        assert!(!valid(schema.clone(), value!(5)));
+        assert!(!valid(schema.clone(), value!(true)));
+        assert_eq!(
+            errors(schema, value!(3)),
+            vec![("".to_string(), "/oneOf".to_string())]
+        );
+
+        let conditional = value!({
+            "if": {"properties": {"kind": {"const": "user"}}},
+            "then": {"required": ["name"]},
+            "else": {"required": ["id"]},
+            "dependentSchemas": {"admin": {"required": ["role"]}}
+        });
+        assert!(valid(
+            conditional.clone(),
+            value!({"kind": "user", "name": "Ada"})
+        ));
+        assert!(valid(
+            conditional.clone(),
+            value!({"kind": "group", "id": 1})
+        ));
+        assert!(!valid(
+            conditional.clone(),
+            value!({"kind": "user", "id": 1})
+        ));
+        assert!(!valid(conditional, value!({"id": 1, "admin": true})));
+
+        let unevaluated = value!({
+            "allOf": [{"properties": {"a": true}}],
+            "anyOf": [{"properties": {"b": true}, "required": ["b"]}, {"required": ["c"]}],
+            "unevaluatedProperties": false
+        });
+        assert!(valid(unevaluated.clone(), value!({"a": 1, "b": 2})));
+        assert!(!valid(unevaluated, value!({"a": 1, "c": 2})));
+        assert!(!valid(
+            value!({"prefixItems": [true], "unevaluatedItems": false}),
+            value!([1, 2])
+        ));
+    }
+
+    #[test]
+    fn test_references() {
+        let schema = value!({
+            "$id": "https://example.com/schemas/node",
+            "type": "object",
+            "properties": {
+                "value": {"$ref": "#/$defs/positive"},
+                "children": {"type": "array", "items": {"$ref": "#"}},
+                "label": {"$ref": "#label"},
+                "meta": {"$ref": "meta"}
+            },
+            "$defs": {
+                "positive": {"type": "number", "exclusiveMinimum": 0},
+                "label": {"$anchor": "label", "type": "string"},
+                "meta": {"$id": "meta", "type": "object", "required": ["version"]}
+            }
+        });
+
+        assert!(valid(
+            schema.clone(),
+            value!({"value": 1, "label": "a", "meta": {"version": 1}, "children": [{"value": 2}]})
+        ));
+        assert_eq!(
+            errors(schema, value!({"children": [{"value": -1}], "label": 1})),
+            vec![
+                (
+                    "/children/0/value".to_string(),
+                    "/properties/children/items/$ref/properties/value/$ref/exclusiveMinimum"
+                        .to_string()
+                ),
+                (
+                    "/label".to_string(),
+                    "/properties/label/$ref/type".to_string()
+                ),
+            ]
+        );
+
+        let cycle = JsonSchema::compile(&value!({"$ref": "#"})).unwrap();
+        assert!(!cycle.is_valid(&value!(1)));
+    }
+
+    #[test]
+    fn test_recursive_references_follow_the_instance() {
+        fn nested(leaf: Value, levels: usize) -> Value {
+            (0..levels).fold(leaf, |inner, _| Value::from(vec![("next", inner)]))
+        }
+
+        // Debug builds need more stack than a test thread has for 300 levels.
+        let validate = || {
+            let schema = JsonSchema::compile(&value!({
+                "type": "object",
+                "properties": {"next": {"$ref": "#"}}
+            }))
+            .unwrap();
+
+            assert!(schema.is_valid(&nested(value!({}), 300)));
+            assert!(!schema.is_valid(&nested(value!(1), 300)));
+        };
+        std::thread::Builder::new()
+            .stack_size(32 * 1024 * 1024)
+            .spawn(validate)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_resolver_and_schema_errors() {
+        let schema = value!({"$ref": "https://example.com/address.json#/$defs/zip"});
+        assert!(JsonSchema::compile(&schema).is_err());
+
+        let resolver = |uri: &str| -> Option<Value> {
+            (uri == "https://example.com/address.json")
+                .then(|| value!({"$defs": {"zip": {"type": "string", "pattern": "^[0-9]{5}$"}}}))
+        };
+        let compiled = JsonSchema::compile_with(&schema, &resolver).unwrap();
+        assert!(compiled.is_valid(&value!("12345")));
+        assert!(value!("1234").validate(&compiled).is_err());
+
+        for schema in [
+            value!(1),
+            value!({"type": "text"}),
+            value!({"pattern": "("}),
+            value!({"minLength": -1}),
+            value!({"allOf": []}),
+            value!({"properties": {"a": 1}}),
+            value!({"$ref": "#/$defs/missing"}),
+            value!({"$ref": "#missing"}),
+        ] {
+            assert!(JsonSchema::compile(&schema).is_err(), "{}", schema);
+        }
+    }
+
+    #[test]
+    fn test_resolve_uri() {
+        use super::resolve_uri;
+
+        assert_eq!(
+            resolve_uri("https://a.com/x/y.json", "z.json"),
+            "https://a.com/x/z.json"
+        );
+        assert_eq!(
+            resolve_uri("https://a.com/x/y.json", "../z.json"),
+            "https://a.com/z.json"
+        );
+        assert_eq!(
+            resolve_uri("https://a.com/x/y.json", "/z"),
+            "https://a.com/z"
+        );
+        assert_eq!(
+            resolve_uri("https://a.com/x/y.json", "#a"),
+            "https://a.com/x/y.json#a"
+        );
+        assert_eq!(resolve_uri("https://a.com/x", "urn:b"), "urn:b");
+        assert_eq!(resolve_uri("", "a.json"), "a.json");
+    }
+}
@@ -39,6 +39,9 @@ impl Serialize for Value {
                 NumberType::I128 => serializer.serialize_i128(value.get_i128_unsafe()),
                 NumberType::F32 => serializer.serialize_f32(value.get_f32_unsafe()),
                 NumberType::F64 => serializer.serialize_f64(value.get_f64_unsafe()),
+                // A string keeps every digit, no serde number type can hold them all.
+                #[cfg(feature = "decimal")]
+                NumberType::Decimal => serializer.serialize_str(&value.to_string()),
                 NumberType::Unknown => Err(Error::custom("Unknown number type")),
             },
             Value::Boolean(value) => serializer.serialize_bool(*value),
@@ -3,23 +3,20 @@ mod test {
     use crate::prelude::*;
     use std::collections::{BTreeMap, HashMap};
 
-    #[derive(ToValue, FromValue, PartialEq, Debug, Clone)]
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
     enum ExampleType {
         Example1,
         Example2,
     }
 
-    #[derive(ToValue, FromValue, PartialEq, Debug, Default, Clone, ToJson, ToYaml)]
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Default, Clone, ToJson, ToYaml)]
     struct Inner {
         item_a: bool,
         tree: BTreeMap<String, String>,
     }
 
-    #[derive(ToValue, FromValue, PartialEq, Debug, Clone, ToJson, ToYaml)]
-    struct Example<T>
-    where
-        T: PrimitiveType + FromValueBehavior<Item = T> + Clone + ToValueBehavior,
-    {
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone, ToJson, ToYaml)]
+    struct Example<T> {
         item_a: i32,
         item_b: String,
         item_c: Option<Vec<String>>,
@@ -43,4 +40,700 @@ mod test {
 
         assert_eq!(example, Example::from_value(value).unwrap());
     }
+
+    #[test]
+    fn test_to_json_writes_non_finite_numbers_as_null() {
+        let example = Example {
+            item_a: 1,
+            item_b: "Hello".to_string(),
+            item_c: None,
+            item_d: HashMap::default(),
+            item_e: ExampleType::Example2,
+            item_f: f64::NAN,
+        };
+
+        let json = example.to_json();
+        assert!(json.contains("\"item_f\": null"), "{}", json);
+        assert!(!json.contains("NaN"));
+    }
+
+    fn default_retries() -> u8 {
+        3
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    #[valu3(rename_all = "camelCase")]
+    struct Settings {
+        user_name: String,
+        #[valu3(rename = "ID")]
+        user_id: u32,
+        #[valu3(skip)]
+        cache: Vec<String>,
+        #[valu3(default)]
+        is_active: bool,
+        #[valu3(default = "default_retries")]
+        max_retries: u8,
+        note: Option<String>,
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    #[valu3(rename_all = "SCREAMING_SNAKE_CASE")]
+    enum Level {
+        LowPriority,
+        #[valu3(rename = "urgent")]
+        HighPriority,
+    }
+
+    #[test]
+    fn test_rename_and_skip() {
+        let settings = Settings {
+            user_name: "ana".to_string(),
+            user_id: 7,
+            cache: vec!["ignored".to_string()],
+            is_active: true,
+            max_retries: 5,
+            note: None,
+        };
+
+        let value = settings.to_value();
+
+        assert_eq!(value.get("userName"), Some(&"ana".to_value()));
+        assert_eq!(value.get("ID"), Some(&7u32.to_value()));
+        assert_eq!(value.get("isActive"), Some(&true.to_value()));
+        assert_eq!(value.get("maxRetries"), Some(&5u8.to_value()));
+        assert_eq!(value.get("note"), Some(&Value::Null));
+        assert_eq!(value.get("cache"), None);
+        assert_eq!(value.get("user_name"), None);
+
+        let restored = Settings::from_value(value).unwrap();
+        assert_eq!(restored.cache, Vec::<String>::new());
+        assert_eq!(
+            restored,
+            Settings {
+                cache: Vec::new(),
+                ..settings
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let value = Value::from(vec![
+            ("userName", "ana".to_value()),
+            ("ID", 1u32.to_value()),
+            ("note", "hi".to_value()),
+        ]);
+
+        let settings = Settings::from_value(value).unwrap();
+        assert!(!settings.is_active);
+        assert_eq!(settings.max_retries, 3);
+        assert_eq!(settings.note, Some("hi".to_string()));
+
+        let missing_required = Value::from(vec![("userName", "ana".to_value())]);
+        assert_eq!(Settings::from_value(missing_required), None);
+    }
+
+    #[test]
+    fn test_option_from_value() {
+        assert_eq!(Option::<u8>::from_value(Value::Null), Some(None));
+        assert_eq!(Option::<u8>::from_value(Value::Undefined), Some(None));
+        assert_eq!(Option::<u8>::from_value(7u8.to_value()), Some(Some(7)));
+        // A value that does not convert fails instead of becoming `None`.
+        assert_eq!(Option::<u8>::from_value("seven".to_value()), None);
+        assert_eq!(Option::<u8>::from_value(300.to_value()), None);
+    }
+
+    #[test]
+    fn test_enum_rename() {
+        assert_eq!(Level::LowPriority.to_value(), "LOW_PRIORITY".to_value());
+        assert_eq!(Level::HighPriority.to_value(), "urgent".to_value());
+        assert_eq!(
+            Level::from_value("LOW_PRIORITY".to_value()),
+            Some(Level::LowPriority)
+        );
+        assert_eq!(
+            Level::from_value("urgent".to_value()),
+            Some(Level::HighPriority)
+        );
+        assert_eq!(Level::from_value("HighPriority".to_value()), None);
+    }
+
+    fn point(x: i32, y: i32) -> BTreeMap<String, i32> {
+        BTreeMap::from([("x".to_string(), x), ("y".to_string(), y)])
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    enum Shape {
+        Empty,
+        Circle(u32),
+        Line(Vec<i32>, Vec<i32>),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    #[valu3(tag = "type", rename_all = "snake_case")]
+    enum Event {
+        Started,
+        Moved(BTreeMap<String, i32>),
+        Resized {
+            width: u32,
+            #[valu3(rename = "h")]
+            height: u32,
+        },
+    }
+
+    #[derive(ToValue, FromValue, PartialEq, Debug, Clone)]
+    #[valu3(tag = "type")]
+    enum Claim {
+        Owner(UserId),
+        Labels(BTreeMap<String, i32>),
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    #[valu3(tag = "t", content = "c")]
+    enum Message {
+        Ping,
+        Text(String),
+        Pair(i32, i32),
+        Move { x: i32, y: i32 },
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    #[valu3(untagged)]
+    enum Loose {
+        Nothing,
+        Number(i32),
+        Pair(bool, bool),
+        Named { label: String },
+    }
+
+    #[test]
+    fn test_externally_tagged_enum() {
+        let shapes = vec![
+            Shape::Empty,
+            Shape::Circle(3),
+            Shape::Line(vec![0, 0], vec![1, 2]),
+            Shape::Rect {
+                width: 2,
+                height: 4,
+            },
+        ];
+
+        assert_eq!(Shape::Empty.to_value(), "Empty".to_value());
+        assert_eq!(
+            Shape::Circle(3).to_value(),
+            Value::from(vec![("Circle", 3u32.to_value())])
+        );
+        assert_eq!(
+            shapes[3].to_value(),
+            Value::from(vec![(
+                "Rect",
+                Value::from(vec![("width", 2u32), ("height", 4u32)])
+            )])
+        );
+
+        for shape in shapes {
+            assert_eq!(Shape::from_value(shape.to_value()), Some(shape));
+        }
+
+        assert_eq!(Shape::from_value("Circle".to_value()), None);
+        assert_eq!(
+            Shape::from_value(Value::from(vec![("Line", vec![1, 2, 3])])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_internally_tagged_enum() {
+        let moved = Event::Moved(point(1, 2));
+        let resized = Event::Resized {
+            width: 3,
+            height: 4,
+        };
+
+        assert_eq!(
+            Event::Started.to_value(),
+            Value::from(vec![("type", "started")])
+        );
+        assert_eq!(
+            moved.to_value(),
+            Value::from(vec![
+                ("type", "moved".to_value()),
+                ("x", 1.to_value()),
+                ("y", 2.to_value()),
+            ])
+        );
+        assert_eq!(
+            resized.to_value(),
+            Value::from(vec![
+                ("type", "resized".to_value()),
+                ("width", 3u32.to_value()),
+                ("h", 4u32.to_value()),
+            ])
+        );
+
+        for event in [Event::Started, moved, resized] {
+            assert_eq!(Event::from_value(event.to_value()), Some(event));
+        }
+
+        assert_eq!(
+            Event::from_value(Value::from(vec![("type", "unknown")])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_internally_tagged_newtype_of_a_non_object() {
+        let owner = Claim::Owner(UserId(7));
+        let labels = Claim::Labels(BTreeMap::from([("Labels".to_string(), 1)]));
+
+        assert_eq!(
+            owner.to_value(),
+            Value::from(vec![
+                ("type", "Owner".to_value()),
+                ("Owner", 7u64.to_value())
+            ])
+        );
+        assert_eq!(
+            labels.to_value(),
+            Value::from(vec![
+                ("type", "Labels".to_value()),
+                ("Labels", 1.to_value())
+            ])
+        );
+
+        for claim in [owner, labels] {
+            assert_eq!(Claim::from_value(claim.to_value()), Some(claim));
+        }
+    }
+
+    #[test]
+    fn test_adjacently_tagged_enum() {
+        let messages = vec![
+            Message::Ping,
+            Message::Text("hi".to_string()),
+            Message::Pair(1, 2),
+            Message::Move { x: 3, y: 4 },
+        ];
+
+        assert_eq!(Message::Ping.to_value(), Value::from(vec![("t", "Ping")]));
+        assert_eq!(
+            messages[1].to_value(),
+            Value::from(vec![("t", "Text"), ("c", "hi")])
+        );
+        assert_eq!(
+            messages[2].to_value(),
+            Value::from(vec![("t", "Pair".to_value()), ("c", vec![1, 2].to_value())])
+        );
+
+        for message in messages {
+            assert_eq!(Message::from_value(message.to_value()), Some(message));
+        }
+
+        assert_eq!(Message::from_value(Value::from(vec![("t", "Text")])), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(ToValue, FromValue, PartialEq, Debug, Clone, serde::Serialize)]
+    #[valu3(tag = "kind", rename_all = "camelCase")]
+    #[serde(tag = "kind", rename_all = "camelCase")]
+    enum Command {
+        SetLevel { new_level: u8, dry_run: bool },
+        ResetAll,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_enum_rename_all_matches_serde() {
+        for command in [
+            Command::SetLevel {
+                new_level: 3,
+                dry_run: true,
+            },
+            Command::ResetAll,
+        ] {
+            let value = command.to_value();
+            let json: serde_json::Value = serde_json::from_str(&value.to_string()).unwrap();
+
+            assert_eq!(json, serde_json::to_value(&command).unwrap());
+            assert_eq!(Command::from_value(value), Some(command));
+        }
+    }
+
+    #[test]
+    fn test_untagged_enum() {
+        let values = vec![
+            Loose::Nothing,
+            Loose::Number(7),
+            Loose::Pair(true, false),
+            Loose::Named {
+                label: "a".to_string(),
+            },
+        ];
+
+        assert_eq!(Loose::Nothing.to_value(), Value::Null);
+        assert_eq!(Loose::Number(7).to_value(), 7.to_value());
+        assert_eq!(
+            Loose::Pair(true, false).to_value(),
+            vec![true, false].to_value()
+        );
+
+        for value in values {
+            assert_eq!(Loose::from_value(value.to_value()), Some(value));
+        }
+
+        assert_eq!(Loose::from_value("text".to_value()), None);
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    struct Pair(i32, String);
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    struct Marker;
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    #[valu3(transparent)]
+    struct UserId(u64);
+
+    #[derive(ToValue, FromValue, PartialEq, Debug, Clone)]
+    #[valu3(transparent)]
+    struct Tags {
+        values: Vec<String>,
+        #[valu3(skip)]
+        dirty: bool,
+    }
+
+    #[test]
+    fn test_tuple_struct() {
+        let pair = Pair(1, "one".to_string());
+
+        assert_eq!(
+            pair.to_value(),
+            Value::from(vec![1.to_value(), "one".to_value()])
+        );
+        assert_eq!(Pair::from_value(pair.to_value()), Some(pair));
+        assert_eq!(Pair::from_value(vec![1].to_value()), None);
+    }
+
+    #[test]
+    fn test_unit_struct() {
+        assert_eq!(Marker.to_value(), Value::Null);
+        assert_eq!(Marker::from_value(Value::Null), Some(Marker));
+        assert_eq!(Marker::from_value(true.to_value()), None);
+    }
+
+    #[test]
+    fn test_transparent_struct() {
+        assert_eq!(UserId(42).to_value(), 42u64.to_value());
+        assert_eq!(UserId::from_value(42u64.to_value()), Some(UserId(42)));
+
+        let tags = Tags {
+            values: vec!["a".to_string()],
+            dirty: true,
+        };
+        assert_eq!(tags.to_value(), vec!["a"].to_value());
+        assert_eq!(
+            Tags::from_value(tags.to_value()),
+            Some(Tags {
+                values: vec!["a".to_string()],
+                dirty: false,
+            })
+        );
+    }
+
+    /// Deliberately neither `Clone` nor `PrimitiveType`.
+    #[derive(ToValue, FromValue, PartialEq, Debug)]
+    struct Handle {
+        id: u32,
+    }
+
+    #[derive(ToValue, FromValue, PartialEq, Debug)]
+    struct Wrapper<T, U> {
+        inner: T,
+        items: Vec<U>,
+        #[valu3(skip)]
+        cached: Option<U>,
+    }
+
+    #[derive(ToValue, FromValue, PartialEq, Debug)]
+    enum Slot<T> {
+        Empty,
+        Filled(T),
+    }
+
+    #[test]
+    fn test_fields_without_clone() {
+        let wrapper = Wrapper {
+            inner: Handle { id: 1 },
+            items: vec![Slot::Filled(Handle { id: 2 }), Slot::Empty],
+            cached: None,
+        };
+
+        let value = wrapper.to_value();
+
+        assert_eq!(value.get("inner"), Some(&Value::from(vec![("id", 1u32)])));
+        assert_eq!(Wrapper::from_value(value), Some(wrapper));
+    }
+
+    mod seconds {
+        use crate::prelude::*;
+        use std::time::Duration;
+
+        pub fn to_value(duration: &Duration) -> Value {
+            duration.as_secs().to_value()
+        }
+
+        pub fn from_value(value: Value) -> Option<Duration> {
+            u64::from_value(value).map(Duration::from_secs)
+        }
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    #[valu3(rename_all = "camelCase")]
+    struct Audit {
+        created_by: String,
+        revision: u32,
+    }
+
+    #[derive(ToValue, FromValue, Schema, PartialEq, Debug, Clone)]
+    struct Document {
+        title: String,
+        #[valu3(flatten)]
+        audit: Audit,
+        #[valu3(with = "seconds")]
+        ttl: std::time::Duration,
+    }
+
+    #[derive(ToValue, FromValue, PartialEq, Debug, Clone)]
+    struct Extensible {
+        name: String,
+        #[valu3(flatten)]
+        extra: BTreeMap<String, i32>,
+    }
+
+    #[derive(ToValue, FromValue, PartialEq, Debug, Clone)]
+    struct Owned {
+        name: String,
+        #[valu3(flatten)]
+        owner: UserId,
+    }
+
+    #[test]
+    fn test_flatten_and_with() {
+        let document = Document {
+            title: "notes".to_string(),
+            audit: Audit {
+                created_by: "ana".to_string(),
+                revision: 2,
+            },
+            ttl: std::time::Duration::from_secs(60),
+        };
+
+        let value = document.to_value();
+
+        assert_eq!(
+            value,
+            Value::from(vec![
+                ("title", "notes".to_value()),
+                ("createdBy", "ana".to_value()),
+                ("revision", 2u32.to_value()),
+                ("ttl", 60u64.to_value()),
+            ])
+        );
+        assert_eq!(Document::from_value(value), Some(document));
+    }
+
+    #[test]
+    fn test_flatten_collects_remaining_keys() {
+        let value = Value::from(vec![
+            ("name", "counter".to_value()),
+            ("a", 1.to_value()),
+            ("b", 2.to_value()),
+        ]);
+
+        let extensible = Extensible::from_value(value.clone()).unwrap();
+
+        assert_eq!(extensible.name, "counter");
+        assert_eq!(
+            extensible.extra,
+            BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+        assert_eq!(extensible.to_value(), value);
+    }
+
+    #[test]
+    fn test_flatten_keeps_values_that_are_not_objects() {
+        let owned = Owned {
+            name: "notes".to_string(),
+            owner: UserId(7),
+        };
+
+        let value = owned.to_value();
+
+        assert_eq!(
+            value,
+            Value::from(vec![
+                ("name", "notes".to_value()),
+                ("owner", 7u64.to_value())
+            ])
+        );
+        assert_eq!(Owned::from_value(value), Some(owned));
+    }
+
+    #[test]
+    fn test_struct_schema() {
+        assert_eq!(
+            Settings::schema(),
+            value!({
+                "type": "object",
+                "properties": {
+                    "userName": {"type": "string"},
+                    "ID": {"type": "integer", "minimum": 0, "maximum": 4294967295u32},
+                    "isActive": {"type": "boolean", "default": false},
+                    "maxRetries": {"type": "integer", "minimum": 0, "maximum": 255, "default": 3},
+                    "note": {"anyOf": [{"type": "string"}, {"type": "null"}]}
+                },
+                "required": ["userName", "ID"]
+            })
+        );
+
+        let schema = JsonSchema::compile(&Settings::schema()).unwrap();
+        let settings = Settings {
+            user_name: "ana".to_string(),
+            user_id: 7,
+            cache: Vec::new(),
+            is_active: true,
+            max_retries: 5,
+            note: None,
+        };
+        assert!(schema.is_valid(&settings.to_value()));
+        assert!(schema.is_valid(&value!({"userName": "ana", "ID": 1})));
+        assert!(!schema.is_valid(&value!({"userName": "ana"})));
+        assert!(!schema.is_valid(&value!({"userName": "ana", "ID": -1})));
+    }
+
+    #[test]
+    fn test_schema_valid_documents_convert() {
+        let schema = JsonSchema::compile(&Settings::schema()).unwrap();
+        let document = value!({"userName": "ana", "ID": 1});
+
+        assert!(schema.is_valid(&document));
+        assert_eq!(
+            Settings::from_value(document),
+            Some(Settings {
+                user_name: "ana".to_string(),
+                user_id: 1,
+                cache: Vec::new(),
+                is_active: false,
+                max_retries: 3,
+                note: None,
+            })
+        );
+
+        let document = value!({"userName": "ana", "ID": 1, "note": 5});
+        assert!(!schema.is_valid(&document));
+        assert_eq!(Settings::from_value(document), None);
+    }
+
+    #[test]
+    fn test_generic_struct_schema() {
+        let schema = Example::<Vec<bool>>::schema();
+
+        assert_eq!(
+            schema.get("required"),
+            Some(&value!(["item_a", "item_b", "item_d", "item_e", "item_f"]))
+        );
+        assert_eq!(
+            schema.get("properties").unwrap().get("item_e"),
+            Some(&value!({"type": "string", "enum": ["Example1", "Example2"]}))
+        );
+        assert_eq!(
+            schema.get("properties").unwrap().get("item_f"),
+            Some(&value!({"type": "array", "items": {"type": "boolean"}}))
+        );
+    }
+
+    #[test]
+    fn test_enum_schemas() {
+        assert_eq!(
+            Level::schema(),
+            value!({"type": "string", "enum": ["LOW_PRIORITY", "urgent"]})
+        );
+
+        let shapes = JsonSchema::compile(&Shape::schema()).unwrap();
+        for shape in [
+            Shape::Empty,
+            Shape::Circle(1),
+            Shape::Line(vec![1], vec![2]),
+            Shape::Rect {
+                width: 1,
+                height: 2,
+            },
+        ] {
+            assert!(shapes.is_valid(&shape.to_value()), "{:?}", shape);
+        }
+        assert!(!shapes.is_valid(&value!("Circle")));
+        assert!(!shapes.is_valid(&value!({"Circle": 1, "Empty": null})));
+
+        let events = JsonSchema::compile(&Event::schema()).unwrap();
+        for event in [
+            Event::Started,
+            Event::Moved(point(1, 2)),
+            Event::Resized {
+                width: 1,
+                height: 2,
+            },
+        ] {
+            assert!(events.is_valid(&event.to_value()), "{:?}", event);
+        }
+        assert!(!events.is_valid(&value!({"type": "resized", "width": 1})));
+
+        let messages = JsonSchema::compile(&Message::schema()).unwrap();
+        for message in [
+            Message::Ping,
+            Message::Text("hi".to_string()),
+            Message::Pair(1, 2),
+            Message::Move { x: 1, y: 2 },
+        ] {
+            assert!(messages.is_valid(&message.to_value()), "{:?}", message);
+        }
+        assert!(!messages.is_valid(&value!({"t": "Pair", "c": [1]})));
+
+        let loose = JsonSchema::compile(&Loose::schema()).unwrap();
+        assert!(loose.is_valid(&Loose::Pair(true, false).to_value()));
+        assert!(loose.is_valid(&Loose::Nothing.to_value()));
+        assert!(!loose.is_valid(&value!("label")));
+    }
+
+    #[test]
+    fn test_tuple_flatten_and_with_schemas() {
+        assert_eq!(
+            Pair::schema(),
+            value!({
+                "type": "array",
+                "prefixItems": [
+                    {"type": "integer", "minimum": -2147483648, "maximum": 2147483647},
+                    {"type": "string"}
+                ],
+                "minItems": 2,
+                "maxItems": 2
+            })
+        );
+        assert_eq!(Marker::schema(), value!({"type": "null"}));
+        assert_eq!(UserId::schema(), u64::schema());
+
+        assert_eq!(
+            Document::schema(),
+            value!({
+                "type": "object",
+                "properties": {
+                    "title": {"type": "string"},
+                    "createdBy": {"type": "string"},
+                    "revision": {"type": "integer", "minimum": 0, "maximum": 4294967295u32},
+                    "ttl": true
+                },
+                "required": ["title", "createdBy", "revision", "ttl"]
+            })
+        );
+    }
 }
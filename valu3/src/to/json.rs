@@ -9,6 +9,18 @@ pub enum JsonMode {
     Inline,
 }
 
+/// How `to_json_with` writes NaN and infinities, which JSON numbers cannot represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinite {
+    /// Writes `null`, as `JSON.stringify` does.
+    #[default]
+    Null,
+    /// Writes the strings `"NaN"`, `"Infinity"` and `"-Infinity"`.
+    String,
+    /// Fails with `Error::NotFinite`.
+    Error,
+}
+
 impl Value {
     /// Converts a `Value` into a JSON string.
     ///
@@ -25,13 +37,36 @@ impl Value {
     /// let json_string = value.to_json(JsonMode::Indented);
     /// println!("{}", json_string);
     /// ```
+    ///
+    /// NaN and infinities are written as `null`, see `to_json_with` for other choices.
     pub fn to_json(&self, mode: JsonMode) -> String {
-        let value = Value::to_json_inner(self, 0);
+        self.to_json_with(mode, NonFinite::Null)
+            .expect("Unable to fail writing non-finite numbers as null")
+    }
 
-        match mode {
+    /// Converts a `Value` into a JSON string, writing NaN and infinities as set by
+    /// `non_finite`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let value = Value::from(vec![1.5, f64::INFINITY]);
+    /// assert_eq!(
+    ///     value.to_json_with(JsonMode::Inline, NonFinite::String),
+    ///     Ok("[1.5,\"Infinity\"]".to_string())
+    /// );
+    /// assert_eq!(
+    ///     value.to_json_with(JsonMode::Inline, NonFinite::Error),
+    ///     Err(Error::NotFinite)
+    /// );
+    /// ```
+    pub fn to_json_with(&self, mode: JsonMode, non_finite: NonFinite) -> Result<String, Error> {
+        let value = Value::to_json_inner(self, 0, non_finite)?;
+
+        Ok(match mode {
             JsonMode::Inline => Self::inline(value),
             JsonMode::Indented => value,
-        }
+        })
     }
 
     /// Converts the inline JSON string into an indented JSON string.
@@ -47,27 +82,27 @@ impl Value {
     }
 
     /// Converts a `Value` into a JSON string.
-    fn to_json_inner(val: &Value, children: i32) -> String {
-        match val {
+    fn to_json_inner(val: &Value, children: i32, non_finite: NonFinite) -> Result<String, Error> {
+        let json = match val {
             Value::Object(o) => {
-                let contents: Vec<_> = o
+                let contents = o
                     .iter()
                     .map(|(name, value)| {
-                        format!(
+                        Ok(format!(
                             "\n\t{}\"{}\": {}",
                             &Self::tabs(children),
                             name,
-                            Value::to_json_inner(value, children + 1)
-                        )
+                            Value::to_json_inner(value, children + 1, non_finite)?
+                        ))
                     })
-                    .collect();
+                    .collect::<Result<Vec<_>, Error>>()?;
                 format!("{{{}\n{}}}", contents.join(","), &Self::tabs(children))
             }
             Value::Array(a) => {
-                let contents: Vec<_> = a
+                let contents = a
                     .into_iter()
-                    .map(|value| Value::to_json_inner(value, children + 1))
-                    .collect();
+                    .map(|value| Value::to_json_inner(value, children + 1, non_finite))
+                    .collect::<Result<Vec<_>, Error>>()?;
                 format!(
                     "[\n\t{}{}\n{}]",
                     &Self::tabs(children),
@@ -104,12 +139,21 @@ impl Value {
 
                 format!("\"{}\"", result.join(""))
             }
+            Value::Number(n) if !n.is_finite() => match non_finite {
+                NonFinite::Null => "null".to_string(),
+                NonFinite::String if n.is_nan() => "\"NaN\"".to_string(),
+                NonFinite::String if n.is_positive() => "\"Infinity\"".to_string(),
+                NonFinite::String => "\"-Infinity\"".to_string(),
+                NonFinite::Error => return Err(Error::NotFinite),
+            },
             Value::Number(n) => format!("{}", n),
             Value::Boolean(b) => format!("{}", b),
             Value::Null => "null".to_string(),
             Value::Undefined => "undefined".to_string(),
             Value::DateTime(date_time) => format!("\"{}\"", date_time),
-        }
+        };
+
+        Ok(json)
     }
 }
 
@@ -148,4 +192,26 @@ mod tests {
             value_boolean.to_json(JsonMode::Indented)
         )
     }
+
+    #[test]
+    fn it_should_write_non_finite_numbers_by_policy() {
+        let value = Value::from(vec![1.5, f64::NAN, f64::INFINITY, f64::NEG_INFINITY]);
+
+        assert_eq!(value.to_json(JsonMode::Inline), "[1.5,null,null,null]");
+        assert_eq!(Value::from(f64::NAN).to_json(JsonMode::Inline), "null");
+        assert_eq!(Value::from(f32::INFINITY).to_string(), "null");
+        assert_eq!(Value::from(2.5).to_string(), "2.5");
+        assert_eq!(
+            value.to_json_with(JsonMode::Inline, NonFinite::String),
+            Ok("[1.5,\"NaN\",\"Infinity\",\"-Infinity\"]".to_string())
+        );
+        assert_eq!(
+            value.to_json_with(JsonMode::Inline, NonFinite::Error),
+            Err(Error::NotFinite)
+        );
+        assert_eq!(
+            Value::from(2.5).to_json_with(JsonMode::Inline, NonFinite::Error),
+            Ok("2.5".to_string())
+        );
+    }
 }
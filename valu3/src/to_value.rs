@@ -227,6 +227,167 @@ macro_rules! vec_value {
     };
 }
 
+/// Builds a `Value` from JSON-like syntax.
+///
+/// Any Rust expression can stand for a value, it is converted with `ToValueBehavior`. On
+/// top of JSON, `undefined` builds `Value::Undefined` and `datetime(...)` builds a
+/// `Value::DateTime` from anything `DateTime` converts from, such as a `&str` or an `i64`
+/// timestamp. Keys are string literals or expressions in parentheses.
+///
+/// # Example
+/// ```no_run
+/// use valu3::prelude::*;
+///
+/// let name = "Ada";
+/// let value = value!({
+///     "name": name,
+///     "tags": ["admin", null, undefined],
+///     "created": datetime("2023-04-05T00:00:00Z"),
+///     (format!("level_{}", 1)): 1 + 1
+/// });
+///
+/// assert_eq!(value.get("name"), Some(&Value::from("Ada")));
+/// ```
+#[macro_export]
+macro_rules! value {
+    ($($value:tt)+) => {
+        $crate::value_internal!($($value)+)
+    };
+}
+
+// Munches the tokens of arrays and objects one value at a time, since a `tt` cannot tell
+// where an expression ends.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_internal {
+    // Arrays: the values built so far go between the first brackets.
+    (@array [$($values:expr,)*]) => {
+        vec![$($values,)*]
+    };
+    (@array [$($values:expr),*]) => {
+        vec![$($values),*]
+    };
+    (@array [$($values:expr,)*] null $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!(null)] $($rest)*)
+    };
+    (@array [$($values:expr,)*] undefined $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!(undefined)] $($rest)*)
+    };
+    (@array [$($values:expr,)*] true $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!(true)] $($rest)*)
+    };
+    (@array [$($values:expr,)*] false $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!(false)] $($rest)*)
+    };
+    (@array [$($values:expr,)*] datetime($($date:tt)+) $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!(datetime($($date)+))] $($rest)*)
+    };
+    (@array [$($values:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!([$($array)*])] $($rest)*)
+    };
+    (@array [$($values:expr,)*] {$($object:tt)*} $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!({$($object)*})] $($rest)*)
+    };
+    (@array [$($values:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!($next),] $($rest)*)
+    };
+    (@array [$($values:expr,)*] $last:expr) => {
+        $crate::value_internal!(@array [$($values,)* $crate::value_internal!($last)])
+    };
+    (@array [$($values:expr),*] , $($rest:tt)*) => {
+        $crate::value_internal!(@array [$($values,)*] $($rest)*)
+    };
+
+    // Objects: `@object object (key tokens) (remaining tokens)`, the key is munched up to the
+    // colon, then its value up to the comma.
+    (@object $object:ident () ()) => {};
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert(($($key)+), $value);
+        $crate::value_internal!(@object $object () ($($rest)*));
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert(($($key)+), $value);
+    };
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: undefined $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!(undefined)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!(true)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!(false)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: datetime($($date:tt)+) $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!(datetime($($date)+))) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!({$($inner)*})) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr)) => {
+        $crate::value_internal!(@object $object [$($key)+] ($crate::value_internal!($value)));
+    };
+    (@object $object:ident ($($key:tt)+) (:)) => {
+        compile_error!("Unable to build an object member without a value")
+    };
+    (@object $object:ident ($($key:tt)+) ()) => {
+        compile_error!("Unable to build an object member without a colon and a value")
+    };
+    (@object $object:ident () (($key:expr) : $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object ($key) (: $($rest)*));
+    };
+    (@object $object:ident ($($key:tt)*) ($next:tt $($rest:tt)*)) => {
+        $crate::value_internal!(@object $object ($($key)* $next) ($($rest)*));
+    };
+
+    (null) => {
+        $crate::prelude::Value::Null
+    };
+    (undefined) => {
+        $crate::prelude::Value::Undefined
+    };
+    (true) => {
+        $crate::prelude::Value::Boolean(true)
+    };
+    (false) => {
+        $crate::prelude::Value::Boolean(false)
+    };
+    (datetime($($date:tt)+)) => {
+        $crate::prelude::Value::DateTime($crate::prelude::DateTime::from($($date)+))
+    };
+    ([]) => {
+        $crate::prelude::Value::Array($crate::prelude::Array::new())
+    };
+    ([ $($values:tt)+ ]) => {
+        $crate::prelude::Value::Array($crate::prelude::Array::from(
+            $crate::value_internal!(@array [] $($values)+)
+        ))
+    };
+    ({}) => {
+        $crate::prelude::Value::Object($crate::prelude::Object::default())
+    };
+    ({ $($members:tt)+ }) => {
+        $crate::prelude::Value::Object({
+            let mut object = $crate::prelude::Object::default();
+            $crate::value_internal!(@object object () ($($members)+));
+            object
+        })
+    };
+    // A method call, so that references convert like the values they point to.
+    ($other:expr) => {{
+        use $crate::prelude::ToValueBehavior as _;
+        ($other).to_value()
+    }};
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -270,6 +431,73 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_value_macro() {
+        let name = "Ada";
+        let tags = vec!["admin", "owner"];
+        let key = String::from("computed");
+
+        let value = value!({
+            "name": name,
+            "age": 36,
+            "active": true,
+            "manager": null,
+            "nickname": undefined,
+            "tags": tags,
+            "scores": [1, 2.5, -3, [], {}],
+            "address": {"city": "London", "zip": null},
+            "created": datetime("2023-04-05T00:00:00Z"),
+            (key): 1 + 1,
+        });
+
+        assert_eq!(value.get("name"), Some(&Value::from("Ada")));
+        assert_eq!(value.get("age"), Some(&Value::from(36)));
+        assert_eq!(value.get("active"), Some(&Value::Boolean(true)));
+        assert_eq!(value.get("manager"), Some(&Value::Null));
+        assert_eq!(value.get("nickname"), Some(&Value::Undefined));
+        assert_eq!(
+            value.get("tags"),
+            Some(&Value::from(vec!["admin", "owner"]))
+        );
+        assert_eq!(
+            value.get("scores"),
+            Some(&Value::from(vec![
+                Value::from(1),
+                Value::from(2.5),
+                Value::from(-3),
+                Value::Array(Array::new()),
+                Value::Object(Object::default()),
+            ]))
+        );
+        assert_eq!(
+            value.get("address"),
+            Some(&Value::from(vec![
+                ("city", Value::from("London")),
+                ("zip", Value::Null)
+            ]))
+        );
+        assert_eq!(
+            value.get("created"),
+            Some(&Value::DateTime(DateTime::from("2023-04-05T00:00:00Z")))
+        );
+        assert_eq!(value.get("computed"), Some(&Value::from(2)));
+    }
+
+    #[test]
+    fn test_value_macro_scalars() {
+        assert_eq!(value!(null), Value::Null);
+        assert_eq!(value!(undefined), Value::Undefined);
+        assert_eq!(value!("text"), Value::from("text"));
+        assert_eq!(
+            value!(datetime(0i64)),
+            Value::DateTime(DateTime::from(0i64))
+        );
+        assert_eq!(
+            value!([null, undefined, [true]]),
+            Value::from(vec![Value::Null, Value::Undefined, Value::from(vec![true]),])
+        );
+    }
+
     #[test]
     fn test_from_usize() {
         let number = 1 as usize;
@@ -20,7 +20,7 @@ impl FromValueBehavior for i8 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_i8()
+            number.to_i8(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -32,7 +32,7 @@ impl FromValueBehavior for i16 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_i16()
+            number.to_i16(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -44,7 +44,7 @@ impl FromValueBehavior for i32 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_i32()
+            number.to_i32(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -56,7 +56,7 @@ impl FromValueBehavior for i64 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_i64()
+            number.to_i64(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -68,7 +68,7 @@ impl FromValueBehavior for i128 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_i128()
+            number.to_i128(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -80,7 +80,7 @@ impl FromValueBehavior for u8 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_u8()
+            number.to_u8(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -92,7 +92,7 @@ impl FromValueBehavior for u16 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_u16()
+            number.to_u16(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -104,7 +104,7 @@ impl FromValueBehavior for u32 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_u32()
+            number.to_u32(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -116,7 +116,7 @@ impl FromValueBehavior for u64 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_u64()
+            number.to_u64(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -128,7 +128,7 @@ impl FromValueBehavior for u128 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_u128()
+            number.to_u128(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -140,7 +140,7 @@ impl FromValueBehavior for f32 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_f32()
+            number.to_f32(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -152,7 +152,31 @@ impl FromValueBehavior for f64 {
 
     fn from_value(value: Value) -> Option<Self::Item> {
         if let Value::Number(number) = value {
-            number.get_f64()
+            number.to_f64(ConversionPolicy::Exact)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromValueBehavior for usize {
+    type Item = usize;
+
+    fn from_value(value: Value) -> Option<Self::Item> {
+        if let Value::Number(number) = value {
+            number.to_usize(ConversionPolicy::Exact)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromValueBehavior for isize {
+    type Item = isize;
+
+    fn from_value(value: Value) -> Option<Self::Item> {
+        if let Value::Number(number) = value {
+            number.to_isize(ConversionPolicy::Exact)
         } else {
             None
         }
@@ -386,8 +410,8 @@ where
 
     fn from_value(value: Value) -> Option<Self::Item> {
         match value {
-            Value::Null => None,
-            _ => Some(T::from_value(value)),
+            Value::Null | Value::Undefined => Some(None),
+            _ => T::from_value(value).map(Some),
         }
     }
 }
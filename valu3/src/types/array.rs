@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display, Formatter};
 
@@ -24,7 +25,7 @@ pub trait ArrayBehavior {
 }
 
 /// Represents an array of `Value`s.
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Hash)]
 pub struct Array {
     pub values: Vec<Value>,
 }
@@ -54,6 +55,15 @@ impl Array {
         self.values.get_mut(index)
     }
 
+    /// Returns a mutable reference to the value at the specified index, first growing the
+    /// array with `Null` up to the index when it is out of bounds.
+    pub fn get_or_insert(&mut self, index: usize) -> &mut Value {
+        if index >= self.len() {
+            self.values.resize(index + 1, Value::Null);
+        }
+        &mut self.values[index]
+    }
+
     pub fn clean(&mut self) {
         self.values = Vec::new();
     }
@@ -84,6 +94,24 @@ impl Array {
     pub fn is_empty(&self) -> bool {
         self.values.is_empty()
     }
+
+    /// Sorts the numbers of the array with `Number::total_cmp`, so NaN and infinities have a
+    /// place, and moves the other values after them in their current order.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let mut array = Array::from(vec![Value::from(f64::NAN), Value::from(2), Value::from(-1.5)]);
+    /// array.sort_numbers();
+    /// ```
+    pub fn sort_numbers(&mut self) {
+        self.values.sort_by(|left, right| match (left, right) {
+            (Value::Number(left), Value::Number(right)) => left.total_cmp(right),
+            (Value::Number(_), _) => Ordering::Less,
+            (_, Value::Number(_)) => Ordering::Greater,
+            _ => Ordering::Equal,
+        });
+    }
 }
 
 
@@ -297,4 +325,24 @@ mod tests {
 
         assert!(found_key1 && found_key2);
     }
+
+    #[test]
+    fn array_sort_numbers() {
+        let mut array = Array::from(vec![
+            Value::from("text"),
+            Value::from(f64::NAN),
+            Value::from(2),
+            Value::Null,
+            Value::from(-1.5),
+            Value::from(f64::NEG_INFINITY),
+        ]);
+        array.sort_numbers();
+
+        assert_eq!(array.get(0), Some(&Value::from(f64::NEG_INFINITY)));
+        assert_eq!(array.get(1), Some(&Value::from(-1.5)));
+        assert_eq!(array.get(2), Some(&Value::from(2)));
+        assert!(array.get(3).unwrap().as_number().unwrap().is_nan());
+        assert_eq!(array.get(4), Some(&Value::from("text")));
+        assert_eq!(array.get(5), Some(&Value::Null));
+    }
 }
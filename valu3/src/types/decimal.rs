@@ -0,0 +1,490 @@
+//! Exact decimal numbers, available with the `decimal` feature.
+//!
+//! `Number::Decimal` stores a `BigDecimal`, an arbitrary precision decimal, so amounts such as
+//! `0.1` or `19.99` are kept exactly. Parsing with `ParseOptions::decimal()` reads JSON numbers
+//! with a fraction or an exponent as decimals instead of `f64`, and `Display` never uses
+//! exponent notation.
+//!
+//! Adding, subtracting and multiplying decimals is exact. Division and rounding to a fixed
+//! number of places go through a `DecimalContext`, which sets the scale and `RoundingMode`.
+//!
+//! Decimals have at most `MAX_DECIMAL_DIGITS` digits, and a scale, the number of digits
+//! after the point, within `MAX_DECIMAL_DIGITS` of zero. Parsing fails beyond them and
+//! arithmetic returns `None` or panics, as on overflow, so that untrusted input such as
+//! `1e5000000000` cannot make a decimal too long to write without an exponent.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let price = Number::decimal_from_str("19.99").unwrap();
+//! let total = &price * &Number::from(3u8);
+//! assert_eq!(total.to_string(), "59.97");
+//!
+//! let context = DecimalContext::new(2, RoundingMode::HalfEven);
+//! let share = context.div(&total, &Number::from(7u8)).unwrap();
+//! assert_eq!(share.to_string(), "8.57");
+//! ```
+use crate::prelude::*;
+use bigdecimal::num_bigint::BigInt;
+pub use bigdecimal::{BigDecimal, RoundingMode};
+use bigdecimal::{Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// The largest number of digits of a decimal, and of the absolute value of its scale.
+pub const MAX_DECIMAL_DIGITS: u64 = 10_000;
+
+/// Returns the decimal when it is within `MAX_DECIMAL_DIGITS`.
+pub(crate) fn bounded(value: BigDecimal) -> Option<BigDecimal> {
+    let within = value.fractional_digit_count().unsigned_abs() <= MAX_DECIMAL_DIGITS
+        && value.digits() <= MAX_DECIMAL_DIGITS;
+    within.then_some(value)
+}
+
+/// The scale and rounding applied to decimal results that are not kept exact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalContext {
+    /// Number of digits after the decimal point.
+    pub scale: i64,
+    pub rounding: RoundingMode,
+}
+
+/// 28 decimal places with banker's rounding, `RoundingMode::HalfEven`.
+impl Default for DecimalContext {
+    fn default() -> Self {
+        DecimalContext {
+            scale: 28,
+            rounding: RoundingMode::HalfEven,
+        }
+    }
+}
+
+impl DecimalContext {
+    pub fn new(scale: i64, rounding: RoundingMode) -> Self {
+        DecimalContext { scale, rounding }
+    }
+
+    /// Converts the number to a decimal rounded to the scale of the context.
+    ///
+    /// Returns `None` for empty numbers, NaN and infinities, and for results beyond
+    /// `MAX_DECIMAL_DIGITS`, like the other operations of the context.
+    pub fn round(&self, number: &Number) -> Option<Number> {
+        Some(Number::from(self.round_decimal(&number.to_decimal()?)?))
+    }
+
+    /// Adds two numbers as decimals, rounding the result to the scale of the context.
+    pub fn add(&self, left: &Number, right: &Number) -> Option<Number> {
+        let result = left.to_decimal()? + right.to_decimal()?;
+        Some(Number::from(self.round_decimal(&result)?))
+    }
+
+    /// Subtracts two numbers as decimals, rounding the result to the scale of the context.
+    pub fn sub(&self, left: &Number, right: &Number) -> Option<Number> {
+        let result = left.to_decimal()? - right.to_decimal()?;
+        Some(Number::from(self.round_decimal(&result)?))
+    }
+
+    /// Multiplies two numbers as decimals, rounding the result to the scale of the context.
+    pub fn mul(&self, left: &Number, right: &Number) -> Option<Number> {
+        let result = left.to_decimal()? * right.to_decimal()?;
+        Some(Number::from(self.round_decimal(&result)?))
+    }
+
+    /// Divides two numbers as decimals, rounding the quotient to the scale of the context.
+    ///
+    /// The quotient is rounded once from its exact value. Returns `None` when dividing by
+    /// zero.
+    pub fn div(&self, left: &Number, right: &Number) -> Option<Number> {
+        let quotient = self.div_decimal(&left.to_decimal()?, &right.to_decimal()?)?;
+        Some(Number::from(quotient))
+    }
+
+    pub(crate) fn div_decimal(&self, left: &BigDecimal, right: &BigDecimal) -> Option<BigDecimal> {
+        if right.is_zero() || self.scale.unsigned_abs() > MAX_DECIMAL_DIGITS {
+            return None;
+        }
+
+        // left = a * 10^-a_scale and right = b * 10^-b_scale, so the quotient carrying one
+        // digit more than the scale is a * 10^shift / b.
+        let (a, a_scale) = left.as_bigint_and_exponent();
+        let (b, b_scale) = right.as_bigint_and_exponent();
+        let shift = self.scale + 1 - a_scale + b_scale;
+        let (numerator, denominator) = if shift >= 0 {
+            (a * ten_pow(shift)?, b)
+        } else {
+            (a, b * ten_pow(-shift)?)
+        };
+
+        let mut digits = &numerator / &denominator;
+        let remainder = &numerator % &denominator;
+
+        // A non-zero remainder behind a final 0 or 5 would otherwise look like an exact
+        // value or an exact tie to the rounding below.
+        if !remainder.is_zero() {
+            let last = (&digits % 10i32).to_i8().unwrap_or(0).abs();
+            if last == 0 || last == 5 {
+                let negative = numerator.is_negative() != denominator.is_negative();
+                digits += if negative { -1 } else { 1 };
+            }
+        }
+
+        let quotient = BigDecimal::new(digits, self.scale + 1);
+        self.round_decimal(&quotient)
+    }
+
+    fn round_decimal(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        if self.scale.unsigned_abs() > MAX_DECIMAL_DIGITS {
+            return None;
+        }
+        bounded(value.with_scale_round(self.scale, self.rounding))
+    }
+}
+
+/// Returns `10^exponent`, or `None` for an exponent that is negative or larger than
+/// decimals within `MAX_DECIMAL_DIGITS` need.
+fn ten_pow(exponent: i64) -> Option<BigInt> {
+    let exponent = u32::try_from(exponent)
+        .ok()
+        .filter(|exponent| u64::from(*exponent) <= 4 * MAX_DECIMAL_DIGITS)?;
+    Some(BigInt::from(10u8).pow(exponent))
+}
+
+impl Number {
+    /// Parses a decimal literal such as `"19.99"` or `"-1.5e3"` exactly, without going
+    /// through `f64`.
+    ///
+    /// Fails with `Error::NotNumber` beyond `MAX_DECIMAL_DIGITS`.
+    pub fn decimal_from_str(value: &str) -> Result<Number, Error> {
+        BigDecimal::from_str(value)
+            .ok()
+            .and_then(bounded)
+            .map(Number::from)
+            .ok_or(Error::NotNumber)
+    }
+
+    /// Converts the number to a `BigDecimal`.
+    ///
+    /// Integers and decimals convert exactly. Floats convert to the shortest decimal that
+    /// reads back as the same float, so `0.1f64` becomes `0.1`. Returns `None` for empty
+    /// numbers, NaN and infinities.
+    pub fn to_decimal(&self) -> Option<BigDecimal> {
+        match self {
+            Number::U8(value) => Some(BigDecimal::from(*value)),
+            Number::U16(value) => Some(BigDecimal::from(*value)),
+            Number::U32(value) => Some(BigDecimal::from(*value)),
+            Number::U64(value) => Some(BigDecimal::from(*value)),
+            Number::U128(value) => Some(BigDecimal::from(*value)),
+            Number::I8(value) => Some(BigDecimal::from(*value)),
+            Number::I16(value) => Some(BigDecimal::from(*value)),
+            Number::I32(value) => Some(BigDecimal::from(*value)),
+            Number::I64(value) => Some(BigDecimal::from(*value)),
+            Number::I128(value) => Some(BigDecimal::from(*value)),
+            Number::F32(value) if value.is_finite() => {
+                BigDecimal::from_str(&value.to_string()).ok()
+            }
+            Number::F64(value) if value.is_finite() => {
+                BigDecimal::from_str(&value.to_string()).ok()
+            }
+            Number::Decimal(value) => Some(value.as_ref().clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Orders numbers when at least one of them is a decimal, on the decimal value of both.
+pub(crate) fn cmp_decimal(left: &Number, right: &Number) -> Option<Ordering> {
+    // Empty numbers come first and infinities lie beyond every decimal. NaN is unordered.
+    fn rank(number: &Number) -> Option<i8> {
+        match number {
+            Number::Empty => Some(-2),
+            Number::F32(value) if value.is_nan() => None,
+            Number::F64(value) if value.is_nan() => None,
+            Number::F32(value) if value.is_infinite() => Some(value.signum() as i8),
+            Number::F64(value) if value.is_infinite() => Some(value.signum() as i8),
+            _ => Some(0),
+        }
+    }
+
+    match (rank(left)?, rank(right)?) {
+        (0, 0) => Some(exact_decimal(left)?.cmp(&exact_decimal(right)?)),
+        (left, right) => Some(left.cmp(&right)),
+    }
+}
+
+/// Converts the number to the decimal of its exact value.
+///
+/// Unlike `Number::to_decimal`, floats convert to the exact value of their binary
+/// representation, so `0.1f64` is not equal to the decimal `0.1`. Comparing on this value
+/// keeps equality transitive between decimals, `f32` and `f64`.
+fn exact_decimal(number: &Number) -> Option<BigDecimal> {
+    match number {
+        Number::F32(value) => BigDecimal::try_from(*value as f64).ok(),
+        Number::F64(value) => BigDecimal::try_from(*value).ok(),
+        _ => number.to_decimal(),
+    }
+}
+
+/// Returns the `f64` equal to the decimal, if the decimal has an exact binary representation.
+pub(crate) fn exact_f64(value: &BigDecimal) -> Option<f64> {
+    // value = digits * 10^-scale, which a float holds only if it is odd * 2^exponent with an
+    // odd part of at most 53 bits. Floats have at most 1074 digits after the point.
+    let (digits, scale) = value.normalized().into_bigint_and_exponent();
+    if digits.is_zero() {
+        return Some(0.0);
+    }
+    if !(-309..=1074).contains(&scale) {
+        return None;
+    }
+
+    let (odd, mut exponent) = if scale <= 0 {
+        (digits * five_pow(-scale), -scale)
+    } else {
+        let divisor = five_pow(scale);
+        if !(&digits % &divisor).is_zero() {
+            return None;
+        }
+        (digits / divisor, -scale)
+    };
+    let zeros = odd.trailing_zeros().unwrap_or(0);
+    let odd = odd >> zeros;
+    exponent += zeros as i64;
+
+    if odd.bits() > 53 || !(-1074..=1023).contains(&exponent) {
+        return None;
+    }
+    let power = if exponent >= -1022 {
+        f64::from_bits(((exponent + 1023) as u64) << 52)
+    } else {
+        f64::from_bits(1 << (exponent + 1074))
+    };
+    let float = odd.to_i64()? as f64 * power;
+
+    // The product rounds when the result falls below the normal range.
+    (BigDecimal::try_from(float).ok()? == *value).then_some(float)
+}
+
+fn five_pow(exponent: i64) -> BigInt {
+    BigInt::from(5u8).pow(exponent as u32)
+}
+
+impl From<BigDecimal> for Number {
+    fn from(value: BigDecimal) -> Self {
+        Number::Decimal(Box::new(value))
+    }
+}
+
+impl PrimitiveType for BigDecimal {}
+
+impl ToValueBehavior for BigDecimal {
+    fn to_value(&self) -> Value {
+        Value::Number(Number::from(self.clone()))
+    }
+}
+
+impl FromValueBehavior for BigDecimal {
+    type Item = BigDecimal;
+
+    fn from_value(value: Value) -> Option<Self::Item> {
+        if let Value::Number(number) = value {
+            number.get_decimal().cloned()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn decimal(value: &str) -> Number {
+        Number::decimal_from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_exact() {
+        let payload = "{\"price\": 0.1, \"count\": 3}";
+        let value = Value::payload_to_value_with(payload, &ParseOptions::decimal()).unwrap();
+        let price = value.get("price").unwrap().as_number().unwrap();
+
+        assert!(price.is_decimal());
+        assert_eq!(price.to_string(), "0.1");
+        assert!(value.get("count").unwrap().as_number().unwrap().is_i32());
+        assert!(Value::payload_to_value(payload)
+            .unwrap()
+            .get("price")
+            .unwrap()
+            .is_f64());
+        assert_eq!(decimal("1.5e3").to_string(), "1500");
+    }
+
+    #[test]
+    fn test_display_without_exponent() {
+        assert_eq!(decimal("1e-10").to_string(), "0.0000000001");
+        assert_eq!(decimal("12e20").to_string(), "1200000000000000000000");
+        assert_eq!(
+            Value::from(decimal("1e-8")).to_json(JsonMode::Inline),
+            "0.00000001"
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let payload = "[1e5000000000]";
+        assert!(Value::payload_to_value_with(payload, &ParseOptions::decimal()).is_err());
+        assert_eq!(Number::decimal_from_str("1e-10001"), Err(Error::NotNumber));
+        assert_eq!(
+            Number::decimal_from_str(&"9".repeat(10_001)),
+            Err(Error::NotNumber)
+        );
+        assert_eq!(decimal("1e10000").to_string().len(), 10_001);
+
+        let large = decimal("1e6000");
+        assert_eq!(large.checked_mul(&large), None);
+        assert_eq!(decimal("1e-6000").checked_mul(&decimal("1e-6000")), None);
+        assert_eq!(
+            DecimalContext::new(1_000_000_000, RoundingMode::HalfUp).round(&decimal("1.5")),
+            None
+        );
+        assert_eq!(
+            DecimalContext::new(2, RoundingMode::HalfUp).div(&decimal("1"), &decimal("1e-9999")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exact_arithmetic() {
+        assert_eq!(decimal("0.1") + decimal("0.2"), decimal("0.3"));
+        assert_eq!(&decimal("19.99") * &Number::from(3u8), decimal("59.97"));
+        assert!((decimal("1.5") - 2i32).is_decimal());
+        assert_eq!(-decimal("2.50"), decimal("-2.5"));
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let half_even = DecimalContext::new(0, RoundingMode::HalfEven);
+        let half_up = DecimalContext::new(0, RoundingMode::HalfUp);
+        let floor = DecimalContext::new(1, RoundingMode::Floor);
+
+        assert_eq!(half_even.round(&decimal("2.5")), Some(decimal("2")));
+        assert_eq!(half_up.round(&decimal("2.5")), Some(decimal("3")));
+        assert_eq!(floor.round(&decimal("-1.25")), Some(decimal("-1.3")));
+        assert_eq!(
+            DecimalContext::new(2, RoundingMode::HalfUp)
+                .round(&decimal("1.005"))
+                .unwrap()
+                .to_string(),
+            "1.01"
+        );
+    }
+
+    #[test]
+    fn test_division() {
+        let context = DecimalContext::new(4, RoundingMode::HalfUp);
+
+        assert_eq!(
+            context.div(&Number::from(1u8), &Number::from(3u8)),
+            Some(decimal("0.3333"))
+        );
+        assert_eq!(
+            context.div(&Number::from(-2i8), &Number::from(3u8)),
+            Some(decimal("-0.6667"))
+        );
+        // 0.00005000001 must round up even though the fifth digit is an exact 5.
+        assert_eq!(
+            DecimalContext::new(4, RoundingMode::HalfDown)
+                .div(&decimal("0.00005000001"), &Number::from(1u8)),
+            Some(decimal("0.0001"))
+        );
+        assert_eq!(context.div(&Number::from(1u8), &decimal("0")), None);
+        assert_eq!(decimal("1") / decimal("8"), decimal("0.125"));
+    }
+
+    #[test]
+    fn test_comparison() {
+        assert_ne!(decimal("0.1"), Number::from(0.1f64));
+        assert!(decimal("0.1") < Number::from(0.1f64));
+        assert_eq!(
+            decimal("0.1000000000000000055511151231257827021181583404541015625"),
+            Number::from(0.1f64)
+        );
+        assert_eq!(decimal("0.5"), Number::from(0.5f32));
+        assert_eq!(decimal("5.00"), Number::from(5u8));
+        assert!(decimal("1e40") > Number::from(u128::MAX));
+        assert!(decimal("-1") > Number::from(f64::NEG_INFINITY));
+        assert_eq!(decimal("1").partial_cmp(&Number::from(f64::NAN)), None);
+    }
+
+    #[test]
+    fn test_hash_matches_equality() {
+        use std::collections::HashSet;
+
+        let numbers = [
+            (decimal("5.00"), Number::from(5u8)),
+            (decimal("0.5"), Number::from(0.5f32)),
+            (decimal("-3"), Number::from(-3.0f32)),
+            (decimal("1e400"), decimal("10e399")),
+            (
+                decimal("0.100000001490116119384765625"),
+                Number::from(0.1f32),
+            ),
+        ];
+
+        for (left, right) in numbers {
+            let set: HashSet<HashableValue> = [left, right]
+                .into_iter()
+                .map(|number| HashableValue::from(Value::from(number)))
+                .collect();
+            assert_eq!(set.len(), 1);
+        }
+
+        let set: HashSet<HashableValue> = [decimal("0.1"), Number::from(0.1f32)]
+            .into_iter()
+            .map(|number| HashableValue::from(Value::from(number)))
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_equality_is_transitive() {
+        let numbers = [
+            decimal("0.1"),
+            Number::from(0.1f32),
+            Number::from(0.1f64),
+            decimal("0.100000001490116119384765625"),
+            decimal("0.5"),
+            Number::from(0.5f32),
+            Number::from(0.5f64),
+            Number::from(f64::from_bits(1)),
+        ];
+
+        for a in &numbers {
+            for b in &numbers {
+                for c in &numbers {
+                    if a == b && b == c {
+                        assert_eq!(a, c);
+                    }
+                }
+            }
+        }
+        assert_ne!(decimal("0.1"), Number::from(0.1f32));
+        assert_eq!(
+            Number::from(f64::from_bits(1)),
+            Number::from(BigDecimal::try_from(f64::from_bits(1)).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_behavior() {
+        let mut value = Value::from(1u8);
+        value.set_decimal(BigDecimal::from(7u8));
+
+        assert!(value.is_decimal());
+        assert_eq!(value.get_decimal(), Some(&BigDecimal::from(7u8)));
+        assert_eq!(value.number_type(), NumberType::Decimal);
+        assert_eq!(
+            BigDecimal::from_value(Value::from(decimal("2.5"))),
+            Some(BigDecimal::from(25u8) / BigDecimal::from(10u8))
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! A `Value` wrapper with `Eq` and `Hash`, to use values as keys of a `HashMap` or a
+//! `HashSet`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::collections::HashSet;
+//!
+//! let values = vec![Value::from(1u8), Value::from(1.0f64), Value::from(f64::NAN), Value::from(f64::NAN)];
+//! let unique: HashSet<HashableValue> = values.into_iter().map(HashableValue::from).collect();
+//! assert_eq!(unique.len(), 2);
+//! ```
+use crate::prelude::*;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+/// A `Value` compared with `==`, except that NaNs with the same sign are equal.
+///
+/// Numbers compare by value, so `1u8`, `1i64` and `1.0f64` are the same key, as are `0.0`
+/// and `-0.0`. Objects compare by entries whether they hold a `HashMap` or a `BTreeMap`.
+#[derive(Debug, Clone)]
+pub struct HashableValue(pub Value);
+
+impl HashableValue {
+    pub fn into_inner(self) -> Value {
+        self.0
+    }
+}
+
+fn canonical_eq(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.value_cmp(right).is_eq(),
+        (Value::Array(left), Value::Array(right)) => {
+            left.len() == right.len()
+                && left
+                    .into_iter()
+                    .zip(right)
+                    .all(|(left, right)| canonical_eq(left, right))
+        }
+        (Value::Object(left), Value::Object(right)) => {
+            left.len() == right.len()
+                && left.iter().all(|(key, value)| {
+                    right
+                        .get_by_key(key)
+                        .is_some_and(|other| canonical_eq(value, other))
+                })
+        }
+        _ => left == right,
+    }
+}
+
+impl PartialEq for HashableValue {
+    fn eq(&self, other: &Self) -> bool {
+        canonical_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for HashableValue {}
+
+impl Hash for HashableValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Deref for HashableValue {
+    type Target = Value;
+
+    fn deref(&self) -> &Value {
+        &self.0
+    }
+}
+
+impl From<Value> for HashableValue {
+    fn from(value: Value) -> Self {
+        HashableValue(value)
+    }
+}
+
+impl From<HashableValue> for Value {
+    fn from(value: HashableValue) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::hash::{Hash, Hasher};
+
+    fn hash(value: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_numbers_hash_by_value() {
+        let pairs = [
+            (Value::from(1u8), Value::from(1.0f64)),
+            (Value::from(-7i8), Value::from(-7.0f32)),
+            (Value::from(0.0f64), Value::from(-0.0f64)),
+            (Value::from(u64::MAX), Value::from(u64::MAX as u128)),
+            (Value::from(0.5f32), Value::from(0.5f64)),
+        ];
+
+        for (left, right) in pairs {
+            assert_eq!(left, right);
+            assert_eq!(hash(&left), hash(&right));
+        }
+        assert_eq!(hash(&Value::from(f64::NAN)), hash(&Value::from(-f32::NAN)));
+    }
+
+    #[test]
+    fn test_objects_equal_across_maps() {
+        let mut hash_map = HashMap::new();
+        let mut btree_map = BTreeMap::new();
+        for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+            hash_map.insert(key, value);
+            btree_map.insert(key, value);
+        }
+
+        let left = Value::from(hash_map);
+        let right = Value::from(btree_map);
+        assert_eq!(left, right);
+        assert_eq!(hash(&left), hash(&right));
+        assert_ne!(left, Value::from(vec![("a", 1), ("b", 2)]));
+    }
+
+    #[test]
+    fn test_hashable_value_as_key() {
+        let values = vec![
+            Value::from(1u8),
+            Value::from(1.0f64),
+            Value::from(f64::NAN),
+            Value::from(vec![f64::NAN]),
+            Value::from(vec![f64::NAN]),
+            Value::from(f64::NAN),
+            Value::from("1"),
+        ];
+        let unique: HashSet<HashableValue> = values.into_iter().map(HashableValue::from).collect();
+        assert_eq!(unique.len(), 4);
+
+        let mut cache = HashMap::new();
+        cache.insert(HashableValue::from(Value::from(0.0f64)), "zero");
+        assert_eq!(
+            cache.get(&HashableValue::from(Value::from(-0i32))),
+            Some(&"zero")
+        );
+    }
+}
@@ -2,5 +2,13 @@ pub mod value_key;
 pub mod array;
 pub mod object;
 pub mod number;
+pub mod number_ops;
+pub mod number_conversion;
+pub mod number_format;
+pub mod number_parse;
+#[cfg(feature = "decimal")]
+pub mod decimal;
 pub mod datetime;
+pub mod hashable;
+pub mod ord_value;
 pub mod stringb;
\ No newline at end of file
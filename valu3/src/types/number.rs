@@ -1,12 +1,16 @@
 //! A module to handle different number types, provide safe and unsafe access methods, and
 //! perform checks on number properties.
 //!
-//! The `Number` struct is used to store multiple numeric types, and provides various methods
-//! to set and retrieve these values safely and unsafely, as well as check their properties.
+//! The `Number` enum stores a value of one of the supported numeric types, and provides various
+//! methods to set and retrieve these values safely and unsafely, as well as check their properties.
 //!
 //! The `NumberType` enum is used to identify the type of number stored in a `Number` instance.
 use crate::prelude::*;
+#[cfg(feature = "decimal")]
+use bigdecimal::{Signed, ToPrimitive, Zero};
+use std::cmp::Ordering;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 
 pub trait NumberBehavior {
     /// Sets the value of the `Number` struct to the given `u8` value.
@@ -45,6 +49,9 @@ pub trait NumberBehavior {
 
     fn set_f64(&mut self, value: f64);
 
+    #[cfg(feature = "decimal")]
+    fn set_decimal(&mut self, value: BigDecimal);
+
     /// Returns the `u8` value stored in the `Number` struct, if any.
     ///
     /// # Returns
@@ -82,6 +89,9 @@ pub trait NumberBehavior {
 
     fn get_f64(&self) -> Option<f64>;
 
+    #[cfg(feature = "decimal")]
+    fn get_decimal(&self) -> Option<&BigDecimal>;
+
     /// Returns the `u8` value stored in the `Number` struct, without checking if it exists.
     ///
     /// # Safety
@@ -120,6 +130,9 @@ pub trait NumberBehavior {
 
     fn get_f64_unsafe(&self) -> f64;
 
+    #[cfg(feature = "decimal")]
+    fn get_decimal_unsafe(&self) -> &BigDecimal;
+
     /// Checks if the stored number is of type `i8`.
     ///
     /// # Returns
@@ -157,6 +170,9 @@ pub trait NumberBehavior {
 
     fn is_f64(&self) -> bool;
 
+    #[cfg(feature = "decimal")]
+    fn is_decimal(&self) -> bool;
+
     /// Checks if the `Number` struct contains any value.
     ///
     /// # Returns
@@ -198,7 +214,10 @@ pub trait NumberBehavior {
 }
 
 /// An enum representing different numeric types.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// The `decimal` feature adds the `Decimal` type, so matches need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
 pub enum NumberType {
     U8,
     U16,
@@ -212,10 +231,14 @@ pub enum NumberType {
     I128,
     F32,
     F64,
+    #[cfg(feature = "decimal")]
+    Decimal,
     Unknown,
 }
 
-/// A struct representing a number that can store different numeric types.
+/// A number stored as exactly one of the supported numeric types.
+///
+/// `Number::default()` holds no value at all, its `number_type()` is `NumberType::Unknown`.
 ///
 /// # Examples
 ///
@@ -224,28 +247,35 @@ pub enum NumberType {
 /// num.set_u8(42);
 /// assert_eq!(num.get_u8(), Some(42));
 /// ```
-#[derive(Debug, Clone, PartialEq, Default, PartialOrd)]
-pub struct Number {
-    pub u8: Option<u8>,
-    pub u16: Option<u16>,
-    pub u32: Option<u32>,
-    pub u64: Option<u64>,
-    pub u128: Option<u128>,
-    pub i8: Option<i8>,
-    pub i16: Option<i16>,
-    pub i32: Option<i32>,
-    pub i64: Option<i64>,
-    pub i128: Option<i128>,
-    pub f32: Option<f32>,
-    pub f64: Option<f64>,
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub enum Number {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    F32(f32),
+    F64(f64),
+    /// An exact decimal, see the `decimal` module.
+    #[cfg(feature = "decimal")]
+    Decimal(Box<BigDecimal>),
+    /// No value stored.
+    #[default]
+    Empty,
 }
 
 impl Number {
-    /// Empties the `Number` struct by removing any stored value.
+    /// Empties the `Number` by removing any stored value.
     ///
     /// # Returns
     ///
-    /// A mutable reference to the `Number` struct after removing any stored value.
+    /// A mutable reference to the `Number` after removing any stored value.
     ///
     /// # Examples
     ///
@@ -256,230 +286,578 @@ impl Number {
     /// assert_eq!(num.is_number(), false);
     /// ```
     pub fn clean(&mut self) -> &mut Self {
-        self.u8 = None;
-        self.u16 = None;
-        self.u32 = None;
-        self.u64 = None;
-        self.u128 = None;
-        self.i8 = None;
-        self.i16 = None;
-        self.i32 = None;
-        self.i64 = None;
-        self.i128 = None;
-        self.f32 = None;
-        self.f64 = None;
+        *self = Number::Empty;
         self
     }
+
+    /// Widens the stored value to the largest type of its family.
+    pub(crate) fn widen(&self) -> Option<Widened> {
+        match *self {
+            Number::U8(value) => Some(Widened::Unsigned(value as u128)),
+            Number::U16(value) => Some(Widened::Unsigned(value as u128)),
+            Number::U32(value) => Some(Widened::Unsigned(value as u128)),
+            Number::U64(value) => Some(Widened::Unsigned(value as u128)),
+            Number::U128(value) => Some(Widened::Unsigned(value)),
+            Number::I8(value) => Some(Widened::Signed(value as i128)),
+            Number::I16(value) => Some(Widened::Signed(value as i128)),
+            Number::I32(value) => Some(Widened::Signed(value as i128)),
+            Number::I64(value) => Some(Widened::Signed(value as i128)),
+            Number::I128(value) => Some(Widened::Signed(value)),
+            Number::F32(value) => Some(Widened::Float(value as f64)),
+            Number::F64(value) => Some(Widened::Float(value)),
+            #[cfg(feature = "decimal")]
+            Number::Decimal(ref value) => value.to_f64().map(Widened::Float),
+            Number::Empty => None,
+        }
+    }
+
+    /// Checks that both numbers have the same `NumberType` and the same value.
+    ///
+    /// `==` compares values only, so `Number::from(1u8) == Number::from(1.0f64)`,
+    /// while `strict_eq` tells them apart.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// assert!(Number::from(1u8).strict_eq(&Number::from(1u8)));
+    /// assert!(!Number::from(1u8).strict_eq(&Number::from(1i32)));
+    /// ```
+    pub fn strict_eq(&self, other: &Number) -> bool {
+        self.number_type() == other.number_type() && self == other
+    }
+
+    /// Orders numbers by value, and numbers with the same value by their `NumberType`.
+    ///
+    /// Unlike `partial_cmp`, only numbers for which `strict_eq` holds are `Equal`.
+    pub fn strict_cmp(&self, other: &Number) -> Option<Ordering> {
+        match self.partial_cmp(other)? {
+            Ordering::Equal => Some(self.number_type().cmp(&other.number_type())),
+            ordering => Some(ordering),
+        }
+    }
+
+    /// Orders every number by value, the order that `total_cmp` refines.
+    ///
+    /// Empty numbers come first, then NaN with the sign bit set, every other number by
+    /// value, and NaN without the sign bit last. Numbers equal by `==` are `Equal`, as are
+    /// NaNs with the same sign.
+    pub(crate) fn value_cmp(&self, other: &Number) -> Ordering {
+        fn rank(number: &Number) -> i8 {
+            match number {
+                Number::Empty => -2,
+                Number::F32(value) if value.is_nan() => sign_rank(value.is_sign_negative()),
+                Number::F64(value) if value.is_nan() => sign_rank(value.is_sign_negative()),
+                _ => 0,
+            }
+        }
+
+        fn sign_rank(negative: bool) -> i8 {
+            if negative {
+                -1
+            } else {
+                1
+            }
+        }
+
+        match (rank(self), rank(other)) {
+            (0, 0) => self.partial_cmp(other).unwrap_or(Ordering::Equal),
+            (left, right) => left.cmp(&right),
+        }
+    }
+
+    /// Orders every number, extending `strict_cmp` with the IEEE 754 totalOrder of floats.
+    ///
+    /// Empty numbers come first, then NaN with the sign bit set, every other number by
+    /// value, and NaN without the sign bit last. `-0.0` comes before `0.0` of the same type,
+    /// so only numbers for which `strict_eq` holds with the same sign are `Equal`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let mut numbers = vec![Number::from(f64::NAN), Number::from(2u8), Number::from(-1.5)];
+    /// numbers.sort_by(Number::total_cmp);
+    /// ```
+    pub fn total_cmp(&self, other: &Number) -> Ordering {
+        fn float(number: &Number) -> Option<f64> {
+            match *number {
+                Number::F32(value) => Some(value as f64),
+                Number::F64(value) => Some(value),
+                _ => None,
+            }
+        }
+
+        let by_value = self.value_cmp(other);
+
+        // Equal floats only differ by the sign of zero or the payload of NaN.
+        let by_bits = match (float(self), float(other)) {
+            (Some(left), Some(right)) => left.total_cmp(&right),
+            _ => Ordering::Equal,
+        };
+
+        by_value
+            .then(by_bits)
+            .then_with(|| self.number_type().cmp(&other.number_type()))
+    }
+
+    /// Checks that the number is not NaN or an infinity.
+    pub fn is_finite(&self) -> bool {
+        match *self {
+            Number::F32(value) => value.is_finite(),
+            Number::F64(value) => value.is_finite(),
+            _ => true,
+        }
+    }
+
+    pub fn is_nan(&self) -> bool {
+        match *self {
+            Number::F32(value) => value.is_nan(),
+            Number::F64(value) => value.is_nan(),
+            _ => false,
+        }
+    }
+
+    /// Creates a number from an `f32`, rejecting NaN and infinities with `Error::NotFinite`.
+    pub fn finite_f32(value: f32) -> Result<Number, Error> {
+        if value.is_finite() {
+            Ok(Number::F32(value))
+        } else {
+            Err(Error::NotFinite)
+        }
+    }
+
+    /// Creates a number from an `f64`, rejecting NaN and infinities with `Error::NotFinite`.
+    pub fn finite_f64(value: f64) -> Result<Number, Error> {
+        if value.is_finite() {
+            Ok(Number::F64(value))
+        } else {
+            Err(Error::NotFinite)
+        }
+    }
+}
+
+/// A number widened to `i128`, `u128` or `f64`, which hold every value of their family.
+#[derive(Clone, Copy)]
+pub(crate) enum Widened {
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+}
+
+/// `2^127` and `2^128`, the first floats beyond the range of `i128` and `u128`.
+pub(crate) const I128_LIMIT: f64 = 170141183460469231731687303715884105728.0;
+pub(crate) const U128_LIMIT: f64 = 340282366920938463463374607431768211456.0;
+
+/// Compares a float with an `i128` without rounding the integer.
+fn cmp_float_signed(float: f64, integer: i128) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    }
+    if float >= I128_LIMIT {
+        return Some(Ordering::Greater);
+    }
+    if float < -I128_LIMIT {
+        return Some(Ordering::Less);
+    }
+
+    // Inside the range the integral part of the float converts exactly.
+    let truncated = float.trunc();
+    match (truncated as i128).cmp(&integer) {
+        Ordering::Equal => float.partial_cmp(&truncated),
+        ordering => Some(ordering),
+    }
+}
+
+/// Compares a float with a `u128` without rounding the integer.
+fn cmp_float_unsigned(float: f64, integer: u128) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    }
+    if float < 0.0 {
+        return Some(Ordering::Less);
+    }
+    if float >= U128_LIMIT {
+        return Some(Ordering::Greater);
+    }
+
+    let truncated = float.trunc();
+    match (truncated as u128).cmp(&integer) {
+        Ordering::Equal => float.partial_cmp(&truncated),
+        ordering => Some(ordering),
+    }
+}
+
+impl PartialEq for Widened {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Widened {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (*self, *other) {
+            (Widened::Signed(left), Widened::Signed(right)) => Some(left.cmp(&right)),
+            (Widened::Unsigned(left), Widened::Unsigned(right)) => Some(left.cmp(&right)),
+            (Widened::Float(left), Widened::Float(right)) => left.partial_cmp(&right),
+            (Widened::Signed(left), Widened::Unsigned(right)) => {
+                if left < 0 {
+                    Some(Ordering::Less)
+                } else {
+                    Some((left as u128).cmp(&right))
+                }
+            }
+            (Widened::Unsigned(left), Widened::Signed(right)) => {
+                if right < 0 {
+                    Some(Ordering::Greater)
+                } else {
+                    Some(left.cmp(&(right as u128)))
+                }
+            }
+            (Widened::Float(left), Widened::Signed(right)) => cmp_float_signed(left, right),
+            (Widened::Float(left), Widened::Unsigned(right)) => cmp_float_unsigned(left, right),
+            (Widened::Signed(left), Widened::Float(right)) => {
+                cmp_float_signed(right, left).map(Ordering::reverse)
+            }
+            (Widened::Unsigned(left), Widened::Float(right)) => {
+                cmp_float_unsigned(right, left).map(Ordering::reverse)
+            }
+        }
+    }
+}
+
+/// Compares numbers by their mathematical value, whatever their types.
+///
+/// `Number::from(1u8) == Number::from(1i32)` and `Number::from(1i32) == Number::from(1.0f64)`.
+/// Integers are never rounded through a float, so `i128::MAX` and `i128::MAX as f64` are
+/// different numbers. NaN is not equal to anything, and two empty numbers are equal.
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+/// Orders numbers by their mathematical value, like `PartialEq`.
+///
+/// Returns `None` when either side is NaN. An empty `Number` is smaller than any value.
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        #[cfg(feature = "decimal")]
+        if self.is_decimal() || other.is_decimal() {
+            return crate::types::decimal::cmp_decimal(self, other);
+        }
+
+        match (self.widen(), other.widen()) {
+            (Some(left), Some(right)) => left.partial_cmp(&right),
+            (None, None) => Some(Ordering::Equal),
+            (None, Some(_)) => Some(Ordering::Less),
+            (Some(_), None) => Some(Ordering::Greater),
+        }
+    }
+}
+
+/// Hashes numbers by their mathematical value, so numbers equal by `PartialEq` hash alike.
+///
+/// Integral floats hash as the integer of the same value, `-0.0` as `0`, and every NaN the
+/// same way.
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        #[cfg(feature = "decimal")]
+        if let Number::Decimal(value) = self {
+            // Decimals equal to an integer or a float hash like it, the others on their value.
+            if value.is_integer() {
+                if let Some(value) = value.to_u128() {
+                    return Widened::Unsigned(value).hash(state);
+                }
+                if let Some(value) = value.to_i128() {
+                    return Widened::Signed(value).hash(state);
+                }
+            }
+            return match crate::types::decimal::exact_f64(value) {
+                Some(float) => Widened::Float(float).hash(state),
+                None => (5u8, value.as_ref()).hash(state),
+            };
+        }
+
+        match self.widen() {
+            Some(widened) => widened.hash(state),
+            None => state.write_u8(0),
+        }
+    }
+}
+
+impl Hash for Widened {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Widened::Unsigned(value) => (1u8, value).hash(state),
+            Widened::Signed(value) if value >= 0 => (1u8, value as u128).hash(state),
+            Widened::Signed(value) => (2u8, value).hash(state),
+            Widened::Float(value) if value.is_nan() => state.write_u8(3),
+            Widened::Float(value) if value.fract() == 0.0 && (0.0..U128_LIMIT).contains(&value) => {
+                Widened::Unsigned(value as u128).hash(state)
+            }
+            Widened::Float(value) if value.fract() == 0.0 && value >= -I128_LIMIT => {
+                Widened::Signed(value as i128).hash(state)
+            }
+            Widened::Float(value) => (4u8, value.to_bits()).hash(state),
+        }
+    }
 }
 
 // Implementations of methods for setting and getting number values safely and unsafely,
 // as well as checking their properties and identifying the number type.
 impl NumberBehavior for Number {
     fn set_u8(&mut self, value: u8) {
-        self.u8 = Some(value);
+        *self = Number::U8(value);
     }
 
     fn set_u16(&mut self, value: u16) {
-        self.u16 = Some(value);
+        *self = Number::U16(value);
     }
 
     fn set_u32(&mut self, value: u32) {
-        self.u32 = Some(value);
+        *self = Number::U32(value);
     }
 
     fn set_u64(&mut self, value: u64) {
-        self.u64 = Some(value);
+        *self = Number::U64(value);
     }
 
     fn set_u128(&mut self, value: u128) {
-        self.u128 = Some(value);
+        *self = Number::U128(value);
     }
 
     fn set_i8(&mut self, value: i8) {
-        self.i8 = Some(value);
+        *self = Number::I8(value);
     }
 
     fn set_i16(&mut self, value: i16) {
-        self.i16 = Some(value);
+        *self = Number::I16(value);
     }
 
     fn set_i32(&mut self, value: i32) {
-        self.i32 = Some(value);
+        *self = Number::I32(value);
     }
 
     fn set_i64(&mut self, value: i64) {
-        self.i64 = Some(value);
+        *self = Number::I64(value);
     }
 
     fn set_i128(&mut self, value: i128) {
-        self.i128 = Some(value);
+        *self = Number::I128(value);
     }
 
     fn set_f32(&mut self, value: f32) {
-        self.f32 = Some(value);
+        *self = Number::F32(value);
     }
 
     fn set_f64(&mut self, value: f64) {
-        self.f64 = Some(value);
+        *self = Number::F64(value);
+    }
+
+    #[cfg(feature = "decimal")]
+    fn set_decimal(&mut self, value: BigDecimal) {
+        *self = Number::from(value);
     }
 
     fn get_u8(&self) -> Option<u8> {
-        self.u8
+        match self {
+            Number::U8(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_u16(&self) -> Option<u16> {
-        self.u16
+        match self {
+            Number::U16(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_u32(&self) -> Option<u32> {
-        self.u32
+        match self {
+            Number::U32(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_u64(&self) -> Option<u64> {
-        self.u64
+        match self {
+            Number::U64(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_u128(&self) -> Option<u128> {
-        self.u128
+        match self {
+            Number::U128(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_i8(&self) -> Option<i8> {
-        self.i8
+        match self {
+            Number::I8(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_i16(&self) -> Option<i16> {
-        self.i16
+        match self {
+            Number::I16(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_i32(&self) -> Option<i32> {
-        self.i32
+        match self {
+            Number::I32(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_i64(&self) -> Option<i64> {
-        self.i64
+        match self {
+            Number::I64(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_i128(&self) -> Option<i128> {
-        self.i128
+        match self {
+            Number::I128(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_f32(&self) -> Option<f32> {
-        self.f32
+        match self {
+            Number::F32(value) => Some(*value),
+            _ => None,
+        }
     }
 
     fn get_f64(&self) -> Option<f64> {
-        self.f64
+        match self {
+            Number::F64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "decimal")]
+    fn get_decimal(&self) -> Option<&BigDecimal> {
+        match self {
+            Number::Decimal(value) => Some(value),
+            _ => None,
+        }
     }
 
     fn get_u8_unsafe(&self) -> u8 {
-        self.u8.unwrap()
+        self.get_u8().unwrap()
     }
 
     fn get_u16_unsafe(&self) -> u16 {
-        self.u16.unwrap()
+        self.get_u16().unwrap()
     }
 
     fn get_u32_unsafe(&self) -> u32 {
-        self.u32.unwrap()
+        self.get_u32().unwrap()
     }
 
     fn get_u64_unsafe(&self) -> u64 {
-        self.u64.unwrap()
+        self.get_u64().unwrap()
     }
 
     fn get_u128_unsafe(&self) -> u128 {
-        self.u128.unwrap()
+        self.get_u128().unwrap()
     }
 
     fn get_i8_unsafe(&self) -> i8 {
-        self.i8.unwrap()
+        self.get_i8().unwrap()
     }
 
     fn get_i16_unsafe(&self) -> i16 {
-        self.i16.unwrap()
+        self.get_i16().unwrap()
     }
 
     fn get_i32_unsafe(&self) -> i32 {
-        self.i32.unwrap()
+        self.get_i32().unwrap()
     }
 
     fn get_i64_unsafe(&self) -> i64 {
-        self.i64.unwrap()
+        self.get_i64().unwrap()
     }
 
     fn get_i128_unsafe(&self) -> i128 {
-        self.i128.unwrap()
+        self.get_i128().unwrap()
     }
 
     fn get_f32_unsafe(&self) -> f32 {
-        self.f32.unwrap()
+        self.get_f32().unwrap()
     }
 
     fn get_f64_unsafe(&self) -> f64 {
-        self.f64.unwrap()
+        self.get_f64().unwrap()
+    }
+
+    #[cfg(feature = "decimal")]
+    fn get_decimal_unsafe(&self) -> &BigDecimal {
+        self.get_decimal().unwrap()
     }
 
     fn is_i8(&self) -> bool {
-        self.i8.is_some()
+        matches!(self, Number::I8(_))
     }
 
     fn is_i16(&self) -> bool {
-        self.i16.is_some()
+        matches!(self, Number::I16(_))
     }
 
     fn is_i32(&self) -> bool {
-        self.i32.is_some()
+        matches!(self, Number::I32(_))
     }
 
     fn is_i64(&self) -> bool {
-        self.i64.is_some()
+        matches!(self, Number::I64(_))
     }
 
     fn is_i128(&self) -> bool {
-        self.i128.is_some()
+        matches!(self, Number::I128(_))
     }
 
     fn is_u8(&self) -> bool {
-        self.u8.is_some()
+        matches!(self, Number::U8(_))
     }
 
     fn is_u16(&self) -> bool {
-        self.u16.is_some()
+        matches!(self, Number::U16(_))
     }
 
     fn is_u32(&self) -> bool {
-        self.u32.is_some()
+        matches!(self, Number::U32(_))
     }
 
     fn is_u64(&self) -> bool {
-        self.u64.is_some()
+        matches!(self, Number::U64(_))
     }
 
     fn is_u128(&self) -> bool {
-        self.u128.is_some()
+        matches!(self, Number::U128(_))
     }
 
     fn is_f32(&self) -> bool {
-        self.f32.is_some()
+        matches!(self, Number::F32(_))
     }
 
     fn is_f64(&self) -> bool {
-        self.f64.is_some()
+        matches!(self, Number::F64(_))
+    }
+
+    #[cfg(feature = "decimal")]
+    fn is_decimal(&self) -> bool {
+        matches!(self, Number::Decimal(_))
     }
 
     fn is_number(&self) -> bool {
-        self.is_i8()
-            || self.is_i16()
-            || self.is_i32()
-            || self.is_i64()
-            || self.is_i128()
-            || self.is_u8()
-            || self.is_u16()
-            || self.is_u32()
-            || self.is_u64()
-            || self.is_u128()
-            || self.is_f32()
-            || self.is_f64()
+        !matches!(self, Number::Empty)
     }
 
     /// Checks if the stored number is an integer.
@@ -496,49 +874,48 @@ impl NumberBehavior for Number {
     /// assert_eq!(num.is_integer(), true);
     /// ```
     fn is_integer(&self) -> bool {
-        self.is_i8()
-            || self.is_i16()
-            || self.is_i32()
-            || self.is_i64()
-            || self.is_i128()
-            || self.is_u8()
-            || self.is_u16()
-            || self.is_u32()
-            || self.is_u64()
-            || self.is_u128()
+        matches!(
+            self.widen(),
+            Some(Widened::Signed(_)) | Some(Widened::Unsigned(_))
+        )
     }
 
     fn is_float(&self) -> bool {
-        self.is_f32() || self.is_f64()
+        matches!(self, Number::F32(_) | Number::F64(_))
     }
 
     fn is_signed(&self) -> bool {
-        self.is_i8() && self.i8.unwrap() < 0
-            || self.is_i16() && self.i16.unwrap() < 0
-            || self.is_i32() && self.i32.unwrap() < 0
-            || self.is_i64() && self.i64.unwrap() < 0
-            || self.is_i128() && self.i128.unwrap() < 0
-            || self.is_f32() && self.f32.unwrap() < 0.0
-            || self.is_f64() && self.f64.unwrap() < 0.0
+        #[cfg(feature = "decimal")]
+        if let Number::Decimal(value) = self {
+            return value.is_negative();
+        }
+
+        match self.widen() {
+            Some(Widened::Signed(value)) => value < 0,
+            Some(Widened::Float(value)) => value < 0.0,
+            _ => false,
+        }
     }
 
     fn is_unsigned(&self) -> bool {
-        self.is_u8() || self.is_u16() || self.is_u32() || self.is_u64() || self.is_u128()
+        matches!(
+            self,
+            Number::U8(_) | Number::U16(_) | Number::U32(_) | Number::U64(_) | Number::U128(_)
+        )
     }
 
     fn is_zero(&self) -> bool {
-        self.is_i8() && self.i8.unwrap() == 0
-            || self.is_i16() && self.i16.unwrap() == 0
-            || self.is_i32() && self.i32.unwrap() == 0
-            || self.is_i64() && self.i64.unwrap() == 0
-            || self.is_i128() && self.i128.unwrap() == 0
-            || self.is_f32() && self.f32.unwrap() == 0.0
-            || self.is_f64() && self.f64.unwrap() == 0.0
-            || self.is_u8() && self.u8.unwrap() == 0
-            || self.is_u16() && self.u16.unwrap() == 0
-            || self.is_u32() && self.u32.unwrap() == 0
-            || self.is_u64() && self.u64.unwrap() == 0
-            || self.is_u128() && self.u128.unwrap() == 0
+        #[cfg(feature = "decimal")]
+        if let Number::Decimal(value) = self {
+            return value.is_zero();
+        }
+
+        match self.widen() {
+            Some(Widened::Signed(value)) => value == 0,
+            Some(Widened::Unsigned(value)) => value == 0,
+            Some(Widened::Float(value)) => value == 0.0,
+            None => false,
+        }
     }
 
     fn is_positive(&self) -> bool {
@@ -550,42 +927,32 @@ impl NumberBehavior for Number {
     }
 
     fn number_type(&self) -> NumberType {
-        if self.is_i8() {
-            NumberType::I8
-        } else if self.is_i16() {
-            NumberType::I16
-        } else if self.is_i32() {
-            NumberType::I32
-        } else if self.is_i64() {
-            NumberType::I64
-        } else if self.is_i128() {
-            NumberType::I128
-        } else if self.is_u8() {
-            NumberType::U8
-        } else if self.is_u16() {
-            NumberType::U16
-        } else if self.is_u32() {
-            NumberType::U32
-        } else if self.is_u64() {
-            NumberType::U64
-        } else if self.is_u128() {
-            NumberType::U128
-        } else if self.is_f32() {
-            NumberType::F32
-        } else if self.is_f64() {
-            NumberType::F64
-        } else {
-            NumberType::Unknown
+        match self {
+            Number::U8(_) => NumberType::U8,
+            Number::U16(_) => NumberType::U16,
+            Number::U32(_) => NumberType::U32,
+            Number::U64(_) => NumberType::U64,
+            Number::U128(_) => NumberType::U128,
+            Number::I8(_) => NumberType::I8,
+            Number::I16(_) => NumberType::I16,
+            Number::I32(_) => NumberType::I32,
+            Number::I64(_) => NumberType::I64,
+            Number::I128(_) => NumberType::I128,
+            Number::F32(_) => NumberType::F32,
+            Number::F64(_) => NumberType::F64,
+            #[cfg(feature = "decimal")]
+            Number::Decimal(_) => NumberType::Decimal,
+            Number::Empty => NumberType::Unknown,
         }
     }
 }
 
-/// Implements the `Display` trait for the `Number` struct.
+/// Implements the `Display` trait for the `Number` enum.
 ///
 /// Provides a human-readable representation of a `Number` instance
-/// by matching its fields and converting the value to a string.
+/// by converting the stored value to a string.
 impl Display for Number {
-    /// Formats the `Number` struct for display by returning a string representation of the stored value.
+    /// Formats the `Number` for display by returning a string representation of the stored value.
     ///
     /// # Arguments
     ///
@@ -600,35 +967,25 @@ impl Display for Number {
     /// ```no_run
     /// let mut num = Number::default();
     /// num.set_f64(42.0);
-    /// println!("{}", num); // Output: 42.0
+    /// println!("{}", num); // Output: 42
     /// ```
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.is_i8() {
-            write!(f, "{}", self.get_i8_unsafe())
-        } else if self.is_i16() {
-            write!(f, "{}", self.get_i16_unsafe())
-        } else if self.is_i32() {
-            write!(f, "{}", self.get_i32_unsafe())
-        } else if self.is_i64() {
-            write!(f, "{}", self.get_i64_unsafe())
-        } else if self.is_i128() {
-            write!(f, "{}", self.get_i128_unsafe())
-        } else if self.is_u8() {
-            write!(f, "{}", self.get_u8_unsafe())
-        } else if self.is_u16() {
-            write!(f, "{}", self.get_u16_unsafe())
-        } else if self.is_u32() {
-            write!(f, "{}", self.get_u32_unsafe())
-        } else if self.is_u64() {
-            write!(f, "{}", self.get_u64_unsafe())
-        } else if self.is_u128() {
-            write!(f, "{}", self.get_u128_unsafe())
-        } else if self.is_f32() {
-            write!(f, "{}", self.get_f32_unsafe())
-        } else if self.is_f64() {
-            write!(f, "{}", self.get_f64_unsafe())
-        } else {
-            write!(f, "0")
+        match self {
+            Number::U8(value) => write!(f, "{}", value),
+            Number::U16(value) => write!(f, "{}", value),
+            Number::U32(value) => write!(f, "{}", value),
+            Number::U64(value) => write!(f, "{}", value),
+            Number::U128(value) => write!(f, "{}", value),
+            Number::I8(value) => write!(f, "{}", value),
+            Number::I16(value) => write!(f, "{}", value),
+            Number::I32(value) => write!(f, "{}", value),
+            Number::I64(value) => write!(f, "{}", value),
+            Number::I128(value) => write!(f, "{}", value),
+            Number::F32(value) => write!(f, "{}", value),
+            Number::F64(value) => write!(f, "{}", value),
+            #[cfg(feature = "decimal")]
+            Number::Decimal(value) => write!(f, "{}", value.to_plain_string()),
+            Number::Empty => write!(f, "0"),
         }
     }
 }
@@ -639,120 +996,84 @@ impl Display for Number {
 /// Converts an `i8` value to a `Number`.
 impl From<i8> for Number {
     fn from(i: i8) -> Self {
-        Number {
-            i8: Some(i),
-            ..Default::default()
-        }
+        Number::I8(i)
     }
 }
 
 /// Converts an `i16` value to a `Number`.
 impl From<i16> for Number {
     fn from(i: i16) -> Self {
-        Number {
-            i16: Some(i),
-            ..Default::default()
-        }
+        Number::I16(i)
     }
 }
 
 /// Converts an `i32` value to a `Number`.
 impl From<i32> for Number {
     fn from(i: i32) -> Self {
-        Number {
-            i32: Some(i),
-            ..Default::default()
-        }
+        Number::I32(i)
     }
 }
 
 /// Converts an `i64` value to a `Number`.
 impl From<i64> for Number {
     fn from(i: i64) -> Self {
-        Number {
-            i64: Some(i),
-            ..Default::default()
-        }
+        Number::I64(i)
     }
 }
 
 /// Converts an `i128` value to a `Number`.
 impl From<i128> for Number {
     fn from(i: i128) -> Self {
-        Number {
-            i128: Some(i),
-            ..Default::default()
-        }
+        Number::I128(i)
     }
 }
 
 /// Converts an `u8` value to a `Number`.
 impl From<u8> for Number {
     fn from(i: u8) -> Self {
-        Number {
-            u8: Some(i),
-            ..Default::default()
-        }
+        Number::U8(i)
     }
 }
 
 /// Converts an `u16` value to a `Number`.
 impl From<u16> for Number {
     fn from(i: u16) -> Self {
-        Number {
-            u16: Some(i),
-            ..Default::default()
-        }
+        Number::U16(i)
     }
 }
 
 /// Converts an `u32` value to a `Number`.
 impl From<u32> for Number {
     fn from(i: u32) -> Self {
-        Number {
-            u32: Some(i),
-            ..Default::default()
-        }
+        Number::U32(i)
     }
 }
 
-/// Converts an `u8` value to a `Number`.
+/// Converts an `u64` value to a `Number`.
 impl From<u64> for Number {
     fn from(i: u64) -> Self {
-        Number {
-            u64: Some(i),
-            ..Default::default()
-        }
+        Number::U64(i)
     }
 }
 
 /// Converts an `u128` value to a `Number`.
 impl From<u128> for Number {
     fn from(i: u128) -> Self {
-        Number {
-            u128: Some(i),
-            ..Default::default()
-        }
+        Number::U128(i)
     }
 }
 
 /// Converts an `f32` value to a `Number`.
 impl From<f32> for Number {
     fn from(i: f32) -> Self {
-        Number {
-            f32: Some(i),
-            ..Default::default()
-        }
+        Number::F32(i)
     }
 }
 
 /// Converts an `f64` value to a `Number`.
 impl From<f64> for Number {
     fn from(i: f64) -> Self {
-        Number {
-            f64: Some(i),
-            ..Default::default()
-        }
+        Number::F64(i)
     }
 }
 
@@ -1119,4 +1440,100 @@ mod tests {
         let number = Number::from(-42isize);
         assert_eq!(number.get_i8(), Some(-42));
     }
+
+    #[test]
+    fn test_size() {
+        assert!(std::mem::size_of::<Number>() <= 32);
+    }
+
+    #[test]
+    fn test_order_by_value() {
+        assert!(Number::from(5u8) > Number::from(3i32));
+        assert!(Number::from(-1i8) < Number::from(0u64));
+        assert!(Number::from(2.5f64) > Number::from(2u8));
+        assert!(Number::from(2.5f32) < Number::from(3i128));
+        assert!(Number::from(u128::MAX) > Number::from(i128::MAX));
+        assert!(Number::from(i128::MAX as f64) > Number::from(i128::MAX));
+        assert!(Number::default() < Number::from(i128::MIN));
+        assert_eq!(
+            Number::from(f64::NAN).partial_cmp(&Number::from(1u8)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_eq_across_types() {
+        assert_eq!(Number::from(1u8), Number::from(1i32));
+        assert_eq!(Number::from(1i32), Number::from(1.0f64));
+        assert_eq!(Number::from(-3i64), Number::from(-3.0f32));
+        assert_eq!(Number::from(u64::MAX), Number::from(u64::MAX as u128));
+        assert_ne!(Number::from(i128::MAX), Number::from(i128::MAX as f64));
+        assert_ne!(Number::from(0.1f32), Number::from(0.1f64));
+        assert_ne!(Number::from(f64::NAN), Number::from(f64::NAN));
+        assert_eq!(Value::from(1u8), Value::from(1.0f64));
+    }
+
+    #[test]
+    fn test_strict_comparison() {
+        assert!(Number::from(5u8).strict_eq(&Number::from(5u8)));
+        assert!(!Number::from(5u8).strict_eq(&Number::from(5i32)));
+        assert_eq!(
+            Number::from(5u8).partial_cmp(&Number::from(5i32)),
+            Some(std::cmp::Ordering::Equal)
+        );
+        assert_eq!(
+            Number::from(5u8).strict_cmp(&Number::from(5i32)),
+            Some(std::cmp::Ordering::Less)
+        );
+        assert_eq!(
+            Number::from(6u8).strict_cmp(&Number::from(5i32)),
+            Some(std::cmp::Ordering::Greater)
+        );
+    }
+
+    #[test]
+    fn test_setter_replaces_type() {
+        let mut number = Number::from(42u8);
+        number.set_i64(-1);
+        assert_eq!(number.get_u8(), None);
+        assert!(number.strict_eq(&Number::I64(-1)));
+    }
+
+    #[test]
+    fn test_total_order() {
+        let mut numbers = vec![
+            Number::from(f64::NAN),
+            Number::from(0.0f64),
+            Number::from(f64::INFINITY),
+            Number::from(-0.0f64),
+            Number::from(0u8),
+            Number::from(-f64::NAN),
+            Number::default(),
+            Number::from(f32::NEG_INFINITY),
+            Number::from(-3i32),
+        ];
+        numbers.sort_by(Number::total_cmp);
+
+        let text: Vec<String> = numbers.iter().map(|number| number.to_string()).collect();
+        assert_eq!(
+            text,
+            vec!["0", "NaN", "-inf", "-3", "0", "-0", "0", "inf", "NaN"]
+        );
+        assert!(numbers[0].strict_eq(&Number::Empty));
+        assert!(numbers[4].strict_eq(&Number::from(0u8)));
+        assert!(numbers[1].is_nan() && numbers[8].is_nan());
+        assert_eq!(
+            Number::from(1.5f64).total_cmp(&Number::from(1.5f64)),
+            std::cmp::Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_finite_constructors() {
+        assert!(Number::from(1u8).is_finite());
+        assert!(!Number::from(f32::NAN).is_finite());
+        assert_eq!(Number::finite_f64(1.5), Ok(Number::from(1.5f64)));
+        assert_eq!(Number::finite_f64(f64::INFINITY), Err(Error::NotFinite));
+        assert_eq!(Number::finite_f32(f32::NAN), Err(Error::NotFinite));
+    }
 }
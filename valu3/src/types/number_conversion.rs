@@ -0,0 +1,338 @@
+//! Conversions of a `Number` to any primitive numeric type, whatever type it is stored as.
+//!
+//! `get_i64` and the other getters only return numbers stored as exactly that type. The
+//! `to_*` methods convert across widths and between integers and floats, following a
+//! `ConversionPolicy` for values the target type cannot hold exactly.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let number = Number::from(300i32);
+//! assert_eq!(number.to_i64(ConversionPolicy::Exact), Some(300));
+//! assert_eq!(number.to_u8(ConversionPolicy::Exact), None);
+//! assert_eq!(number.to_u8(ConversionPolicy::Saturating), Some(255));
+//! assert_eq!(Number::from(2.7f64).to_i32(ConversionPolicy::Rounding), Some(3));
+//! ```
+use crate::prelude::*;
+use crate::types::number::{Widened, I128_LIMIT, U128_LIMIT};
+#[cfg(feature = "decimal")]
+use bigdecimal::{Signed, ToPrimitive};
+
+/// How a `to_*` conversion handles a value the target type cannot hold exactly.
+///
+/// NaN never converts to an integer, and converts to NaN for float targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConversionPolicy {
+    /// Fails unless the value is representable exactly: `300` to `u8`, `1.5` to `i32` and
+    /// `16777217` to `f32` all fail.
+    #[default]
+    Exact,
+    /// Clamps the value to the bounds of the target type, dropping the fraction of floats.
+    Saturating,
+    /// Drops the fraction of floats, rounding toward zero, and fails when out of range.
+    Truncating,
+    /// Rounds floats to the nearest integer, ties away from zero, and fails when out of range.
+    Rounding,
+}
+
+/// A number reduced to an integer, or the side of the `i128` and `u128` range it lies beyond.
+enum Whole {
+    Signed(i128),
+    Unsigned(u128),
+    Below,
+    Above,
+}
+
+fn whole_float(value: f64, policy: ConversionPolicy) -> Option<Whole> {
+    let value = match policy {
+        ConversionPolicy::Exact if value.fract() != 0.0 => return None,
+        ConversionPolicy::Rounding => value.round(),
+        _ => value.trunc(),
+    };
+
+    if value.is_nan() {
+        None
+    } else if value < -I128_LIMIT {
+        Some(Whole::Below)
+    } else if value < 0.0 {
+        Some(Whole::Signed(value as i128))
+    } else if value < U128_LIMIT {
+        Some(Whole::Unsigned(value as u128))
+    } else {
+        Some(Whole::Above)
+    }
+}
+
+#[cfg(feature = "decimal")]
+fn whole_decimal(value: &BigDecimal, policy: ConversionPolicy) -> Option<Whole> {
+    let value = match policy {
+        ConversionPolicy::Exact if !value.is_integer() => return None,
+        ConversionPolicy::Rounding => value.with_scale_round(0, RoundingMode::HalfUp),
+        _ => value.with_scale_round(0, RoundingMode::Down),
+    };
+
+    if let Some(value) = value.to_i128() {
+        Some(Whole::Signed(value))
+    } else if let Some(value) = value.to_u128() {
+        Some(Whole::Unsigned(value))
+    } else if value.is_negative() {
+        Some(Whole::Below)
+    } else {
+        Some(Whole::Above)
+    }
+}
+
+/// Converts a whole number to `T`, clamping it to `min..=max` when saturating.
+fn fit<T>(whole: Whole, policy: ConversionPolicy, min: T, max: T) -> Option<T>
+where
+    T: TryFrom<i128> + TryFrom<u128>,
+{
+    let fitted = match whole {
+        Whole::Signed(value) => T::try_from(value).ok(),
+        Whole::Unsigned(value) => T::try_from(value).ok(),
+        Whole::Below | Whole::Above => None,
+    };
+
+    match (fitted, policy) {
+        (Some(value), _) => Some(value),
+        (None, ConversionPolicy::Saturating) => match whole {
+            Whole::Signed(value) if value < 0 => Some(min),
+            Whole::Below => Some(min),
+            _ => Some(max),
+        },
+        (None, _) => None,
+    }
+}
+
+impl Number {
+    fn whole(&self, policy: ConversionPolicy) -> Option<Whole> {
+        #[cfg(feature = "decimal")]
+        if let Number::Decimal(value) = self {
+            return whole_decimal(value, policy);
+        }
+
+        match self.widen()? {
+            Widened::Signed(value) => Some(Whole::Signed(value)),
+            Widened::Unsigned(value) => Some(Whole::Unsigned(value)),
+            Widened::Float(value) => whole_float(value, policy),
+        }
+    }
+
+    /// Applies `policy` to `result`, the nearest float to this number in a type whose
+    /// largest finite value is `max`.
+    fn fit_float(&self, result: f64, max: f64, policy: ConversionPolicy) -> Option<f64> {
+        let infinite_source = match self {
+            Number::F32(value) => value.is_infinite(),
+            Number::F64(value) => value.is_infinite(),
+            _ => false,
+        };
+
+        if result.is_infinite() && !infinite_source {
+            return match policy {
+                ConversionPolicy::Saturating => Some(max.copysign(result)),
+                _ => None,
+            };
+        }
+
+        if policy == ConversionPolicy::Exact && !result.is_nan() && Number::from(result) != *self {
+            return None;
+        }
+
+        Some(result)
+    }
+
+    pub fn to_u8(&self, policy: ConversionPolicy) -> Option<u8> {
+        fit(self.whole(policy)?, policy, u8::MIN, u8::MAX)
+    }
+
+    pub fn to_u16(&self, policy: ConversionPolicy) -> Option<u16> {
+        fit(self.whole(policy)?, policy, u16::MIN, u16::MAX)
+    }
+
+    pub fn to_u32(&self, policy: ConversionPolicy) -> Option<u32> {
+        fit(self.whole(policy)?, policy, u32::MIN, u32::MAX)
+    }
+
+    pub fn to_u64(&self, policy: ConversionPolicy) -> Option<u64> {
+        fit(self.whole(policy)?, policy, u64::MIN, u64::MAX)
+    }
+
+    pub fn to_u128(&self, policy: ConversionPolicy) -> Option<u128> {
+        fit(self.whole(policy)?, policy, u128::MIN, u128::MAX)
+    }
+
+    pub fn to_usize(&self, policy: ConversionPolicy) -> Option<usize> {
+        fit(self.whole(policy)?, policy, usize::MIN, usize::MAX)
+    }
+
+    pub fn to_i8(&self, policy: ConversionPolicy) -> Option<i8> {
+        fit(self.whole(policy)?, policy, i8::MIN, i8::MAX)
+    }
+
+    pub fn to_i16(&self, policy: ConversionPolicy) -> Option<i16> {
+        fit(self.whole(policy)?, policy, i16::MIN, i16::MAX)
+    }
+
+    pub fn to_i32(&self, policy: ConversionPolicy) -> Option<i32> {
+        fit(self.whole(policy)?, policy, i32::MIN, i32::MAX)
+    }
+
+    pub fn to_i64(&self, policy: ConversionPolicy) -> Option<i64> {
+        fit(self.whole(policy)?, policy, i64::MIN, i64::MAX)
+    }
+
+    pub fn to_i128(&self, policy: ConversionPolicy) -> Option<i128> {
+        fit(self.whole(policy)?, policy, i128::MIN, i128::MAX)
+    }
+
+    pub fn to_isize(&self, policy: ConversionPolicy) -> Option<isize> {
+        fit(self.whole(policy)?, policy, isize::MIN, isize::MAX)
+    }
+
+    /// Converts the number to the nearest `f32`.
+    ///
+    /// Only `Exact` fails on a loss of precision, the other policies differ on values beyond
+    /// the range of `f32`, which `Saturating` clamps to `f32::MIN` and `f32::MAX`.
+    pub fn to_f32(&self, policy: ConversionPolicy) -> Option<f32> {
+        #[cfg(feature = "decimal")]
+        if let Number::Decimal(value) = self {
+            let result = value.to_f32()? as f64;
+            return self
+                .fit_float(result, f32::MAX as f64, policy)
+                .map(|value| value as f32);
+        }
+
+        let result = match self.widen()? {
+            Widened::Signed(value) => value as f32,
+            Widened::Unsigned(value) => value as f32,
+            Widened::Float(value) => value as f32,
+        };
+        self.fit_float(result as f64, f32::MAX as f64, policy)
+            .map(|value| value as f32)
+    }
+
+    /// Converts the number to the nearest `f64`, see `to_f32`.
+    pub fn to_f64(&self, policy: ConversionPolicy) -> Option<f64> {
+        #[cfg(feature = "decimal")]
+        if let Number::Decimal(value) = self {
+            return self.fit_float(value.to_f64()?, f64::MAX, policy);
+        }
+
+        let result = match self.widen()? {
+            Widened::Signed(value) => value as f64,
+            Widened::Unsigned(value) => value as f64,
+            Widened::Float(value) => value,
+        };
+        self.fit_float(result, f64::MAX, policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_exact() {
+        let number = Number::from(300i32);
+
+        assert_eq!(number.to_i64(ConversionPolicy::Exact), Some(300));
+        assert_eq!(number.to_u16(ConversionPolicy::Exact), Some(300));
+        assert_eq!(number.to_u8(ConversionPolicy::Exact), None);
+        assert_eq!(number.to_f32(ConversionPolicy::Exact), Some(300.0));
+        assert_eq!(Number::from(-1i8).to_u128(ConversionPolicy::Exact), None);
+        assert_eq!(Number::from(4.0f64).to_u8(ConversionPolicy::Exact), Some(4));
+        assert_eq!(Number::from(4.5f64).to_u8(ConversionPolicy::Exact), None);
+        assert_eq!(
+            Number::from(16_777_217u32).to_f32(ConversionPolicy::Exact),
+            None
+        );
+        assert_eq!(Number::from(0.1f64).to_f32(ConversionPolicy::Exact), None);
+        assert_eq!(
+            Number::from(0.5f64).to_f32(ConversionPolicy::Exact),
+            Some(0.5)
+        );
+        assert_eq!(
+            Number::from(u128::MAX).to_f64(ConversionPolicy::Exact),
+            None
+        );
+        assert_eq!(Number::default().to_i32(ConversionPolicy::Exact), None);
+    }
+
+    #[test]
+    fn test_saturating() {
+        assert_eq!(
+            Number::from(300i32).to_u8(ConversionPolicy::Saturating),
+            Some(255)
+        );
+        assert_eq!(
+            Number::from(-5i32).to_u8(ConversionPolicy::Saturating),
+            Some(0)
+        );
+        assert_eq!(
+            Number::from(u128::MAX).to_i64(ConversionPolicy::Saturating),
+            Some(i64::MAX)
+        );
+        assert_eq!(
+            Number::from(-2.9f64).to_i8(ConversionPolicy::Saturating),
+            Some(-2)
+        );
+        assert_eq!(
+            Number::from(1e300f64).to_i128(ConversionPolicy::Saturating),
+            Some(i128::MAX)
+        );
+        assert_eq!(
+            Number::from(f64::NEG_INFINITY).to_i16(ConversionPolicy::Saturating),
+            Some(i16::MIN)
+        );
+        assert_eq!(
+            Number::from(1e300f64).to_f32(ConversionPolicy::Saturating),
+            Some(f32::MAX)
+        );
+        assert_eq!(
+            Number::from(f64::NAN).to_u8(ConversionPolicy::Saturating),
+            None
+        );
+    }
+
+    #[test]
+    fn test_truncating_and_rounding() {
+        assert_eq!(
+            Number::from(2.7f64).to_i32(ConversionPolicy::Truncating),
+            Some(2)
+        );
+        assert_eq!(
+            Number::from(-2.7f32).to_i32(ConversionPolicy::Truncating),
+            Some(-2)
+        );
+        assert_eq!(
+            Number::from(2.5f64).to_i32(ConversionPolicy::Rounding),
+            Some(3)
+        );
+        assert_eq!(
+            Number::from(-2.5f64).to_i32(ConversionPolicy::Rounding),
+            Some(-3)
+        );
+        assert_eq!(
+            Number::from(300.2f64).to_u8(ConversionPolicy::Truncating),
+            None
+        );
+        assert_eq!(
+            Number::from(1e300f64).to_f32(ConversionPolicy::Rounding),
+            None
+        );
+        assert_eq!(
+            Number::from(0.1f64).to_f32(ConversionPolicy::Rounding),
+            Some(0.1f32)
+        );
+    }
+
+    #[test]
+    fn test_from_value_across_widths() {
+        assert_eq!(i64::from_value(Value::from(42i32)), Some(42));
+        assert_eq!(u8::from_value(Value::from(42i64)), Some(42));
+        assert_eq!(f64::from_value(Value::from(3u8)), Some(3.0));
+        assert_eq!(u8::from_value(Value::from(300i32)), None);
+        assert_eq!(i32::from_value(Value::from(1.5f64)), None);
+        assert_eq!(usize::from_value(Value::from(7u8)), Some(7));
+    }
+}
@@ -0,0 +1,562 @@
+//! Formatting of numbers as text for display, configured with a `NumberFormat`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let format = NumberFormat::new().decimals(2).group_separator(',');
+//! assert_eq!(Number::from(1234567.891f64).format_with(&format), "1,234,567.89");
+//!
+//! let format = NumberFormat::new().scientific().significant_digits(3);
+//! assert_eq!(Number::from(1e21f64).format_with(&format), "1.00e21");
+//!
+//! let format = NumberFormat::new().hexadecimal().radix_prefix(true);
+//! assert_eq!(Value::from(255u8).format_with(&format), Some("0xff".to_string()));
+//! ```
+use crate::prelude::*;
+
+/// How the digits of a number are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notation {
+    /// Plain positional notation, `1234.5`.
+    #[default]
+    Standard,
+    /// One integer digit and a power of ten, `1.2345e3`.
+    Scientific,
+    /// One to three integer digits and a power of ten multiple of three, `1.2345e3`, `12.5e-6`.
+    Engineering,
+    /// Base 16 for integers, `ff`.
+    Hexadecimal,
+    /// Base 8 for integers, `377`.
+    Octal,
+    /// Base 2 for integers, `11111111`.
+    Binary,
+}
+
+/// How many digits are written, rounding half away from zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// As many digits as needed to read the same number back.
+    #[default]
+    Shortest,
+    /// A fixed number of digits after the decimal mark.
+    Decimals(usize),
+    /// A fixed number of significant digits.
+    SignificantDigits(usize),
+}
+
+/// Options for writing a `Number` as text.
+///
+/// Built by chaining methods from `NumberFormat::new()`, then used with
+/// `NumberFormat::format`, `Number::format_with` or `Value::format_with`.
+///
+/// Floats are rounded from their shortest representation, so `2.675` with two decimals is
+/// `2.68`. NaN and infinities are written as `NaN`, `inf` and `-inf` in every notation.
+/// The radix notations only apply to integers, other numbers are written as in `Standard`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    notation: Notation,
+    precision: Precision,
+    group_separator: Option<char>,
+    decimal_mark: char,
+    radix_prefix: bool,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            notation: Notation::Standard,
+            precision: Precision::Shortest,
+            group_separator: None,
+            decimal_mark: '.',
+            radix_prefix: false,
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    pub fn scientific(self) -> Self {
+        self.notation(Notation::Scientific)
+    }
+
+    pub fn engineering(self) -> Self {
+        self.notation(Notation::Engineering)
+    }
+
+    pub fn hexadecimal(self) -> Self {
+        self.notation(Notation::Hexadecimal)
+    }
+
+    pub fn octal(self) -> Self {
+        self.notation(Notation::Octal)
+    }
+
+    pub fn binary(self) -> Self {
+        self.notation(Notation::Binary)
+    }
+
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Writes exactly `decimals` digits after the decimal mark, in the mantissa for the
+    /// scientific and engineering notations. Counts above 4096 are treated as 4096.
+    pub fn decimals(self, decimals: usize) -> Self {
+        self.precision(Precision::Decimals(decimals))
+    }
+
+    pub fn significant_digits(self, digits: usize) -> Self {
+        self.precision(Precision::SignificantDigits(digits.max(1)))
+    }
+
+    /// Separates groups of three integer digits with `separator`, in standard notation.
+    pub fn group_separator(mut self, separator: char) -> Self {
+        self.group_separator = Some(separator);
+        self
+    }
+
+    /// Sets the character between the integer and the fraction, `.` by default.
+    pub fn decimal_mark(mut self, mark: char) -> Self {
+        self.decimal_mark = mark;
+        self
+    }
+
+    /// Prefixes radix notations with `0x`, `0o` or `0b`.
+    pub fn radix_prefix(mut self, prefix: bool) -> Self {
+        self.radix_prefix = prefix;
+        self
+    }
+
+    pub fn format(&self, number: &Number) -> String {
+        let (radix, prefix) = match self.notation {
+            Notation::Hexadecimal => (16, "0x"),
+            Notation::Octal => (8, "0o"),
+            Notation::Binary => (2, "0b"),
+            _ => (10, ""),
+        };
+
+        if radix != 10 {
+            if let Some(text) = self.format_radix(number, radix, prefix) {
+                return text;
+            }
+        }
+
+        let digits = match Digits::from_number(number) {
+            Ok(digits) => digits,
+            Err(text) => return text.to_string(),
+        };
+
+        match self.notation {
+            Notation::Scientific => self.format_exponent(digits, 1),
+            Notation::Engineering => self.format_exponent(digits, 3),
+            _ => self.format_standard(digits),
+        }
+    }
+
+    fn format_radix(&self, number: &Number, radix: u32, prefix: &str) -> Option<String> {
+        let (negative, magnitude) = match number.to_u128(ConversionPolicy::Exact) {
+            Some(value) => (false, value),
+            None => (
+                true,
+                number.to_i128(ConversionPolicy::Exact)?.unsigned_abs(),
+            ),
+        };
+
+        if !number.is_integer() {
+            return None;
+        }
+
+        let digits = match radix {
+            16 => format!("{:x}", magnitude),
+            8 => format!("{:o}", magnitude),
+            _ => format!("{:b}", magnitude),
+        };
+        let sign = if negative { "-" } else { "" };
+        let prefix = if self.radix_prefix { prefix } else { "" };
+
+        Some(format!("{}{}{}", sign, prefix, digits))
+    }
+
+    fn format_standard(&self, mut digits: Digits) -> String {
+        match self.precision {
+            Precision::Shortest => {}
+            Precision::Decimals(decimals) => digits.round_at(-limit(decimals)),
+            Precision::SignificantDigits(significant) => {
+                digits.round_at(digits.magnitude() - limit(significant) + 1)
+            }
+        }
+
+        digits.positional(self.group_separator, self.decimal_mark)
+    }
+
+    /// Writes the digits with a power of ten that is a multiple of `step`.
+    fn format_exponent(&self, mut digits: Digits, step: i64) -> String {
+        let power = |digits: &Digits| digits.magnitude().div_euclid(step) * step;
+
+        // Rounding up may carry into a new digit, 9.99 becoming 10.0, which moves the power
+        // and leaves one digit too many, so the rounding is applied again.
+        for _ in 0..2 {
+            match self.precision {
+                Precision::Shortest => digits.trim(),
+                Precision::Decimals(decimals) => digits.round_at(power(&digits) - limit(decimals)),
+                Precision::SignificantDigits(significant) => {
+                    digits.round_at(digits.magnitude() - limit(significant) + 1)
+                }
+            }
+        }
+
+        let exponent = power(&digits);
+        let mantissa = Digits {
+            exponent: digits.exponent - exponent,
+            ..digits
+        };
+
+        format!(
+            "{}e{}",
+            mantissa.positional(None, self.decimal_mark),
+            exponent
+        )
+    }
+}
+
+/// The largest number of decimals or significant digits written, far more than a float has.
+const MAX_DIGITS: usize = 4096;
+
+/// Converts a number of digits to a power of ten offset, at most `MAX_DIGITS`.
+fn limit(digits: usize) -> i64 {
+    digits.min(MAX_DIGITS) as i64
+}
+
+/// A finite number as the decimal digits of an integer and a power of ten.
+#[derive(Clone)]
+struct Digits {
+    negative: bool,
+    /// Decimal digits, most significant first, without leading zeros unless the value is 0.
+    digits: Vec<u8>,
+    exponent: i64,
+}
+
+impl Digits {
+    /// Reads the digits of a number, or returns the text of NaN and infinities.
+    fn from_number(number: &Number) -> Result<Digits, &'static str> {
+        match number {
+            Number::F32(value) if value.is_nan() => Err("NaN"),
+            Number::F64(value) if value.is_nan() => Err("NaN"),
+            Number::F32(value) if value.is_infinite() => Err(infinity(*value > 0.0)),
+            Number::F64(value) if value.is_infinite() => Err(infinity(*value > 0.0)),
+            // `{:e}` writes the shortest digits reading back as the same float.
+            Number::F32(value) => Ok(Digits::parse(&format!("{:e}", value))),
+            Number::F64(value) => Ok(Digits::parse(&format!("{:e}", value))),
+            #[cfg(feature = "decimal")]
+            Number::Decimal(value) => {
+                let (integer, scale) = value.as_bigint_and_exponent();
+                Ok(Digits::parse(&format!("{}e{}", integer, -scale)))
+            }
+            Number::Empty => Ok(Digits::parse("0")),
+            _ => Ok(Digits::parse(&number.to_string())),
+        }
+    }
+
+    /// Parses `[-]digits[.digits][e[-]exponent]`.
+    fn parse(text: &str) -> Digits {
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+        let (mantissa, exponent) = match text.split_once('e') {
+            Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().unwrap_or(0)),
+            None => (text, 0),
+        };
+        let fraction = mantissa
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len());
+
+        let mut digits = Digits {
+            negative,
+            digits: mantissa
+                .bytes()
+                .filter(u8::is_ascii_digit)
+                .map(|digit| digit - b'0')
+                .collect(),
+            exponent: exponent - fraction as i64,
+        };
+        digits.normalize();
+        digits
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.iter().all(|digit| *digit == 0)
+    }
+
+    /// Drops leading zeros and the sign of zero.
+    fn normalize(&mut self) {
+        let leading = self.digits.iter().take_while(|digit| **digit == 0).count();
+        self.digits
+            .drain(..leading.min(self.digits.len().saturating_sub(1)));
+
+        if self.digits.is_empty() {
+            self.digits.push(0);
+        }
+        if self.is_zero() {
+            self.negative = false;
+        }
+    }
+
+    /// The power of ten of the most significant digit, 0 for zero.
+    fn magnitude(&self) -> i64 {
+        if self.is_zero() {
+            0
+        } else {
+            self.digits.len() as i64 - 1 + self.exponent
+        }
+    }
+
+    /// Removes trailing zeros of the fraction.
+    fn trim(&mut self) {
+        while self.digits.len() > 1 && self.digits.last() == Some(&0) {
+            self.digits.pop();
+            self.exponent += 1;
+        }
+    }
+
+    /// Rounds half away from zero so that the last digit has the power of ten `exponent`,
+    /// padding with zeros when there are fewer digits.
+    fn round_at(&mut self, exponent: i64) {
+        if self.exponent >= exponent {
+            let padding = (self.exponent - exponent) as usize;
+            self.digits.resize(self.digits.len() + padding, 0);
+            self.exponent = exponent;
+            return;
+        }
+
+        let dropped = (exponent - self.exponent) as usize;
+        let round_up = if dropped > self.digits.len() {
+            self.digits.clear();
+            false
+        } else {
+            let kept = self.digits.len() - dropped;
+            let round_up = self.digits[kept] >= 5;
+            self.digits.truncate(kept);
+            round_up
+        };
+        self.exponent = exponent;
+
+        if round_up {
+            let mut index = self.digits.len();
+            loop {
+                if index == 0 {
+                    self.digits.insert(0, 1);
+                    break;
+                }
+                index -= 1;
+                if self.digits[index] == 9 {
+                    self.digits[index] = 0;
+                } else {
+                    self.digits[index] += 1;
+                    break;
+                }
+            }
+        }
+
+        self.normalize();
+    }
+
+    /// Writes the digits in positional notation.
+    fn positional(&self, group_separator: Option<char>, decimal_mark: char) -> String {
+        let digits: String = self
+            .digits
+            .iter()
+            .map(|digit| (b'0' + digit) as char)
+            .collect();
+        let point = self.digits.len() as i64 + self.exponent;
+
+        let (integer, fraction) = if self.exponent >= 0 {
+            (digits + &"0".repeat(self.exponent as usize), String::new())
+        } else if point <= 0 {
+            ("0".to_string(), "0".repeat(-point as usize) + &digits)
+        } else {
+            let (integer, fraction) = digits.split_at(point as usize);
+            (integer.to_string(), fraction.to_string())
+        };
+
+        let mut text = String::new();
+        if self.negative {
+            text.push('-');
+        }
+
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0 && (integer.len() - index) % 3 == 0 {
+                if let Some(separator) = group_separator {
+                    text.push(separator);
+                }
+            }
+            text.push(digit);
+        }
+
+        if !fraction.is_empty() {
+            text.push(decimal_mark);
+            text.push_str(&fraction);
+        }
+
+        text
+    }
+}
+
+fn infinity(positive: bool) -> &'static str {
+    if positive {
+        "inf"
+    } else {
+        "-inf"
+    }
+}
+
+impl Number {
+    /// Writes the number as text following `format`.
+    pub fn format_with(&self, format: &NumberFormat) -> String {
+        format.format(self)
+    }
+}
+
+impl Value {
+    /// Writes a number as text following `format`, or returns `None` for other values.
+    pub fn format_with(&self, format: &NumberFormat) -> Option<String> {
+        self.as_number().map(|number| format.format(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn format(number: impl Into<Number>, format: NumberFormat) -> String {
+        number.into().format_with(&format)
+    }
+
+    #[test]
+    fn test_fixed_decimals() {
+        assert_eq!(format(1.23456f64, NumberFormat::new().decimals(2)), "1.23");
+        assert_eq!(format(2.675f64, NumberFormat::new().decimals(2)), "2.68");
+        assert_eq!(format(5u8, NumberFormat::new().decimals(2)), "5.00");
+        assert_eq!(format(9.999f64, NumberFormat::new().decimals(2)), "10.00");
+        assert_eq!(format(-0.001f64, NumberFormat::new().decimals(2)), "0.00");
+        assert_eq!(format(1234.5f32, NumberFormat::new().decimals(0)), "1235");
+
+        let long = format(1.5f64, NumberFormat::new().decimals(usize::MAX));
+        assert_eq!(long.len(), 2 + 4096);
+        assert!(long.starts_with("1.50"));
+        let long = format(1.5f64, NumberFormat::new().significant_digits(usize::MAX));
+        assert_eq!(long.len(), 1 + 4096);
+    }
+
+    #[test]
+    fn test_significant_digits() {
+        assert_eq!(
+            format(123456u32, NumberFormat::new().significant_digits(3)),
+            "123000"
+        );
+        assert_eq!(
+            format(0.00123456f64, NumberFormat::new().significant_digits(2)),
+            "0.0012"
+        );
+        assert_eq!(
+            format(1.5f64, NumberFormat::new().significant_digits(3)),
+            "1.50"
+        );
+    }
+
+    #[test]
+    fn test_scientific_and_engineering() {
+        assert_eq!(format(1e21f64, NumberFormat::new().scientific()), "1e21");
+        assert_eq!(
+            format(1234.5f64, NumberFormat::new().scientific()),
+            "1.2345e3"
+        );
+        assert_eq!(
+            format(-0.00012f64, NumberFormat::new().scientific()),
+            "-1.2e-4"
+        );
+        assert_eq!(
+            format(9.99f64, NumberFormat::new().scientific().decimals(1)),
+            "1.0e1"
+        );
+        assert_eq!(
+            format(0u8, NumberFormat::new().scientific().decimals(2)),
+            "0.00e0"
+        );
+        assert_eq!(
+            format(12345u32, NumberFormat::new().engineering()),
+            "12.345e3"
+        );
+        assert_eq!(
+            format(0.0000125f64, NumberFormat::new().engineering()),
+            "12.5e-6"
+        );
+        assert_eq!(
+            format(
+                999.9f64,
+                NumberFormat::new().engineering().significant_digits(3)
+            ),
+            "1.00e3"
+        );
+    }
+
+    #[test]
+    fn test_grouping_and_decimal_mark() {
+        let format_eu = NumberFormat::new()
+            .decimals(2)
+            .group_separator('.')
+            .decimal_mark(',');
+
+        assert_eq!(format(1234567.891f64, format_eu), "1.234.567,89");
+        assert_eq!(
+            format(-123456i32, NumberFormat::new().group_separator(',')),
+            "-123,456"
+        );
+        assert_eq!(
+            format(999u16, NumberFormat::new().group_separator(',')),
+            "999"
+        );
+        assert_eq!(
+            format(1e21f64, NumberFormat::new().group_separator('_')),
+            "1_000_000_000_000_000_000_000"
+        );
+    }
+
+    #[test]
+    fn test_radix() {
+        assert_eq!(format(255u8, NumberFormat::new().hexadecimal()), "ff");
+        assert_eq!(
+            format(
+                -255i32,
+                NumberFormat::new().hexadecimal().radix_prefix(true)
+            ),
+            "-0xff"
+        );
+        assert_eq!(
+            format(8u8, NumberFormat::new().octal().radix_prefix(true)),
+            "0o10"
+        );
+        assert_eq!(format(5u8, NumberFormat::new().binary()), "101");
+        assert_eq!(format(1.5f64, NumberFormat::new().binary()), "1.5");
+    }
+
+    #[test]
+    fn test_special_values() {
+        assert_eq!(format(f64::NAN, NumberFormat::new().decimals(2)), "NaN");
+        assert_eq!(
+            format(f64::NEG_INFINITY, NumberFormat::new().scientific()),
+            "-inf"
+        );
+        assert_eq!(format(0.1f32, NumberFormat::new()), "0.1");
+        assert_eq!(Value::from("text").format_with(&NumberFormat::new()), None);
+    }
+}
@@ -0,0 +1,976 @@
+//! Arithmetic and bitwise operators for `Number` and for `Value` holding numbers.
+//!
+//! Operands of different types are promoted to a common type before the operation:
+//!
+//! * integers of the same signedness widen to the larger width, `u8 + u32` is a `u32`;
+//! * mixed unsigned and signed integers go to the smallest signed type holding both,
+//!   `u8 + i8` is an `i16` and `u64 + i32` is an `i128`. No signed type holds every `u128`,
+//!   so a `u128` with a signed integer is an `i128`, or a `u128` for a result beyond
+//!   `i128::MAX`;
+//! * `f32` with `f32` stays `f32`, any other mix involving a float is an `f64`;
+//! * with the `decimal` feature, any mix involving a decimal is a decimal.
+//!
+//! Like the primitive operators in debug builds, the integer operators panic on overflow
+//! and division by zero, while float operators follow IEEE 754. The `checked_*`,
+//! `wrapping_*` and `saturating_*` methods report overflow instead of panicking.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let sum = Number::from(200u8) + Number::from(-1i8);
+//! assert_eq!(sum, Number::from(199i16));
+//!
+//! assert_eq!(Number::from(255u8).checked_add(&Number::from(1u8)), None);
+//! assert_eq!(Number::from(255u8).wrapping_add(&Number::from(1u8)), Number::from(0u8));
+//! assert_eq!(Number::from(255u8).saturating_add(&Number::from(1u8)), Number::from(255u8));
+//! ```
+use crate::prelude::*;
+#[cfg(feature = "decimal")]
+use bigdecimal::Zero;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Rem, Shl, Shr, Sub};
+
+/// How a result outside the range of the promoted type is handled.
+#[derive(Clone, Copy)]
+enum Overflow {
+    /// The plain operators: integer overflow fails, floats follow IEEE 754.
+    Strict,
+    /// Any overflow fails, including a float result that is not finite.
+    Checked,
+    /// Integers wrap around the bounds of the type, floats follow IEEE 754.
+    Wrapping,
+    /// Results are clamped to the bounds of the type.
+    Saturating,
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Operation {
+    fn name(&self) -> &'static str {
+        match self {
+            Operation::Add => "add",
+            Operation::Sub => "subtract",
+            Operation::Mul => "multiply",
+            Operation::Div => "divide",
+            Operation::Rem => "calculate the remainder",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Bitwise {
+    And,
+    Or,
+    Xor,
+}
+
+/// An integer of any supported type as a sign and a magnitude, so that every `u128` and
+/// `i128` value is represented exactly.
+///
+/// A result beyond `u128::MAX` keeps the low 128 bits of its magnitude and sets `overflow`.
+#[derive(Clone, Copy)]
+struct Integer {
+    negative: bool,
+    magnitude: u128,
+    overflow: bool,
+}
+
+impl Integer {
+    fn new(negative: bool, magnitude: u128) -> Self {
+        Integer {
+            negative: negative && magnitude != 0,
+            magnitude,
+            overflow: false,
+        }
+    }
+
+    fn from_number(number: &Number) -> Option<Self> {
+        match *number {
+            Number::U8(value) => Some(Integer::new(false, value as u128)),
+            Number::U16(value) => Some(Integer::new(false, value as u128)),
+            Number::U32(value) => Some(Integer::new(false, value as u128)),
+            Number::U64(value) => Some(Integer::new(false, value as u128)),
+            Number::U128(value) => Some(Integer::new(false, value)),
+            Number::I8(value) => Some(Integer::new(value < 0, value.unsigned_abs() as u128)),
+            Number::I16(value) => Some(Integer::new(value < 0, value.unsigned_abs() as u128)),
+            Number::I32(value) => Some(Integer::new(value < 0, value.unsigned_abs() as u128)),
+            Number::I64(value) => Some(Integer::new(value < 0, value.unsigned_abs() as u128)),
+            Number::I128(value) => Some(Integer::new(value < 0, value.unsigned_abs())),
+            _ => None,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        if self.negative == other.negative {
+            let (magnitude, overflow) = self.magnitude.overflowing_add(other.magnitude);
+            Integer {
+                negative: self.negative,
+                magnitude,
+                overflow,
+            }
+        } else if self.magnitude >= other.magnitude {
+            Integer::new(self.negative, self.magnitude - other.magnitude)
+        } else {
+            Integer::new(other.negative, other.magnitude - self.magnitude)
+        }
+    }
+
+    fn neg(self) -> Self {
+        Integer {
+            negative: !self.negative && self.magnitude != 0,
+            ..self
+        }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.add(other.neg())
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let (magnitude, overflow) = self.magnitude.overflowing_mul(other.magnitude);
+        Integer {
+            negative: self.negative != other.negative && (magnitude != 0 || overflow),
+            magnitude,
+            overflow,
+        }
+    }
+
+    fn div(self, other: Self) -> Option<Self> {
+        let magnitude = self.magnitude.checked_div(other.magnitude)?;
+        Some(Integer::new(self.negative != other.negative, magnitude))
+    }
+
+    fn rem(self, other: Self) -> Option<Self> {
+        let magnitude = self.magnitude.checked_rem(other.magnitude)?;
+        Some(Integer::new(self.negative, magnitude))
+    }
+
+    /// The two's complement bits of the value, modulo `2^128`.
+    fn bits(self) -> u128 {
+        if self.negative {
+            self.magnitude.wrapping_neg()
+        } else {
+            self.magnitude
+        }
+    }
+
+    /// Converts the value to `target`, if it fits.
+    fn exact(self, target: NumberType) -> Option<Number> {
+        if self.overflow {
+            return None;
+        }
+
+        let unsigned = if self.negative {
+            None
+        } else {
+            Some(self.magnitude)
+        };
+        let signed = if !self.negative {
+            i128::try_from(self.magnitude).ok()
+        } else if self.magnitude <= i128::MIN.unsigned_abs() {
+            Some(self.magnitude.wrapping_neg() as i128)
+        } else {
+            None
+        };
+
+        match target {
+            NumberType::U8 => u8::try_from(unsigned?).ok().map(Number::from),
+            NumberType::U16 => u16::try_from(unsigned?).ok().map(Number::from),
+            NumberType::U32 => u32::try_from(unsigned?).ok().map(Number::from),
+            NumberType::U64 => u64::try_from(unsigned?).ok().map(Number::from),
+            NumberType::U128 => unsigned.map(Number::from),
+            NumberType::I8 => i8::try_from(signed?).ok().map(Number::from),
+            NumberType::I16 => i16::try_from(signed?).ok().map(Number::from),
+            NumberType::I32 => i32::try_from(signed?).ok().map(Number::from),
+            NumberType::I64 => i64::try_from(signed?).ok().map(Number::from),
+            NumberType::I128 => signed.map(Number::from),
+            _ => None,
+        }
+    }
+
+    /// Converts the value to `target`, handling values out of its range as `overflow` says.
+    fn narrow(self, target: NumberType, overflow: Overflow) -> Option<Number> {
+        if let Some(number) = self.exact(target) {
+            return Some(number);
+        }
+
+        match overflow {
+            Overflow::Strict | Overflow::Checked => None,
+            Overflow::Wrapping => Some(from_bits(self.bits(), target)),
+            Overflow::Saturating if self.negative => Some(min_of(target)),
+            Overflow::Saturating => Some(max_of(target)),
+        }
+    }
+}
+
+/// Keeps the low bits of `bits` that fit in `target`.
+fn from_bits(bits: u128, target: NumberType) -> Number {
+    match target {
+        NumberType::U8 => Number::from(bits as u8),
+        NumberType::U16 => Number::from(bits as u16),
+        NumberType::U32 => Number::from(bits as u32),
+        NumberType::U64 => Number::from(bits as u64),
+        NumberType::U128 => Number::from(bits),
+        NumberType::I8 => Number::from(bits as i8),
+        NumberType::I16 => Number::from(bits as i16),
+        NumberType::I32 => Number::from(bits as i32),
+        NumberType::I64 => Number::from(bits as i64),
+        NumberType::I128 => Number::from(bits as i128),
+        _ => Number::default(),
+    }
+}
+
+fn min_of(target: NumberType) -> Number {
+    match target {
+        NumberType::I8 => Number::from(i8::MIN),
+        NumberType::I16 => Number::from(i16::MIN),
+        NumberType::I32 => Number::from(i32::MIN),
+        NumberType::I64 => Number::from(i64::MIN),
+        NumberType::I128 => Number::from(i128::MIN),
+        NumberType::F32 => Number::from(f32::MIN),
+        NumberType::F64 => Number::from(f64::MIN),
+        _ => from_bits(0, target),
+    }
+}
+
+fn max_of(target: NumberType) -> Number {
+    match target {
+        NumberType::U8 => Number::from(u8::MAX),
+        NumberType::U16 => Number::from(u16::MAX),
+        NumberType::U32 => Number::from(u32::MAX),
+        NumberType::U64 => Number::from(u64::MAX),
+        NumberType::U128 => Number::from(u128::MAX),
+        NumberType::I8 => Number::from(i8::MAX),
+        NumberType::I16 => Number::from(i16::MAX),
+        NumberType::I32 => Number::from(i32::MAX),
+        NumberType::I64 => Number::from(i64::MAX),
+        NumberType::I128 => Number::from(i128::MAX),
+        NumberType::F32 => Number::from(f32::MAX),
+        NumberType::F64 => Number::from(f64::MAX),
+        _ => Number::default(),
+    }
+}
+
+/// The width in bits of an integer type.
+fn width_of(number_type: NumberType) -> u32 {
+    match number_type {
+        NumberType::U8 | NumberType::I8 => 8,
+        NumberType::U16 | NumberType::I16 => 16,
+        NumberType::U32 | NumberType::I32 | NumberType::F32 => 32,
+        NumberType::U64 | NumberType::I64 | NumberType::F64 => 64,
+        NumberType::U128 | NumberType::I128 => 128,
+        _ => 0,
+    }
+}
+
+fn unsigned_of(width: u32) -> NumberType {
+    match width {
+        8 => NumberType::U8,
+        16 => NumberType::U16,
+        32 => NumberType::U32,
+        64 => NumberType::U64,
+        _ => NumberType::U128,
+    }
+}
+
+fn signed_of(width: u32) -> NumberType {
+    match width {
+        8 => NumberType::I8,
+        16 => NumberType::I16,
+        32 => NumberType::I32,
+        64 => NumberType::I64,
+        _ => NumberType::I128,
+    }
+}
+
+/// The type both operands are converted to before an operation.
+fn promote(left: &Number, right: &Number) -> Option<NumberType> {
+    let (left_type, right_type) = (left.number_type(), right.number_type());
+
+    if !left.is_number() || !right.is_number() {
+        return None;
+    }
+
+    #[cfg(feature = "decimal")]
+    if left.is_decimal() || right.is_decimal() {
+        return Some(NumberType::Decimal);
+    }
+
+    if left_type == NumberType::F32 && right_type == NumberType::F32 {
+        Some(NumberType::F32)
+    } else if left.is_float() || right.is_float() {
+        Some(NumberType::F64)
+    } else if left.is_unsigned() == right.is_unsigned() {
+        let width = width_of(left_type).max(width_of(right_type));
+        if left.is_unsigned() {
+            Some(unsigned_of(width))
+        } else {
+            Some(signed_of(width))
+        }
+    } else {
+        let (unsigned, signed) = if left.is_unsigned() {
+            (left_type, right_type)
+        } else {
+            (right_type, left_type)
+        };
+        Some(signed_of((width_of(unsigned) * 2).max(width_of(signed))))
+    }
+}
+
+fn to_f64(number: &Number) -> f64 {
+    match *number {
+        Number::U8(value) => value as f64,
+        Number::U16(value) => value as f64,
+        Number::U32(value) => value as f64,
+        Number::U64(value) => value as f64,
+        Number::U128(value) => value as f64,
+        Number::I8(value) => value as f64,
+        Number::I16(value) => value as f64,
+        Number::I32(value) => value as f64,
+        Number::I64(value) => value as f64,
+        Number::I128(value) => value as f64,
+        Number::F32(value) => value as f64,
+        Number::F64(value) => value,
+        _ => f64::NAN,
+    }
+}
+
+/// Stores a float result as `target`, handling results that are not finite as `overflow` says.
+fn float_result(result: f64, target: NumberType, overflow: Overflow) -> Option<Number> {
+    // An `f32` operation computed in `f64` and rounded once gives the same result as in `f32`.
+    let (result, number) = if target == NumberType::F32 {
+        let rounded = result as f32;
+        (rounded as f64, Number::from(rounded))
+    } else {
+        (result, Number::from(result))
+    };
+
+    match overflow {
+        _ if result.is_finite() => Some(number),
+        Overflow::Checked => None,
+        Overflow::Saturating if result == f64::INFINITY => Some(max_of(target)),
+        Overflow::Saturating if result == f64::NEG_INFINITY => Some(min_of(target)),
+        _ => Some(number),
+    }
+}
+
+fn arithmetic(
+    left: &Number,
+    right: &Number,
+    operation: Operation,
+    overflow: Overflow,
+) -> Option<Number> {
+    let target = promote(left, right)?;
+
+    #[cfg(feature = "decimal")]
+    if target == NumberType::Decimal {
+        let (left, right) = (left.to_decimal()?, right.to_decimal()?);
+        let result = match operation {
+            Operation::Add => left + right,
+            Operation::Sub => left - right,
+            Operation::Mul => left * right,
+            // Quotients that do not end within the default scale are rounded to it.
+            Operation::Div => DecimalContext::default()
+                .div_decimal(&left, &right)?
+                .normalized(),
+            Operation::Rem if right.is_zero() => return None,
+            Operation::Rem => left % right,
+        };
+        return crate::types::decimal::bounded(result).map(Number::from);
+    }
+
+    if target == NumberType::F32 || target == NumberType::F64 {
+        let (left, right) = (to_f64(left), to_f64(right));
+        let result = match operation {
+            Operation::Add => left + right,
+            Operation::Sub => left - right,
+            Operation::Mul => left * right,
+            Operation::Div => left / right,
+            Operation::Rem => left % right,
+        };
+        return float_result(result, target, overflow);
+    }
+
+    let with_u128 =
+        left.number_type() == NumberType::U128 || right.number_type() == NumberType::U128;
+    let (left, right) = (Integer::from_number(left)?, Integer::from_number(right)?);
+    let result = match operation {
+        Operation::Add => left.add(right),
+        Operation::Sub => left.sub(right),
+        Operation::Mul => left.mul(right),
+        Operation::Div => left.div(right)?,
+        Operation::Rem => left.rem(right)?,
+    };
+
+    // A `u128` mixed with a signed type goes to `i128`, which misses the upper half of the
+    // `u128` range, so a positive result beyond `i128::MAX` stays unsigned.
+    if target == NumberType::I128
+        && with_u128
+        && !result.negative
+        && result.exact(NumberType::I128).is_none()
+    {
+        return result.narrow(NumberType::U128, overflow);
+    }
+    result.narrow(target, overflow)
+}
+
+/// Runs an operation that cannot report failure, panicking with the reason instead.
+fn arithmetic_or_panic(
+    left: &Number,
+    right: &Number,
+    operation: Operation,
+    overflow: Overflow,
+) -> Number {
+    if !left.is_number() || !right.is_number() {
+        panic!("Unable to {} an empty number", operation.name());
+    }
+
+    match arithmetic(left, right, operation, overflow) {
+        Some(number) => number,
+        None if right.is_zero() => panic!("attempt to {} by zero", operation.name()),
+        None => panic!("attempt to {} with overflow", operation.name()),
+    }
+}
+
+/// The plain arithmetic operators.
+fn operator(left: &Number, right: &Number, operation: Operation) -> Number {
+    arithmetic_or_panic(left, right, operation, Overflow::Strict)
+}
+
+fn negate(number: &Number, overflow: Overflow) -> Option<Number> {
+    let target = match number.number_type() {
+        NumberType::F32 => return float_result(-to_f64(number), NumberType::F32, overflow),
+        NumberType::F64 => return float_result(-to_f64(number), NumberType::F64, overflow),
+        #[cfg(feature = "decimal")]
+        NumberType::Decimal => return Some(Number::from(-number.to_decimal()?)),
+        NumberType::Unknown => return None,
+        // Unsigned numbers become signed, with room for the negated value.
+        number_type if number.is_unsigned() => signed_of((width_of(number_type) * 2).min(128)),
+        number_type => number_type,
+    };
+
+    Integer::from_number(number)?.neg().narrow(target, overflow)
+}
+
+fn negate_or_panic(number: &Number, overflow: Overflow) -> Number {
+    if !number.is_number() {
+        panic!("Unable to negate an empty number");
+    }
+
+    negate(number, overflow).unwrap_or_else(|| panic!("attempt to negate with overflow"))
+}
+
+/// Shifts an integer by `amount` bits, keeping the type of `number`.
+///
+/// An amount beyond the width of the type is masked when wrapping and fails otherwise.
+fn shift(number: &Number, amount: &Number, left: bool, overflow: Overflow) -> Option<Number> {
+    let value = Integer::from_number(number)?;
+    let amount = Integer::from_number(amount)?;
+    let width = width_of(number.number_type());
+
+    if amount.negative {
+        return None;
+    }
+
+    let amount = match overflow {
+        Overflow::Wrapping => (amount.magnitude % width as u128) as u32,
+        _ if amount.magnitude < width as u128 => amount.magnitude as u32,
+        _ => return None,
+    };
+
+    let bits = if left {
+        value.bits() << amount
+    } else if value.negative {
+        // Arithmetic shift, filling with ones like the signed primitives do.
+        ((value.bits() as i128) >> amount) as u128
+    } else {
+        value.bits() >> amount
+    };
+
+    Some(from_bits(bits, number.number_type()))
+}
+
+fn shift_or_panic(number: &Number, amount: &Number, left: bool) -> Number {
+    let direction = if left { "left" } else { "right" };
+
+    // Integral decimals are integers, but have no bits to shift.
+    if Integer::from_number(number).is_none() || Integer::from_number(amount).is_none() {
+        panic!(
+            "Unable to shift {} a number that is not an integer",
+            direction
+        );
+    }
+
+    shift(number, amount, left, Overflow::Strict)
+        .unwrap_or_else(|| panic!("attempt to shift {} with overflow", direction))
+}
+
+fn bitwise(left: &Number, right: &Number, operation: Bitwise) -> Number {
+    // Integral decimals are integers, but have no bits to combine.
+    let (target, left, right) = match (
+        promote(left, right),
+        Integer::from_number(left),
+        Integer::from_number(right),
+    ) {
+        (Some(target), Some(left), Some(right)) => (target, left.bits(), right.bits()),
+        _ => panic!("Unable to apply a bitwise operation to a number that is not an integer"),
+    };
+    let bits = match operation {
+        Bitwise::And => left & right,
+        Bitwise::Or => left | right,
+        Bitwise::Xor => left ^ right,
+    };
+
+    from_bits(bits, target)
+}
+
+impl Number {
+    /// Adds two numbers, returning `None` on overflow or if either number is empty.
+    pub fn checked_add(&self, other: &Number) -> Option<Number> {
+        arithmetic(self, other, Operation::Add, Overflow::Checked)
+    }
+
+    /// Subtracts two numbers, returning `None` on overflow or if either number is empty.
+    pub fn checked_sub(&self, other: &Number) -> Option<Number> {
+        arithmetic(self, other, Operation::Sub, Overflow::Checked)
+    }
+
+    /// Multiplies two numbers, returning `None` on overflow or if either number is empty.
+    pub fn checked_mul(&self, other: &Number) -> Option<Number> {
+        arithmetic(self, other, Operation::Mul, Overflow::Checked)
+    }
+
+    /// Divides two numbers, returning `None` on overflow, division by zero or if either
+    /// number is empty.
+    pub fn checked_div(&self, other: &Number) -> Option<Number> {
+        arithmetic(self, other, Operation::Div, Overflow::Checked)
+    }
+
+    /// Calculates the remainder of a division, returning `None` on division by zero or if
+    /// either number is empty.
+    pub fn checked_rem(&self, other: &Number) -> Option<Number> {
+        arithmetic(self, other, Operation::Rem, Overflow::Checked)
+    }
+
+    /// Negates the number, returning `None` on overflow or if the number is empty.
+    ///
+    /// Unsigned numbers are negated into a signed type of twice their width.
+    pub fn checked_neg(&self) -> Option<Number> {
+        negate(self, Overflow::Checked)
+    }
+
+    /// Shifts an integer left, returning `None` if `amount` is not smaller than its width.
+    pub fn checked_shl(&self, amount: &Number) -> Option<Number> {
+        shift(self, amount, true, Overflow::Checked)
+    }
+
+    /// Shifts an integer right, returning `None` if `amount` is not smaller than its width.
+    pub fn checked_shr(&self, amount: &Number) -> Option<Number> {
+        shift(self, amount, false, Overflow::Checked)
+    }
+
+    /// Adds two numbers, wrapping around the bounds of the promoted type.
+    pub fn wrapping_add(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Add, Overflow::Wrapping)
+    }
+
+    /// Subtracts two numbers, wrapping around the bounds of the promoted type.
+    pub fn wrapping_sub(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Sub, Overflow::Wrapping)
+    }
+
+    /// Multiplies two numbers, wrapping around the bounds of the promoted type.
+    pub fn wrapping_mul(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Mul, Overflow::Wrapping)
+    }
+
+    /// Divides two numbers, wrapping around the bounds of the promoted type.
+    ///
+    /// # Panics
+    ///
+    /// Panics when dividing an integer by zero.
+    pub fn wrapping_div(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Div, Overflow::Wrapping)
+    }
+
+    /// Calculates the remainder of a division.
+    ///
+    /// # Panics
+    ///
+    /// Panics when dividing an integer by zero.
+    pub fn wrapping_rem(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Rem, Overflow::Wrapping)
+    }
+
+    /// Negates the number, wrapping around the bounds of its type.
+    pub fn wrapping_neg(&self) -> Number {
+        negate_or_panic(self, Overflow::Wrapping)
+    }
+
+    /// Shifts an integer left, masking `amount` to the width of its type.
+    pub fn wrapping_shl(&self, amount: &Number) -> Number {
+        shift(self, amount, true, Overflow::Wrapping)
+            .expect("Unable to shift left a number that is not an integer")
+    }
+
+    /// Shifts an integer right, masking `amount` to the width of its type.
+    pub fn wrapping_shr(&self, amount: &Number) -> Number {
+        shift(self, amount, false, Overflow::Wrapping)
+            .expect("Unable to shift right a number that is not an integer")
+    }
+
+    /// Adds two numbers, clamping the result to the bounds of the promoted type.
+    pub fn saturating_add(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Add, Overflow::Saturating)
+    }
+
+    /// Subtracts two numbers, clamping the result to the bounds of the promoted type.
+    pub fn saturating_sub(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Sub, Overflow::Saturating)
+    }
+
+    /// Multiplies two numbers, clamping the result to the bounds of the promoted type.
+    pub fn saturating_mul(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Mul, Overflow::Saturating)
+    }
+
+    /// Divides two numbers, clamping the result to the bounds of the promoted type.
+    ///
+    /// # Panics
+    ///
+    /// Panics when dividing an integer by zero.
+    pub fn saturating_div(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Div, Overflow::Saturating)
+    }
+
+    /// Calculates the remainder of a division, which never leaves the promoted type.
+    ///
+    /// # Panics
+    ///
+    /// Panics when dividing an integer by zero.
+    pub fn saturating_rem(&self, other: &Number) -> Number {
+        arithmetic_or_panic(self, other, Operation::Rem, Overflow::Saturating)
+    }
+
+    /// Negates the number, clamping the result to the bounds of its type.
+    pub fn saturating_neg(&self) -> Number {
+        negate_or_panic(self, Overflow::Saturating)
+    }
+}
+
+/// Implements a binary operator for `Number` and `&Number`, and for `Value` and `&Value`.
+///
+/// `Number` and `Value` accept anything convertible into them on the right-hand side,
+/// so `number + 1` and `value + 1` work as well.
+macro_rules! impl_operator {
+    ($trait:ident, $method:ident, $function:ident($($argument:expr),*)) => {
+        impl<T: Into<Number>> $trait<T> for Number {
+            type Output = Number;
+
+            fn $method(self, other: T) -> Number {
+                $function(&self, &other.into(), $($argument),*)
+            }
+        }
+
+        impl $trait<&Number> for &Number {
+            type Output = Number;
+
+            fn $method(self, other: &Number) -> Number {
+                $function(self, other, $($argument),*)
+            }
+        }
+
+        impl<T: Into<Value>> $trait<T> for Value {
+            type Output = Value;
+
+            fn $method(self, other: T) -> Value {
+                $trait::$method(&self, &other.into())
+            }
+        }
+
+        impl $trait<&Value> for &Value {
+            type Output = Value;
+
+            fn $method(self, other: &Value) -> Value {
+                match (self, other) {
+                    (Value::Number(left), Value::Number(right)) => {
+                        Value::Number($function(left, right, $($argument),*))
+                    }
+                    _ => panic!(
+                        "Unable to apply {} to a type other than a number",
+                        stringify!($trait)
+                    ),
+                }
+            }
+        }
+    };
+}
+
+impl_operator!(Add, add, operator(Operation::Add));
+impl_operator!(Sub, sub, operator(Operation::Sub));
+impl_operator!(Mul, mul, operator(Operation::Mul));
+impl_operator!(Div, div, operator(Operation::Div));
+impl_operator!(Rem, rem, operator(Operation::Rem));
+impl_operator!(BitAnd, bitand, bitwise(Bitwise::And));
+impl_operator!(BitOr, bitor, bitwise(Bitwise::Or));
+impl_operator!(BitXor, bitxor, bitwise(Bitwise::Xor));
+impl_operator!(Shl, shl, shift_or_panic(true));
+impl_operator!(Shr, shr, shift_or_panic(false));
+
+impl Neg for Number {
+    type Output = Number;
+
+    fn neg(self) -> Number {
+        negate_or_panic(&self, Overflow::Strict)
+    }
+}
+
+impl Neg for &Number {
+    type Output = Number;
+
+    fn neg(self) -> Number {
+        negate_or_panic(self, Overflow::Strict)
+    }
+}
+
+impl Neg for Value {
+    type Output = Value;
+
+    fn neg(self) -> Value {
+        -&self
+    }
+}
+
+impl Neg for &Value {
+    type Output = Value;
+
+    fn neg(self) -> Value {
+        match self {
+            Value::Number(number) => Value::Number(-number),
+            _ => panic!("Unable to negate a type other than a number"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn assert_strict(left: Number, right: Number) {
+        assert!(left.strict_eq(&right), "{:?} is not {:?}", left, right);
+    }
+
+    #[test]
+    fn test_promotion() {
+        assert_strict(Number::from(1u8) + Number::from(2u32), Number::from(3u32));
+        assert_strict(Number::from(1i64) + Number::from(2i8), Number::from(3i64));
+        assert_strict(
+            Number::from(200u8) + Number::from(-1i8),
+            Number::from(199i16),
+        );
+        assert_strict(
+            Number::from(1u64) - Number::from(2i32),
+            Number::from(-1i128),
+        );
+        assert_strict(
+            Number::from(2u8) * Number::from(1.5f32),
+            Number::from(3.0f64),
+        );
+        assert_strict(
+            Number::from(1.5f32) + Number::from(1.5f32),
+            Number::from(3.0f32),
+        );
+        assert_strict(Number::from(7i32) / 2, Number::from(3i32));
+        assert_strict(Number::from(-7i32) % 2, Number::from(-1i32));
+        assert_strict(-Number::from(5u8), Number::from(-5i16));
+    }
+
+    #[test]
+    fn test_u128_with_signed() {
+        assert_strict(
+            Number::from(u128::MAX) - Number::from(1i32),
+            Number::from(u128::MAX - 1),
+        );
+        assert_strict(
+            Number::from(1u128) - Number::from(2i8),
+            Number::from(-1i128),
+        );
+        assert_strict(
+            Number::from(i128::MAX) + Number::from(1u128),
+            Number::from(i128::MAX as u128 + 1),
+        );
+        assert_eq!(
+            Number::from(u128::MAX).checked_sub(&Number::from(1i32)),
+            Some(Number::from(u128::MAX - 1))
+        );
+        assert_eq!(
+            Number::from(u128::MAX).checked_add(&Number::from(1i32)),
+            None
+        );
+        assert_strict(
+            Number::from(u128::MAX).saturating_add(&Number::from(1i64)),
+            Number::from(u128::MAX),
+        );
+        assert_strict(
+            Number::from(u128::MAX).wrapping_add(&Number::from(1i64)),
+            Number::from(0u128),
+        );
+        assert_strict(
+            Number::from(0u128).saturating_sub(&Number::from(i128::MIN)),
+            Number::from(i128::MAX as u128 + 1),
+        );
+        assert_strict(
+            Number::from(u128::MAX).saturating_mul(&Number::from(-2i8)),
+            Number::from(i128::MIN),
+        );
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(Number::from(255u8).checked_add(&Number::from(1u8)), None);
+        assert_eq!(Number::from(0u8).checked_sub(&Number::from(1u8)), None);
+        assert_eq!(Number::from(1i32).checked_div(&Number::from(0i32)), None);
+        assert_eq!(Number::from(i8::MIN).checked_neg(), None);
+        assert_eq!(
+            Number::from(u128::MAX).checked_add(&Number::from(i128::MIN)),
+            Some(Number::from(i128::MAX))
+        );
+        assert_eq!(Number::from(f64::MAX).checked_mul(&Number::from(2u8)), None);
+        assert_eq!(Number::default().checked_add(&Number::from(1u8)), None);
+        assert_eq!(Number::from(1u8).checked_shl(&Number::from(8u8)), None);
+    }
+
+    #[test]
+    fn test_wrapping() {
+        assert_strict(
+            Number::from(255u8).wrapping_add(&Number::from(1u8)),
+            Number::from(0u8),
+        );
+        assert_strict(
+            Number::from(0u8).wrapping_sub(&Number::from(1u8)),
+            Number::from(255u8),
+        );
+        assert_strict(
+            Number::from(i64::MAX).wrapping_mul(&Number::from(2i64)),
+            Number::from(-2i64),
+        );
+        assert_strict(
+            Number::from(i8::MIN).wrapping_div(&Number::from(-1i8)),
+            Number::from(i8::MIN),
+        );
+        assert_strict(Number::from(i8::MIN).wrapping_neg(), Number::from(i8::MIN));
+        assert_strict(
+            Number::from(1u8).wrapping_shl(&Number::from(9u8)),
+            Number::from(2u8),
+        );
+    }
+
+    #[test]
+    fn test_saturating() {
+        assert_strict(
+            Number::from(250u8).saturating_add(&Number::from(10u8)),
+            Number::from(255u8),
+        );
+        assert_strict(
+            Number::from(5u32).saturating_sub(&Number::from(10u32)),
+            Number::from(0u32),
+        );
+        assert_strict(
+            Number::from(i32::MIN).saturating_mul(&Number::from(2i32)),
+            Number::from(i32::MIN),
+        );
+        assert_strict(
+            Number::from(i8::MIN).saturating_div(&Number::from(-1i8)),
+            Number::from(i8::MAX),
+        );
+        assert_strict(
+            Number::from(f32::MAX).saturating_mul(&Number::from(2.0f32)),
+            Number::from(f32::MAX),
+        );
+        assert_strict(
+            Number::from(i8::MIN).saturating_rem(&Number::from(-1i8)),
+            Number::from(0i8),
+        );
+        assert_strict(
+            Number::from(-7i32).saturating_rem(&Number::from(3u8)),
+            Number::from(-1i32),
+        );
+    }
+
+    #[test]
+    fn test_bitwise_and_shifts() {
+        assert_strict(
+            Number::from(0b1100u8) & Number::from(0b1010u8),
+            Number::from(0b1000u8),
+        );
+        assert_strict(
+            Number::from(0b1100u8) | Number::from(0b1010u16),
+            Number::from(0b1110u16),
+        );
+        assert_strict(Number::from(-1i8) ^ Number::from(1u8), Number::from(-2i16));
+        assert_strict(Number::from(1u8) << 7u8, Number::from(128u8));
+        assert_strict(Number::from(-16i32) >> 2u8, Number::from(-4i32));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Unable to apply a bitwise operation to a number that is not an integer"
+    )]
+    fn test_bitwise_on_float_panics() {
+        let _ = Number::from(1.0f64) & Number::from(1u8);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    #[should_panic(
+        expected = "Unable to apply a bitwise operation to a number that is not an integer"
+    )]
+    fn test_bitwise_on_decimal_panics() {
+        let _ = Number::from(BigDecimal::from(4)) | Number::from(1u8);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    #[should_panic(expected = "Unable to shift left a number that is not an integer")]
+    fn test_shift_on_decimal_panics() {
+        let _ = Number::from(BigDecimal::from(4)) << 1u8;
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to add with overflow")]
+    fn test_add_overflow_panics() {
+        let _ = Number::from(255u8) + Number::from(1u8);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_division_by_zero_panics() {
+        let _ = Number::from(1u8) / Number::from(0u8);
+    }
+
+    #[test]
+    fn test_float_division_by_zero() {
+        assert_strict(Number::from(1.0f64) / 0u8, Number::from(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_value_operators() {
+        let value = Value::from(40u8) + Value::from(2i32);
+        assert_strict(value.as_number().unwrap().clone(), Number::from(42i32));
+
+        assert_eq!(Value::from(10u8) * 2u8, Value::from(20u8));
+        assert_eq!(-&Value::from(1.5f64), Value::from(-1.5f64));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to apply Add to a type other than a number")]
+    fn test_value_operator_on_string_panics() {
+        let _ = Value::from("a") + Value::from(1u8);
+    }
+}
@@ -0,0 +1,195 @@
+//! Parsing of number literals written the way Rust source and config files write them.
+//!
+//! `Number::from_literal` accepts, on top of plain decimal numbers:
+//!
+//! - an explicit sign, `+5` or `-5`;
+//! - the radix prefixes `0x`, `0o` and `0b` for integers, `0xFF`, `0o755`, `0b1010`;
+//! - underscores between digits, `1_000_000`;
+//! - a type suffix selecting the `NumberType`, `10u8`, `-3i64`, `2.5f32`, `1usize`.
+//!
+//! Without a suffix, integers take the first of `i32`, `i64`, `i128` and `u128` that holds
+//! them, and numbers with a fraction or an exponent are `f64`. As in Rust, a hexadecimal
+//! literal only takes integer suffixes since `f32` are hexadecimal digits.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! assert_eq!(Number::from_literal("0xFF"), Ok(Number::from(255i32)));
+//! assert_eq!(Number::from_literal("1_000u16"), Ok(Number::from(1000u16)));
+//! assert!(Number::from_literal("256u8").is_err());
+//! ```
+use crate::prelude::*;
+
+const SUFFIXES: [&str; 14] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32",
+    "f64",
+];
+
+impl Number {
+    /// Parses a number literal with an optional sign, radix prefix, underscores and type
+    /// suffix.
+    ///
+    /// Returns `Error::NotNumber` when the text is not a literal or its value does not fit
+    /// the type of the suffix.
+    pub fn from_literal(text: &str) -> Result<Number, Error> {
+        let (negative, body) = match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            _ => (false, text),
+        };
+
+        let prefix = body.get(..2).map(str::to_ascii_lowercase);
+        let (radix, body) = match prefix.as_deref() {
+            Some("0x") => (16, &body[2..]),
+            Some("0o") => (8, &body[2..]),
+            Some("0b") => (2, &body[2..]),
+            _ => (10, body),
+        };
+
+        let suffix = SUFFIXES
+            .iter()
+            .find(|suffix| body.ends_with(*suffix) && !(radix == 16 && suffix.starts_with('f')))
+            .copied();
+        let body = &body[..body.len() - suffix.map_or(0, str::len)];
+
+        // Underscores only separate digits, the literal starts with one.
+        if body.is_empty() || (radix == 10 && !body.starts_with(|c: char| c.is_ascii_digit())) {
+            return Err(Error::NotNumber);
+        }
+
+        let digits: String = body.chars().filter(|c| *c != '_').collect();
+        let sign = if negative { "-" } else { "" };
+
+        let float = matches!(suffix, Some("f32" | "f64"))
+            || (radix == 10 && digits.contains(['.', 'e', 'E']));
+
+        if float {
+            if radix != 10
+                || !digits
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+            {
+                return Err(Error::NotNumber);
+            }
+
+            let text = format!("{}{}", sign, digits);
+            let number = match suffix {
+                None | Some("f64") => text.parse::<f64>().map(Number::from),
+                Some("f32") => text.parse::<f32>().map(Number::from),
+                _ => return Err(Error::NotNumber),
+            };
+            return number.map_err(|_| Error::NotNumber);
+        }
+
+        if !digits.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::NotNumber);
+        }
+
+        let magnitude = u128::from_str_radix(&digits, radix).map_err(|_| Error::NotNumber)?;
+        let number = if negative {
+            if magnitude > i128::MIN.unsigned_abs() {
+                return Err(Error::NotNumber);
+            }
+            // The magnitude of `i128::MIN` wraps to itself.
+            Number::from((magnitude as i128).wrapping_neg())
+        } else {
+            Number::from(magnitude)
+        };
+
+        let policy = ConversionPolicy::Exact;
+        let converted = match suffix {
+            Some("u8") => number.to_u8(policy).map(Number::from),
+            Some("u16") => number.to_u16(policy).map(Number::from),
+            Some("u32") => number.to_u32(policy).map(Number::from),
+            Some("u64") => number.to_u64(policy).map(Number::from),
+            Some("u128") => number.to_u128(policy).map(Number::from),
+            Some("usize") => number.to_usize(policy).map(Number::from),
+            Some("i8") => number.to_i8(policy).map(Number::from),
+            Some("i16") => number.to_i16(policy).map(Number::from),
+            Some("i32") => number.to_i32(policy).map(Number::from),
+            Some("i64") => number.to_i64(policy).map(Number::from),
+            Some("i128") => number.to_i128(policy).map(Number::from),
+            Some("isize") => number.to_isize(policy).map(Number::from),
+            _ => number
+                .to_i32(policy)
+                .map(Number::from)
+                .or_else(|| number.to_i64(policy).map(Number::from))
+                .or_else(|| number.to_i128(policy).map(Number::from))
+                .or(Some(number)),
+        };
+
+        converted.ok_or(Error::NotNumber)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn literal(text: &str) -> Number {
+        Number::from_literal(text).unwrap()
+    }
+
+    #[test]
+    fn test_radix_and_separators() {
+        assert!(literal("0xFF").strict_eq(&Number::from(255i32)));
+        assert!(literal("0o755").strict_eq(&Number::from(493i32)));
+        assert!(literal("0B1010").strict_eq(&Number::from(10i32)));
+        assert!(literal("1_000_000").strict_eq(&Number::from(1_000_000i32)));
+        assert!(literal("0x_ff_ff").strict_eq(&Number::from(65535i32)));
+        assert!(literal("-0x80").strict_eq(&Number::from(-128i32)));
+        assert!(literal("1_000.5").strict_eq(&Number::from(1000.5f64)));
+    }
+
+    #[test]
+    fn test_sign_and_default_types() {
+        assert!(literal("+5").strict_eq(&Number::from(5i32)));
+        assert!(literal("-5").strict_eq(&Number::from(-5i32)));
+        assert!(literal("4294967295").strict_eq(&Number::from(4294967295i64)));
+        assert!(
+            literal("-170141183460469231731687303715884105728").strict_eq(&Number::from(i128::MIN))
+        );
+        assert!(literal("0xffffffffffffffffffffffffffffffff").strict_eq(&Number::from(u128::MAX)));
+        assert!(literal("1e3").strict_eq(&Number::from(1000f64)));
+    }
+
+    #[test]
+    fn test_suffixes() {
+        assert!(literal("10u8").strict_eq(&Number::from(10u8)));
+        assert!(literal("-3i64").strict_eq(&Number::from(-3i64)));
+        assert!(literal("2.5f32").strict_eq(&Number::from(2.5f32)));
+        assert!(literal("7f64").strict_eq(&Number::from(7f64)));
+        assert!(literal("0xffu16").strict_eq(&Number::from(255u16)));
+        assert!(literal("0x1f32").strict_eq(&Number::from(0x1f32i32)));
+        assert!(literal("1_usize").strict_eq(&Number::from(1usize)));
+    }
+
+    #[test]
+    fn test_invalid_literals() {
+        for text in [
+            "",
+            "+",
+            "_1",
+            "0x",
+            "256u8",
+            "-1u8",
+            "1.5u8",
+            "0x1.5",
+            "0b102",
+            "1e",
+            "inf",
+            "NaN",
+            "1.5x",
+            "--1",
+            "0o7f32",
+            "0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffff_f",
+        ] {
+            assert_eq!(
+                Number::from_literal(text),
+                Err(Error::NotNumber),
+                "{}",
+                text
+            );
+        }
+    }
+}
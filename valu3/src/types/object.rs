@@ -1,5 +1,7 @@
 use crate::prelude::*;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 use std::iter::Iterator;
 
 pub trait ObjectBehavior {
@@ -21,12 +23,37 @@ pub trait ObjectBehavior {
 }
 
 /// An enum representing a JSON object as a `BTreeMap` or a `HashMap`.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Objects compare and hash by their entries, whichever map holds them.
+#[derive(Debug, Clone)]
 pub enum Object {
     BTreeMap(BTreeMap<ValueKey, Value>),
     HashMap(HashMap<ValueKey, Value>),
 }
 
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get_by_key(key) == Some(value))
+    }
+}
+
+impl Hash for Object {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Entries are combined with a commutative sum, since a `HashMap` has no stable order.
+        let sum = self.iter().fold(0u64, |sum, entry| {
+            let mut hasher = DefaultHasher::new();
+            entry.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+
+        self.len().hash(state);
+        sum.hash(state);
+    }
+}
+
 impl PartialOrd for Object {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         match (self, other) {
@@ -50,6 +77,28 @@ impl Object {
         }
     }
 
+    pub(crate) fn get_by_key(&self, key: &ValueKey) -> Option<&Value> {
+        match self {
+            Object::BTreeMap(map) => map.get(key),
+            Object::HashMap(map) => map.get(key),
+        }
+    }
+
+    pub(crate) fn remove_by_key(&mut self, key: &ValueKey) -> Option<Value> {
+        match self {
+            Object::BTreeMap(map) => map.remove(key),
+            Object::HashMap(map) => map.remove(key),
+        }
+    }
+
+    /// Returns the value of `key`, inserting `Value::Null` when it is missing.
+    pub(crate) fn entry_or_null(&mut self, key: ValueKey) -> &mut Value {
+        match self {
+            Object::BTreeMap(map) => map.entry(key).or_insert(Value::Null),
+            Object::HashMap(map) => map.entry(key).or_insert(Value::Null),
+        }
+    }
+
     pub fn get_mut<T>(&mut self, key: T) -> Option<&mut Value>
     where
         T: ValueKeyBehavior,
@@ -222,6 +271,16 @@ impl Into<BTreeMap<ValueKey, Value>> for Object {
     }
 }
 
+impl Extend<(ValueKey, Value)> for Object {
+    /// Inserts every key-value pair, replacing the values of existing keys.
+    fn extend<I: IntoIterator<Item = (ValueKey, Value)>>(&mut self, iter: I) {
+        match self {
+            Object::BTreeMap(map) => map.extend(iter),
+            Object::HashMap(map) => map.extend(iter),
+        }
+    }
+}
+
 /// An iterator over the key-value pairs in an Object.
 #[allow(dead_code)]
 pub struct ObjectIter<'a> {
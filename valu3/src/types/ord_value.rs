@@ -0,0 +1,257 @@
+//! A total order over every `Value`, for sorting mixed arrays and for ordered collections.
+//!
+//! Values of different variants are ordered as
+//! undefined < null < boolean < number < string < datetime < array < object,
+//! and values of the same variant are compared deeply:
+//!
+//! - numbers by value whatever their types, in the order `Number::total_cmp` refines: empty
+//!   numbers first, NaN with the sign bit set before and other NaN after every other
+//!   number, so `1u8` and `1.0f64` are equal, as are `0.0` and `-0.0`;
+//! - strings by their bytes;
+//! - datetimes as dates, then times, then date-times, each chronologically;
+//! - arrays element by element, a shorter prefix first;
+//! - objects as lists of entries sorted by key, whichever map holds them.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! let mut values = vec![Value::from("a"), Value::from(2), Value::Null, Value::from(true)];
+//! values.sort_by(Value::total_cmp);
+//!
+//! let set: BTreeSet<OrdValue> = values.into_iter().map(OrdValue::from).collect();
+//! ```
+use crate::prelude::*;
+use std::cmp::Ordering;
+use std::ops::Deref;
+
+impl Value {
+    /// Compares two values with the total order described in `crate::types::ord_value`.
+    pub fn total_cmp(&self, other: &Value) -> Ordering {
+        fn rank(value: &Value) -> u8 {
+            match value {
+                Value::Undefined => 0,
+                Value::Null => 1,
+                Value::Boolean(_) => 2,
+                Value::Number(_) => 3,
+                Value::String(_) => 4,
+                Value::DateTime(_) => 5,
+                Value::Array(_) => 6,
+                Value::Object(_) => 7,
+            }
+        }
+
+        match (self, other) {
+            (Value::Boolean(left), Value::Boolean(right)) => left.cmp(right),
+            (Value::Number(left), Value::Number(right)) => left.value_cmp(right),
+            (Value::String(left), Value::String(right)) => left.cmp(right),
+            (Value::DateTime(left), Value::DateTime(right)) => left.cmp(right),
+            (Value::Array(left), Value::Array(right)) => cmp_arrays(left, right),
+            (Value::Object(left), Value::Object(right)) => cmp_objects(left, right),
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+fn cmp_arrays(left: &Array, right: &Array) -> Ordering {
+    left.into_iter()
+        .zip(right)
+        .map(|(left, right)| left.total_cmp(right))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| left.len().cmp(&right.len()))
+}
+
+fn cmp_objects(left: &Object, right: &Object) -> Ordering {
+    fn sorted(object: &Object) -> Vec<(&ValueKey, &Value)> {
+        let mut entries: Vec<_> = object.iter().collect();
+        entries.sort_by(|left, right| left.0.cmp(right.0));
+        entries
+    }
+
+    let (left, right) = (sorted(left), sorted(right));
+    left.iter()
+        .zip(&right)
+        .map(|((left_key, left), (right_key, right))| {
+            left_key.cmp(right_key).then_with(|| left.total_cmp(right))
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| left.len().cmp(&right.len()))
+}
+
+/// A `Value` with `Ord`, following `Value::total_cmp`, for `BTreeMap` keys, `BTreeSet`s and
+/// `sort`.
+///
+/// Two values are equal when `total_cmp` finds them `Equal`, which matches the equality of
+/// `HashableValue`.
+#[derive(Debug, Clone)]
+pub struct OrdValue(pub Value);
+
+impl OrdValue {
+    pub fn into_inner(self) -> Value {
+        self.0
+    }
+}
+
+impl PartialEq for OrdValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrdValue {}
+
+impl PartialOrd for OrdValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrdValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Deref for OrdValue {
+    type Target = Value;
+
+    fn deref(&self) -> &Value {
+        &self.0
+    }
+}
+
+impl From<Value> for OrdValue {
+    fn from(value: Value) -> Self {
+        OrdValue(value)
+    }
+}
+
+impl From<OrdValue> for Value {
+    fn from(value: OrdValue) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use std::cmp::Ordering;
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+    #[test]
+    fn test_order_across_variants() {
+        let mut values = [
+            Value::from(vec![("a", 1)]),
+            Value::from(vec![1]),
+            Value::DateTime(DateTime::from("2023-04-05T00:00:00Z")),
+            Value::from("text"),
+            Value::from(1.5),
+            Value::from(false),
+            Value::Null,
+            Value::Undefined,
+        ];
+        values.sort_by(Value::total_cmp);
+
+        assert_eq!(values[0], Value::Undefined);
+        assert_eq!(values[1], Value::Null);
+        assert_eq!(values[2], Value::from(false));
+        assert_eq!(values[3], Value::from(1.5));
+        assert_eq!(values[4], Value::from("text"));
+        assert!(matches!(values[5], Value::DateTime(_)));
+        assert_eq!(values[6], Value::from(vec![1]));
+        assert!(values[7].is_object());
+    }
+
+    #[test]
+    fn test_deep_order() {
+        let nan = Value::from(f64::NAN);
+        assert_eq!(
+            nan.total_cmp(&Value::from(f64::INFINITY)),
+            Ordering::Greater
+        );
+        assert_eq!(nan.total_cmp(&nan), Ordering::Equal);
+        assert_eq!(
+            Value::from(-f64::NAN).total_cmp(&Value::from(f64::NEG_INFINITY)),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(1u8).total_cmp(&Value::from(1.0)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            Value::from(-0.0).total_cmp(&Value::from(0u8)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            Value::from(vec![1, 2]).total_cmp(&Value::from(vec![1, 2, 0])),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(vec![2]).total_cmp(&Value::from(vec![1, 2, 0])),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_numbers_follow_number_total_cmp() {
+        let numbers = [
+            Number::Empty,
+            Number::from(-f64::NAN),
+            Number::from(f64::NEG_INFINITY),
+            Number::from(-1i8),
+            Number::from(-0.0f32),
+            Number::from(0u8),
+            Number::from(1.5f64),
+            Number::from(u128::MAX),
+            Number::from(f32::NAN),
+        ];
+
+        for left in &numbers {
+            for right in &numbers {
+                let ordering = Value::from(left.clone()).total_cmp(&Value::from(right.clone()));
+                if ordering.is_ne() {
+                    assert_eq!(left.total_cmp(right), ordering, "{:?} {:?}", left, right);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_objects_compare_by_sorted_entries() {
+        let mut hash_map = HashMap::new();
+        hash_map.insert("b", 1);
+        hash_map.insert("a", 2);
+        let mut btree_map = BTreeMap::new();
+        btree_map.insert("a", 2);
+        btree_map.insert("b", 1);
+
+        let left = Value::from(hash_map);
+        assert_eq!(left.total_cmp(&Value::from(btree_map)), Ordering::Equal);
+        assert_eq!(left.total_cmp(&Value::from(vec![("a", 3)])), Ordering::Less);
+        assert_eq!(
+            left.total_cmp(&Value::from(vec![("a", 2)])),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_ord_value() {
+        let set: BTreeSet<OrdValue> = [
+            Value::from(2),
+            Value::from("b"),
+            Value::from(2.0),
+            Value::from(f64::NAN),
+            Value::from(f64::NAN),
+            Value::Null,
+        ]
+        .into_iter()
+        .map(OrdValue::from)
+        .collect();
+
+        let values: Vec<Value> = set.into_iter().map(Value::from).collect();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[0], Value::Null);
+        assert_eq!(values[1], Value::from(2));
+        assert!(values[2].as_number().unwrap().is_nan());
+        assert_eq!(values[3], Value::from("b"));
+    }
+}
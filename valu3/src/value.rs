@@ -18,7 +18,10 @@ use crate::prelude::*;
 use std::fmt::{Display, Formatter};
 
 /// Represents different data types as an enum.
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+///
+/// `Value` hashes consistently with `==`. Since NaN is not equal to itself, use
+/// `HashableValue` for keys of a `HashMap` or a `HashSet`.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Hash)]
 pub enum Value {
     String(StringB),
     Number(Number),
@@ -42,7 +45,7 @@ impl Display for Value {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Value::String(_) => write!(f, "{}", self.to_json(JsonMode::Indented)),
-            Value::Number(value) => write!(f, "{}", value),
+            Value::Number(_) => write!(f, "{}", self.to_json(JsonMode::Inline)),
             Value::Boolean(value) => write!(f, "{}", if *value { "true" } else { "false" }),
             Value::Array(_) => write!(f, "{}", self.to_json(JsonMode::Indented)),
             Value::Object(_) => write!(f, "{}", self.to_json(JsonMode::Indented)),
@@ -0,0 +1,272 @@
+use syn::{Attribute, ExprPath, LitStr};
+
+/// Case conventions accepted by `#[valu3(rename_all = "...")]`.
+#[derive(Clone, Copy)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<Self> {
+        match rule {
+            "lowercase" => Some(RenameRule::Lower),
+            "UPPERCASE" => Some(RenameRule::Upper),
+            "PascalCase" => Some(RenameRule::Pascal),
+            "camelCase" => Some(RenameRule::Camel),
+            "snake_case" => Some(RenameRule::Snake),
+            "SCREAMING_SNAKE_CASE" => Some(RenameRule::ScreamingSnake),
+            "kebab-case" => Some(RenameRule::Kebab),
+            "SCREAMING-KEBAB-CASE" => Some(RenameRule::ScreamingKebab),
+            _ => None,
+        }
+    }
+
+    /// Applies the rule to a `snake_case` Rust field name.
+    pub(crate) fn apply_to_field(&self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply_to_field(field);
+                lowercase_first(&pascal)
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Applies the rule to a `PascalCase` Rust variant name.
+    pub(crate) fn apply_to_variant(&self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => lowercase_first(variant),
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (index, ch) in variant.char_indices() {
+                    if index > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+}
+
+fn lowercase_first(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// How a missing field is filled in by `FromValue`.
+pub(crate) enum FieldDefault {
+    /// `#[valu3(default)]`, uses `Default::default()`.
+    Trait,
+    /// `#[valu3(default = "path::to::fn")]`.
+    Path(ExprPath),
+}
+
+/// How the variants of an enum are represented as a `Value`.
+pub(crate) enum Tagging {
+    /// `{"Variant": content}`, the default.
+    External,
+    /// `{"tag": "Variant", ...fields}`, set with `#[valu3(tag = "...")]`.
+    Internal { tag: String },
+    /// `{"tag": "Variant", "content": content}`, set with `#[valu3(tag = "...", content = "...")]`.
+    Adjacent { tag: String, content: String },
+    /// Only the content, set with `#[valu3(untagged)]`.
+    Untagged,
+}
+
+/// Attributes placed on the struct or enum itself.
+#[derive(Default)]
+pub(crate) struct ContainerAttrs {
+    pub(crate) rename_all: Option<RenameRule>,
+    pub(crate) tag: Option<String>,
+    pub(crate) content: Option<String>,
+    pub(crate) untagged: bool,
+    pub(crate) transparent: bool,
+}
+
+/// Attributes placed on a struct field.
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    pub(crate) rename: Option<String>,
+    pub(crate) skip: bool,
+    pub(crate) default: Option<FieldDefault>,
+    pub(crate) flatten: bool,
+    /// Module providing `to_value(&T) -> Value` and `from_value(Value) -> Option<T>`.
+    pub(crate) with: Option<syn::Path>,
+}
+
+/// Attributes placed on an enum variant.
+#[derive(Default)]
+pub(crate) struct VariantAttrs {
+    pub(crate) rename: Option<String>,
+}
+
+/// Calls `parse` for every item found inside the `#[valu3(...)]` attributes.
+fn for_each_meta<F>(attrs: &[Attribute], mut parse: F)
+where
+    F: FnMut(syn::meta::ParseNestedMeta) -> syn::Result<()>,
+{
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("valu3")) {
+        if let Err(err) = attr.parse_nested_meta(&mut parse) {
+            panic!("Invalid valu3 attribute: {}", err);
+        }
+    }
+}
+
+impl ContainerAttrs {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> Self {
+        let mut container = ContainerAttrs::default();
+
+        for_each_meta(attrs, |meta| {
+            if meta.path.is_ident("rename_all") {
+                let rule: LitStr = meta.value()?.parse()?;
+                match RenameRule::from_str(&rule.value()) {
+                    Some(rule) => container.rename_all = Some(rule),
+                    None => {
+                        return Err(meta.error(format!("unknown rename rule `{}`", rule.value())))
+                    }
+                }
+                Ok(())
+            } else if meta.path.is_ident("tag") {
+                let tag: LitStr = meta.value()?.parse()?;
+                container.tag = Some(tag.value());
+                Ok(())
+            } else if meta.path.is_ident("content") {
+                let content: LitStr = meta.value()?.parse()?;
+                container.content = Some(content.value());
+                Ok(())
+            } else if meta.path.is_ident("untagged") {
+                container.untagged = true;
+                Ok(())
+            } else if meta.path.is_ident("transparent") {
+                container.transparent = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported container attribute"))
+            }
+        });
+
+        container
+    }
+
+    pub(crate) fn tagging(&self) -> Tagging {
+        if self.transparent {
+            panic!("#[valu3(transparent)] can only be used on structs");
+        }
+
+        match (&self.tag, &self.content, self.untagged) {
+            (None, None, false) => Tagging::External,
+            (Some(tag), None, false) => Tagging::Internal { tag: tag.clone() },
+            (Some(tag), Some(content), false) => Tagging::Adjacent {
+                tag: tag.clone(),
+                content: content.clone(),
+            },
+            (None, None, true) => Tagging::Untagged,
+            (None, Some(_), _) => {
+                panic!("#[valu3(content = \"...\")] requires #[valu3(tag = \"...\")]")
+            }
+            (_, _, true) => panic!("#[valu3(untagged)] cannot be combined with tag or content"),
+        }
+    }
+}
+
+impl FieldAttrs {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> Self {
+        let mut field = FieldAttrs::default();
+
+        for_each_meta(attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                let rename: LitStr = meta.value()?.parse()?;
+                field.rename = Some(rename.value());
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                field.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                if meta.input.peek(syn::Token![=]) {
+                    let path: LitStr = meta.value()?.parse()?;
+                    field.default = Some(FieldDefault::Path(path.parse()?));
+                } else {
+                    field.default = Some(FieldDefault::Trait);
+                }
+                Ok(())
+            } else if meta.path.is_ident("flatten") {
+                field.flatten = true;
+                Ok(())
+            } else if meta.path.is_ident("with") {
+                let module: LitStr = meta.value()?.parse()?;
+                field.with = Some(module.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported field attribute"))
+            }
+        });
+
+        field
+    }
+}
+
+impl VariantAttrs {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> Self {
+        let mut variant = VariantAttrs::default();
+
+        for_each_meta(attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                let rename: LitStr = meta.value()?.parse()?;
+                variant.rename = Some(rename.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported variant attribute"))
+            }
+        });
+
+        variant
+    }
+}
+
+/// Strips the `r#` prefix of raw identifiers such as `r#type`.
+pub(crate) fn unraw(ident: &syn::Ident) -> String {
+    let ident = ident.to_string();
+    ident.trim_start_matches("r#").to_string()
+}
@@ -1,18 +1,28 @@
 extern crate proc_macro;
+mod attr;
+
+use attr::{unraw, ContainerAttrs, FieldAttrs, FieldDefault, Tagging, VariantAttrs};
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields, Generics, Variant};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, FieldsNamed, Generics, Ident, Variant};
 
-#[proc_macro_derive(ToValue)]
+#[proc_macro_derive(ToValue, attributes(valu3))]
 pub fn to_value_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
+    let container = ContainerAttrs::from_attrs(&input.attrs);
     let name = input.ident;
-    let generics = input.generics;
+    let generics = with_field_bounds(&input.generics, &input.data, |_, attrs| {
+        if attrs.skip || attrs.with.is_some() {
+            None
+        } else {
+            Some(quote! { ToValueBehavior })
+        }
+    });
 
     let to_value_impl = match input.data {
-        Data::Struct(data) => to_value_struct_impl(name, generics, data.fields),
-        Data::Enum(data) => to_value_enum_impl(name, generics, data.variants),
+        Data::Struct(data) => to_value_struct_impl(name, generics, data.fields, &container),
+        Data::Enum(data) => to_value_enum_impl(name, generics, data.variants, &container),
         Data::Union(_) => panic!("ToValueBehavior cannot be derived for unions"),
     };
 
@@ -23,56 +33,347 @@ pub fn to_value_derive(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Returns the key used for a named field, after `rename` and `rename_all`.
+fn field_key(field: &syn::Field, attrs: &FieldAttrs, container: &ContainerAttrs) -> String {
+    if let Some(rename) = &attrs.rename {
+        return rename.clone();
+    }
+
+    let name = match field.ident.as_ref() {
+        Some(name) => unraw(name),
+        None => panic!("Field keys can only be computed for named fields"),
+    };
+
+    match &container.rename_all {
+        Some(rule) => rule.apply_to_field(&name),
+        None => name,
+    }
+}
+
+/// Returns the string used for an enum variant, after `rename` and `rename_all`.
+fn variant_key(variant: &Variant, container: &ContainerAttrs) -> String {
+    let attrs = VariantAttrs::from_attrs(&variant.attrs);
+    if let Some(rename) = attrs.rename {
+        return rename;
+    }
+
+    let name = unraw(&variant.ident);
+    match &container.rename_all {
+        Some(rule) => rule.apply_to_variant(&name),
+        None => name,
+    }
+}
+
+/// Returns the attributes applying to the fields of enum variants.
+///
+/// As in serde, `rename_all` on an enum renames its variants but not their fields.
+fn variant_fields_attrs() -> ContainerAttrs {
+    ContainerAttrs::default()
+}
+
+/// Returns `true` when the tokens of `ty` mention one of the generic type parameters.
+fn mentions_type_param(ty: &syn::Type, params: &[Ident]) -> bool {
+    fn walk(tokens: proc_macro2::TokenStream, params: &[Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => params.contains(&ident),
+            proc_macro2::TokenTree::Group(group) => walk(group.stream(), params),
+            _ => false,
+        })
+    }
+
+    walk(quote! { #ty }, params)
+}
+
+/// Adds a where predicate for every field type that mentions a generic type parameter.
+///
+/// `bound` receives each field with its attributes and returns the bound to require,
+/// or `None` when the field does not need one. Field types without type parameters
+/// are left alone, so generic types only pay for the bounds their fields actually use.
+fn with_field_bounds<F>(generics: &Generics, data: &Data, bound: F) -> Generics
+where
+    F: Fn(&syn::Field, &FieldAttrs) -> Option<proc_macro2::TokenStream>,
+{
+    let params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    let mut generics = generics.clone();
+    if params.is_empty() {
+        return generics;
+    }
+
+    let fields: Vec<&syn::Field> = match data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter())
+            .collect(),
+        Data::Union(data) => data.fields.named.iter().collect(),
+    };
+
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        let field_type = &field.ty;
+        if !mentions_type_param(field_type, &params) {
+            continue;
+        }
+
+        let attrs = FieldAttrs::from_attrs(&field.attrs);
+        if let Some(bound) = bound(field, &attrs) {
+            where_clause
+                .predicates
+                .push(syn::parse_quote! { #field_type: #bound });
+        }
+    }
+
+    generics
+}
+
+/// Adds `Self: ToValueBehavior` so derives built on top of `to_value` share its bounds.
+fn with_self_to_value_bound(name: &Ident, generics: &Generics) -> Generics {
+    let mut bounded = generics.clone();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    bounded
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! { #name #ty_generics: ToValueBehavior });
+    bounded
+}
+
+/// Builds the expression used when a field is skipped or missing and has a default.
+fn default_expr(default: &FieldDefault) -> proc_macro2::TokenStream {
+    match default {
+        FieldDefault::Trait => quote! { ::std::default::Default::default() },
+        FieldDefault::Path(path) => quote! { #path() },
+    }
+}
+
+/// Builds the expression converting a field, given as a reference, into a `Value`.
+fn field_to_value(
+    attrs: &FieldAttrs,
+    reference: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match &attrs.with {
+        Some(module) => quote! { #module::to_value(#reference) },
+        None => quote! { ToValueBehavior::to_value(#reference) },
+    }
+}
+
+/// Builds the expression converting a `Value` into an `Option` of the field type.
+fn field_from_value(
+    field: &syn::Field,
+    attrs: &FieldAttrs,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let field_type = &field.ty;
+    match &attrs.with {
+        Some(module) => quote! { #module::from_value(#value) },
+        None => quote! { <#field_type as FromValueBehavior>::from_value(#value) },
+    }
+}
+
+/// Builds the statements inserting every named field into an `object` variable.
+///
+/// `access` receives the field identifier and returns a reference to it,
+/// such as `&self.field` for structs or a binding for enum variants.
+fn named_fields_to_object<F>(
+    fields: &FieldsNamed,
+    container: &ContainerAttrs,
+    access: F,
+) -> Vec<proc_macro2::TokenStream>
+where
+    F: Fn(&Ident) -> proc_macro2::TokenStream,
+{
+    fields
+        .named
+        .iter()
+        .filter_map(|field| {
+            let attrs = FieldAttrs::from_attrs(&field.attrs);
+            if attrs.skip {
+                return None;
+            }
+
+            let name = match field.ident.as_ref() {
+                Some(name) => name,
+                None => panic!("ToValueBehavior cannot be derived for unnamed fields"),
+            };
+            let field_value = field_to_value(&attrs, access(name));
+            let field_name = field_key(field, &attrs, container);
+            if attrs.flatten {
+                if attrs.with.is_none() && is_scalar(&field.ty) {
+                    panic!("Only fields converted into objects can be flattened");
+                }
+
+                // A value that is not an object has no members to merge and keeps its key.
+                return Some(quote! {
+                    match #field_value {
+                        Value::Object(flattened) => {
+                            let entries: std::collections::HashMap<ValueKey, Value> = flattened.into();
+                            object.extend(entries);
+                        }
+                        Value::Null => {}
+                        other => {
+                            object.insert(#field_name, other);
+                        }
+                    }
+                });
+            }
+
+            Some(quote! {
+                object.insert(#field_name, #field_value);
+            })
+        })
+        .collect()
+}
+
+/// Builds the `field: expr` initializers reading every named field from a `map` object.
+///
+/// The generated expressions `return None` when a required field is missing or invalid.
+/// Flattened fields receive the entries of `map` not claimed by their sibling fields.
+fn named_fields_from_map(
+    fields: &FieldsNamed,
+    container: &ContainerAttrs,
+) -> Vec<proc_macro2::TokenStream> {
+    let claimed_keys = fields
+        .named
+        .iter()
+        .filter_map(|field| {
+            let attrs = FieldAttrs::from_attrs(&field.attrs);
+            if attrs.skip || attrs.flatten {
+                None
+            } else {
+                Some(field_key(field, &attrs, container))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    fields
+        .named
+        .iter()
+        .map(|field| {
+            let field_name = match field.ident.as_ref() {
+                Some(name) => name,
+                None => panic!("Can only derive FromValueBehavior for a struct with named fields."),
+            };
+            let attrs = FieldAttrs::from_attrs(&field.attrs);
+
+            if attrs.skip {
+                let default = default_expr(attrs.default.as_ref().unwrap_or(&FieldDefault::Trait));
+                return quote! {
+                    #field_name: #default
+                };
+            }
+
+            let key = field_key(field, &attrs, container);
+            if attrs.flatten {
+                let from_value = field_from_value(field, &attrs, quote! { Value::Object(rest) });
+                let from_item = field_from_value(field, &attrs, quote! { item.clone() });
+                return quote! {
+                    #field_name: {
+                        let mut rest = map.clone();
+                        #(rest.remove(&#claimed_keys);)*
+                        match #from_value {
+                            Some(item) => item,
+                            None => match map.get(#key).and_then(|item| #from_item) {
+                                Some(item) => item,
+                                None => return None,
+                            },
+                        }
+                    }
+                };
+            }
+
+            let from_value = field_from_value(field, &attrs, quote! { item.clone() });
+            let missing = match &attrs.default {
+                Some(default) => default_expr(default),
+                None if is_option(&field.ty) => quote! { None },
+                None => quote! { return None },
+            };
+
+            quote! {
+                #field_name: {
+                    match map.get(#key) {
+                        Some(item) => match #from_value {
+                            Some(item) => item,
+                            None => return None,
+                        },
+                        None => #missing,
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// Returns the only field of a `#[valu3(transparent)]` struct that is not skipped.
+fn transparent_field(fields: &Fields) -> (usize, &syn::Field) {
+    let mut candidates = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !FieldAttrs::from_attrs(&field.attrs).skip);
+
+    match (candidates.next(), candidates.next()) {
+        (Some(candidate), None) => candidate,
+        _ => panic!("#[valu3(transparent)] requires exactly one field that is not skipped"),
+    }
+}
+
+/// Returns the expression reading a struct field through `self`.
+fn self_field(index: usize, field: &syn::Field) -> proc_macro2::TokenStream {
+    match &field.ident {
+        Some(name) => quote! { self.#name },
+        None => {
+            let index = syn::Index::from(index);
+            quote! { self.#index }
+        }
+    }
+}
+
 fn to_value_struct_impl(
     name: syn::Ident,
     generics: Generics,
     fields: Fields,
+    container: &ContainerAttrs,
 ) -> proc_macro2::TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let field_transforms = match fields {
-        Fields::Named(fields) => fields
-            .named
-            .iter()
-            .map(|field| {
-                let name = match field.ident.as_ref() {
-                    Some(name) => name,
-                    None => panic!("ToValueBehavior cannot be derived for unnamed fields"),
-                };
-                let field_name = format!("{}", name);
+    let body = if container.transparent {
+        let (index, field) = transparent_field(&fields);
+        let access = self_field(index, field);
+        field_to_value(&FieldAttrs::from_attrs(&field.attrs), quote! { &#access })
+    } else {
+        match &fields {
+            Fields::Named(fields) => {
+                let field_transforms = named_fields_to_object(fields, container, |name| {
+                    quote! { &self.#name }
+                });
                 quote! {
-                    map.insert(#field_name.to_string(), self.#name.clone().into());
+                    let mut object = Object::default();
+                    #(#field_transforms)*
+                    Value::Object(object)
                 }
-            })
-            .collect::<Vec<_>>(),
-        Fields::Unnamed(fields) => fields
-            .unnamed
-            .iter()
-            .enumerate()
-            .map(|(index, _field)| {
-                let index = syn::Index::from(index);
+            }
+            Fields::Unnamed(fields) => {
+                let items = fields.unnamed.iter().enumerate().map(|(index, field)| {
+                    let access = self_field(index, field);
+                    field_to_value(&FieldAttrs::from_attrs(&field.attrs), quote! { &#access })
+                });
                 quote! {
-                    Value::from(self.#index.clone())
-                }
-            })
-            .collect::<Vec<_>>(),
-        Fields::Unit => {
-            return quote! {
-                impl #impl_generics ToValueBehavior for  #name #ty_generics #where_clause {
-                    fn to_value(&self) -> Value {
-                        Value::Null
-                    }
+                    let items: Vec<Value> = vec![#(#items),*];
+                    Value::Array(Array::from(items))
                 }
             }
+            Fields::Unit => quote! {
+                Value::Null
+            },
         }
     };
 
     quote! {
         impl #impl_generics ToValueBehavior  for #name #ty_generics #where_clause {
             fn to_value(&self) -> Value {
-                let mut map: std::collections::HashMap<String, Value>= std::collections::HashMap::new();
-                #(#field_transforms)*
-                Value::from(map)
+                #body
             }
         }
     }
@@ -82,13 +383,123 @@ fn to_value_enum_impl(
     name: syn::Ident,
     generics: Generics,
     variants: syn::punctuated::Punctuated<Variant, syn::Token![,]>,
+    container: &ContainerAttrs,
 ) -> proc_macro2::TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let tagging = container.tagging();
 
     let variant_transforms = variants.iter().map(|variant| {
         let variant_name = &variant.ident;
+        let key = variant_key(variant, container);
+
+        let (pattern, content) = match &variant.fields {
+            Fields::Unit => (quote! { #name::#variant_name }, None),
+            Fields::Unnamed(fields) => {
+                let bindings = (0..fields.unnamed.len())
+                    .map(|index| format_ident!("__field{}", index))
+                    .collect::<Vec<_>>();
+                let items = fields
+                    .unnamed
+                    .iter()
+                    .zip(&bindings)
+                    .map(|(field, binding)| {
+                        field_to_value(&FieldAttrs::from_attrs(&field.attrs), quote! { #binding })
+                    })
+                    .collect::<Vec<_>>();
+                let content = if items.len() == 1 {
+                    let field = &fields.unnamed[0];
+                    if let Tagging::Internal { .. } = tagging {
+                        if FieldAttrs::from_attrs(&field.attrs).with.is_none()
+                            && is_scalar(&field.ty)
+                        {
+                            panic!("Internally tagged newtype variants must contain an object");
+                        }
+                    }
+                    items[0].clone()
+                } else {
+                    if let Tagging::Internal { .. } = tagging {
+                        panic!("Internally tagged enums cannot contain tuple variants");
+                    }
+                    quote! {{
+                        let items: Vec<Value> = vec![#(#items),*];
+                        Value::Array(Array::from(items))
+                    }}
+                };
+                (
+                    quote! { #name::#variant_name(#(#bindings),*) },
+                    Some(content),
+                )
+            }
+            Fields::Named(fields) => {
+                let idents = fields
+                    .named
+                    .iter()
+                    .filter_map(|field| field.ident.clone())
+                    .collect::<Vec<_>>();
+                let bindings = idents
+                    .iter()
+                    .map(|ident| format_ident!("__{}", unraw(ident)))
+                    .collect::<Vec<_>>();
+                let inserts = named_fields_to_object(fields, &variant_fields_attrs(), |ident| {
+                    let binding = format_ident!("__{}", unraw(ident));
+                    quote! { #binding }
+                });
+                let tag_insert = match &tagging {
+                    Tagging::Internal { tag } => quote! {
+                        object.insert(#tag, Value::from(#key));
+                    },
+                    _ => quote! {},
+                };
+                let content = quote! {{
+                    let mut object = Object::default();
+                    #tag_insert
+                    #(#inserts)*
+                    Value::Object(object)
+                }};
+                (
+                    quote! { #name::#variant_name { #(#idents: #bindings,)* .. } },
+                    Some(content),
+                )
+            }
+        };
+
+        let value = match (&tagging, content) {
+            (Tagging::External, None) => quote! { Value::from(#key) },
+            (Tagging::External, Some(content)) => quote! {
+                Value::from(vec![(#key, #content)])
+            },
+            (Tagging::Internal { tag }, None) | (Tagging::Adjacent { tag, .. }, None) => quote! {
+                Value::from(vec![(#tag, Value::from(#key))])
+            },
+            (Tagging::Internal { tag }, Some(content)) => match &variant.fields {
+                Fields::Named(_) => content,
+                // A value that is not an object has no members to hold the tag, and is kept
+                // under the variant key instead.
+                _ => quote! {
+                    match #content {
+                        Value::Object(mut object) => {
+                            object.insert(#tag, Value::from(#key));
+                            Value::Object(object)
+                        }
+                        other => Value::from(vec![(#tag, Value::from(#key)), (#key, other)]),
+                    }
+                },
+            },
+            (
+                Tagging::Adjacent {
+                    tag,
+                    content: content_key,
+                },
+                Some(content),
+            ) => quote! {
+                Value::from(vec![(#tag, Value::from(#key)), (#content_key, #content)])
+            },
+            (Tagging::Untagged, None) => quote! { Value::Null },
+            (Tagging::Untagged, Some(content)) => content,
+        };
+
         quote! {
-            #name::#variant_name => Value::from(stringify!(#variant_name)),
+            #pattern => #value,
         }
     });
 
@@ -103,65 +514,331 @@ fn to_value_enum_impl(
     }
 }
 
-#[proc_macro_derive(FromValue)]
+/// Builds the expression turning a `content` value into the given variant.
+///
+/// The expression evaluates to `Option<Self>` and may `return None` early.
+fn variant_from_content(target_name: &Ident, variant: &Variant) -> proc_macro2::TokenStream {
+    let variant_name = &variant.ident;
+
+    match &variant.fields {
+        Fields::Unit => quote! { Some(#target_name::#variant_name) },
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let field = &fields.unnamed[0];
+            let from_value = field_from_value(
+                field,
+                &FieldAttrs::from_attrs(&field.attrs),
+                quote! { content },
+            );
+            quote! {
+                Some(#target_name::#variant_name(#from_value?))
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let total = fields.unnamed.len();
+            let items = fields.unnamed.iter().map(|field| {
+                let attrs = FieldAttrs::from_attrs(&field.attrs);
+                let from_value = field_from_value(field, &attrs, quote! { items.next()? });
+                quote! { #from_value? }
+            });
+            quote! {
+                match content {
+                    Value::Array(array) if array.len() == #total => {
+                        let mut items = array.into_iter();
+                        Some(#target_name::#variant_name(#(#items),*))
+                    }
+                    _ => None,
+                }
+            }
+        }
+        Fields::Named(fields) => {
+            let from_value_exprs = named_fields_from_map(fields, &variant_fields_attrs());
+            quote! {
+                match content {
+                    Value::Object(map) => Some(#target_name::#variant_name {
+                        #(#from_value_exprs),*
+                    }),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+fn from_value_enum_body(
+    target_name: &Ident,
+    variants: &syn::punctuated::Punctuated<Variant, syn::Token![,]>,
+    container: &ContainerAttrs,
+) -> proc_macro2::TokenStream {
+    let keys = variants
+        .iter()
+        .map(|variant| variant_key(variant, container))
+        .collect::<Vec<_>>();
+
+    match container.tagging() {
+        Tagging::External => {
+            let mut unit_keys = Vec::new();
+            let mut unit_names = Vec::new();
+            let mut data_keys = Vec::new();
+            let mut data_parsers = Vec::new();
+
+            for (variant, key) in variants.iter().zip(keys) {
+                if let Fields::Unit = variant.fields {
+                    unit_keys.push(key);
+                    unit_names.push(variant.ident.clone());
+                } else {
+                    data_keys.push(key);
+                    data_parsers.push(variant_from_content(target_name, variant));
+                }
+            }
+
+            let object_arm = if data_keys.is_empty() {
+                quote! {}
+            } else {
+                quote! {
+                    Value::Object(object) => {
+                        if object.len() != 1 {
+                            return None;
+                        }
+                        let (key, content) = match object.iter().next() {
+                            Some((key, content)) => (key.to_string(), content.clone()),
+                            None => return None,
+                        };
+                        match key.as_str() {
+                            #(
+                                #data_keys => #data_parsers,
+                            )*
+                            _ => None,
+                        }
+                    }
+                }
+            };
+
+            quote! {
+                match value {
+                    Value::String(value) => {
+                        match value.as_str() {
+                            #(
+                                #unit_keys => Some(#target_name::#unit_names),
+                            )*
+                            _ => None,
+                        }
+                    },
+                    #object_arm
+                    _ => None,
+                }
+            }
+        }
+        Tagging::Internal { tag } => {
+            let parsers = variants.iter().zip(&keys).map(|(variant, key)| {
+                let parser = variant_from_content(target_name, variant);
+                match &variant.fields {
+                    // Newtypes of values that are not objects are kept under the variant key.
+                    Fields::Unnamed(_) => quote! {{
+                        let parse = |content: Value| -> Option<Self> { #parser };
+                        match map.get(#key) {
+                            Some(item) if map.len() == 1 => parse(Value::Object(map.clone()))
+                                .or_else(|| parse(item.clone())),
+                            _ => parse(Value::Object(map)),
+                        }
+                    }},
+                    _ => quote! {{
+                        let content = Value::Object(map);
+                        #parser
+                    }},
+                }
+            });
+
+            quote! {
+                let mut map = match value {
+                    Value::Object(map) => map,
+                    _ => return None,
+                };
+                let tag = match map.remove(&#tag) {
+                    Some(Value::String(tag)) => tag.as_string(),
+                    _ => return None,
+                };
+                match tag.as_str() {
+                    #(
+                        #keys => #parsers
+                    )*
+                    _ => None,
+                }
+            }
+        }
+        Tagging::Adjacent { tag, content } => {
+            let arms = variants.iter().zip(keys).map(|(variant, key)| {
+                let parser = variant_from_content(target_name, variant);
+                match variant.fields {
+                    Fields::Unit => quote! { #key => #parser, },
+                    _ => quote! {
+                        #key => {
+                            let content = match map.get(#content) {
+                                Some(content) => content.clone(),
+                                None => return None,
+                            };
+                            #parser
+                        }
+                    },
+                }
+            });
+
+            quote! {
+                let map = match value {
+                    Value::Object(map) => map,
+                    _ => return None,
+                };
+                let tag = match map.get(#tag) {
+                    Some(Value::String(tag)) => tag.as_string(),
+                    _ => return None,
+                };
+                match tag.as_str() {
+                    #(#arms)*
+                    _ => None,
+                }
+            }
+        }
+        Tagging::Untagged => {
+            let attempts = variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let parser = match variant.fields {
+                    Fields::Unit => quote! {
+                        match content {
+                            Value::Null => Some(#target_name::#variant_name),
+                            _ => None,
+                        }
+                    },
+                    _ => variant_from_content(target_name, variant),
+                };
+
+                quote! {
+                    #[allow(clippy::redundant_closure_call)]
+                    let attempt = (|| -> Option<Self> {
+                        let content = value.clone();
+                        #parser
+                    })();
+                    if attempt.is_some() {
+                        return attempt;
+                    }
+                }
+            });
+
+            quote! {
+                #(#attempts)*
+                None
+            }
+        }
+    }
+}
+
+/// Builds the body of `from_value` for a `#[valu3(transparent)]` struct.
+fn from_value_transparent_body(fields: &Fields) -> proc_macro2::TokenStream {
+    let (inner_index, _) = transparent_field(fields);
+
+    let values = fields.iter().enumerate().map(|(index, field)| {
+        let attrs = FieldAttrs::from_attrs(&field.attrs);
+        if index == inner_index {
+            let from_value = field_from_value(field, &attrs, quote! { value });
+            quote! { #from_value? }
+        } else {
+            default_expr(attrs.default.as_ref().unwrap_or(&FieldDefault::Trait))
+        }
+    });
+
+    match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote! {
+                Some(Self { #(#names: #values),* })
+            }
+        }
+        _ => quote! {
+            Some(Self(#(#values),*))
+        },
+    }
+}
+
+#[proc_macro_derive(FromValue, attributes(valu3))]
 pub fn from_value_derive(input: TokenStream) -> TokenStream {
     // Parse a `DeriveInput` AST from the input tokens.
     let ast = parse_macro_input!(input as DeriveInput);
+    let container = ContainerAttrs::from_attrs(&ast.attrs);
 
     // Get the name and fields of the struct being derived.
     let target_name = &ast.ident;
-    let target_generics = &ast.generics;
+    let target_generics = &with_field_bounds(&ast.generics, &ast.data, |field, attrs| {
+        let field_type = &field.ty;
+        if attrs.skip {
+            match attrs.default {
+                Some(FieldDefault::Path(_)) => None,
+                _ => Some(quote! { ::std::default::Default }),
+            }
+        } else if attrs.with.is_some() {
+            match attrs.default {
+                Some(FieldDefault::Trait) => Some(quote! { ::std::default::Default }),
+                _ => None,
+            }
+        } else if let Some(FieldDefault::Trait) = attrs.default {
+            Some(quote! { FromValueBehavior<Item = #field_type> + ::std::default::Default })
+        } else {
+            Some(quote! { FromValueBehavior<Item = #field_type> })
+        }
+    });
     let (impl_generics, ty_generics, where_clause) = target_generics.split_for_impl();
 
     match ast.data {
         Data::Struct(data_struct) => {
             // Define a new implementation of the `FromValueBehavior` trait for the struct.
-            let mut field_names = Vec::new();
-            let mut from_value_exprs = Vec::new();
-
-            if let Fields::Named(fields) = data_struct.fields {
-                for field in fields.named.iter() {
-                    let field_name = match field.ident.as_ref() {
-                        Some(name) => name,
-                        None => panic!(
-                            "Can only derive FromValueBehavior for a struct with named fields."
-                        ),
-                    };
-                    let field_type = &field.ty;
-
-                    field_names.push(field_name.clone());
-
-                    from_value_exprs.push(quote! {
-                        #field_name: {
-                            let item = match map.get(stringify!(#field_name)) {
-                                Some(item) => item.clone(),
-                                None => return None,
-                            };
-                            match <#field_type as FromValueBehavior>::from_value(item) {
-                                Some(item) => item,
-                                None => return None,
+            let body = if container.transparent {
+                from_value_transparent_body(&data_struct.fields)
+            } else {
+                match &data_struct.fields {
+                    Fields::Named(fields) => {
+                        let from_value_exprs = named_fields_from_map(fields, &container);
+                        quote! {
+                            if let Value::Object(map) = value {
+                                Some(
+                                    Self {
+                                        #(#from_value_exprs),*
+                                    }
+                                )
+                            } else {
+                                None
+                            }
+                        }
+                    }
+                    Fields::Unnamed(fields) => {
+                        let total = fields.unnamed.len();
+                        let items = fields.unnamed.iter().map(|field| {
+                            let attrs = FieldAttrs::from_attrs(&field.attrs);
+                            let from_value =
+                                field_from_value(field, &attrs, quote! { items.next()? });
+                            quote! { #from_value? }
+                        });
+                        quote! {
+                            match value {
+                                Value::Array(array) if array.len() == #total => {
+                                    let mut items = array.into_iter();
+                                    Some(Self(#(#items),*))
+                                }
+                                _ => None,
                             }
                         }
-                    });
+                    }
+                    Fields::Unit => quote! {
+                        match value {
+                            Value::Null => Some(Self),
+                            _ => None,
+                        }
+                    },
                 }
-            } else {
-                panic!("Can only derive FromValueBehavior for a struct with named fields.");
-            }
+            };
 
             let expanded = quote! {
                 impl #impl_generics FromValueBehavior for #target_name #ty_generics #where_clause {
                     type Item = Self;
 
                     fn from_value(value: Value) -> Option<Self> {
-                        if let Value::Object(map) = value {
-                            Some(
-                                Self {
-                                    #(#from_value_exprs),*
-                                }
-                            )
-                        } else {
-                            None
-                        }
+                        #body
                     }
                 }
             };
@@ -169,13 +846,7 @@ pub fn from_value_derive(input: TokenStream) -> TokenStream {
             TokenStream::from(expanded)
         }
         Data::Enum(data_enum) => {
-            let variants = data_enum.variants;
-            let mut variant_names = Vec::new();
-
-            for variant in variants.iter() {
-                let variant_name = &variant.ident;
-                variant_names.push(variant_name.clone());
-            }
+            let body = from_value_enum_body(target_name, &data_enum.variants, &container);
 
             let expanded = quote! {
                 impl #impl_generics PrimitiveType for #target_name #ty_generics #where_clause {}
@@ -184,24 +855,14 @@ pub fn from_value_derive(input: TokenStream) -> TokenStream {
                     type Item = Self;
 
                     fn from_value(value: Value) -> Option<Self> {
-                        match value {
-                            Value::String(value) => {
-                                match value.as_str() {
-                                    #(
-                                        stringify!(#variant_names) => Some(#target_name::#variant_names),
-                                    )*
-                                    _ => None,
-                                }
-                            },
-                            _ => None,
-                        }
+                        #body
                     }
                 }
             };
 
             TokenStream::from(expanded)
         }
-        _ => panic!("Can only derive FromValueBehavior for a struct."),
+        Data::Union(_) => panic!("FromValueBehavior cannot be derived for unions"),
     }
 }
 
@@ -209,13 +870,13 @@ pub fn from_value_derive(input: TokenStream) -> TokenStream {
 pub fn to_json_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let name = &ast.ident;
-    let generics = &ast.generics;
+    let generics = &with_self_to_value_bound(name, &ast.generics);
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let gen = quote! {
         impl #impl_generics ToJsonBehavior for #name #ty_generics #where_clause {
             fn to_json(&self) -> String {
-                self.to_value().to_string()
+                self.to_value().to_json(JsonMode::Indented)
             }
         }
     };
@@ -227,7 +888,7 @@ pub fn to_json_derive(input: TokenStream) -> TokenStream {
 pub fn to_yaml_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
-    let generics = input.generics;
+    let generics = with_self_to_value_bound(&name, &input.generics);
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let expanded = quote! {
@@ -241,3 +902,334 @@ pub fn to_yaml_derive(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Returns `true` when a field type is written as `Option<..>`, which may be missing.
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// Whether `ty` is known from its syntax to convert into a value other than an object,
+/// such as a number, a string, a sequence or a tuple.
+fn is_scalar(ty: &syn::Type) -> bool {
+    const SCALARS: &[&str] = &[
+        "bool", "char", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+        "i128", "isize", "f32", "f64", "str", "String", "Vec", "VecDeque", "HashSet", "BTreeSet",
+    ];
+
+    match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| SCALARS.iter().any(|scalar| segment.ident == scalar)),
+        syn::Type::Reference(reference) => is_scalar(&reference.elem),
+        syn::Type::Array(_) | syn::Type::Slice(_) => true,
+        syn::Type::Tuple(tuple) => !tuple.elems.is_empty(),
+        _ => false,
+    }
+}
+
+/// Builds the expression returning the schema of a field.
+///
+/// Fields converted `with` a module accept any value, since their representation is not
+/// known from their type.
+fn field_schema(field: &syn::Field, attrs: &FieldAttrs) -> proc_macro2::TokenStream {
+    let field_type = &field.ty;
+    let schema = match &attrs.with {
+        Some(_) => quote! { Value::Boolean(true) },
+        None => quote! { <#field_type as SchemaBehavior>::schema() },
+    };
+
+    let default = match &attrs.default {
+        Some(default) => default_expr(default),
+        None => return schema,
+    };
+    let default_value = field_to_value(attrs, quote! { &default });
+    quote! {{
+        let mut schema = #schema;
+        if let Value::Object(object) = &mut schema {
+            let default: #field_type = #default;
+            object.insert("default", #default_value);
+        }
+        schema
+    }}
+}
+
+/// Builds the expression returning the schema of a tuple of fields, as a fixed-size array.
+fn tuple_schema(fields: &syn::FieldsUnnamed) -> proc_macro2::TokenStream {
+    let total = fields.unnamed.len();
+    let items = fields
+        .unnamed
+        .iter()
+        .map(|field| field_schema(field, &FieldAttrs::from_attrs(&field.attrs)));
+    quote! {{
+        let items: Vec<Value> = vec![#(#items),*];
+        Value::from(vec![
+            ("type", Value::from("array")),
+            ("prefixItems", Value::Array(Array::from(items))),
+            ("minItems", Value::from(#total)),
+            ("maxItems", Value::from(#total)),
+        ])
+    }}
+}
+
+/// Builds the statements adding the properties of an object `schema` expression to the
+/// `properties`, `required` and `additional` variables of `object_schema`.
+fn flatten_schema(schema: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        if let Value::Object(flattened) = #schema {
+            if let Some(Value::Object(inner)) = flattened.get("properties") {
+                let entries: std::collections::HashMap<ValueKey, Value> = inner.clone().into();
+                properties.extend(entries);
+            }
+            if let Some(Value::Array(inner)) = flattened.get("required") {
+                required.extend(inner.values.iter().cloned());
+            }
+            if let Some(inner) = flattened.get("additionalProperties") {
+                additional = Some(inner.clone());
+            }
+        }
+    }
+}
+
+/// Builds the expression returning the schema of an object with the given named fields.
+///
+/// Fields are required unless they are `Option`s or have a default, and flattened fields
+/// contribute the properties of their own schema. `tag` adds a required member holding
+/// the name of an internally tagged variant, along with the schema of its content.
+fn object_schema(
+    fields: Option<&FieldsNamed>,
+    container: &ContainerAttrs,
+    tag: Option<(&str, &str, Option<proc_macro2::TokenStream>)>,
+) -> proc_macro2::TokenStream {
+    let tag_property = tag.map(|(tag, key, content)| {
+        let content = content.map(flatten_schema);
+        quote! {
+            properties.insert(#tag, Value::from(vec![("const", Value::from(#key))]));
+            required.push(Value::from(#tag));
+            #content
+        }
+    });
+
+    let field_properties = fields
+        .into_iter()
+        .flat_map(|fields| fields.named.iter())
+        .filter_map(|field| {
+            let attrs = FieldAttrs::from_attrs(&field.attrs);
+            if attrs.skip {
+                return None;
+            }
+
+            let schema = field_schema(field, &attrs);
+            if attrs.flatten {
+                return Some(flatten_schema(schema));
+            }
+
+            let key = field_key(field, &attrs, container);
+            let require = if attrs.default.is_none() && !is_option(&field.ty) {
+                Some(quote! { required.push(Value::from(#key)); })
+            } else {
+                None
+            };
+            Some(quote! {
+                properties.insert(#key, #schema);
+                #require
+            })
+        });
+
+    quote! {{
+        let mut properties = Object::default();
+        let mut required: Vec<Value> = Vec::new();
+        #[allow(unused_mut)]
+        let mut additional: Option<Value> = None;
+        #tag_property
+        #(#field_properties)*
+
+        let mut schema = Object::default();
+        schema.insert("type", Value::from("object"));
+        schema.insert("properties", Value::Object(properties));
+        if !required.is_empty() {
+            schema.insert("required", Value::Array(Array::from(required)));
+        }
+        if let Some(additional) = additional {
+            schema.insert("additionalProperties", additional);
+        }
+        Value::Object(schema)
+    }}
+}
+
+/// Builds the expression returning the schema of the content of an enum variant.
+fn variant_content_schema(variant: &Variant) -> Option<proc_macro2::TokenStream> {
+    match &variant.fields {
+        Fields::Unit => None,
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let field = &fields.unnamed[0];
+            Some(field_schema(field, &FieldAttrs::from_attrs(&field.attrs)))
+        }
+        Fields::Unnamed(fields) => Some(tuple_schema(fields)),
+        Fields::Named(fields) => Some(object_schema(Some(fields), &variant_fields_attrs(), None)),
+    }
+}
+
+/// Builds the expression returning the schema of an enum, one alternative per variant.
+fn enum_schema(
+    variants: &syn::punctuated::Punctuated<Variant, syn::Token![,]>,
+    container: &ContainerAttrs,
+) -> proc_macro2::TokenStream {
+    let tagging = container.tagging();
+    let fields_attrs = variant_fields_attrs();
+    let string_enum = |keys: &[String]| {
+        quote! {
+            Value::from(vec![
+                ("type", Value::from("string")),
+                ("enum", Value::Array(Array::from(vec![#(Value::from(#keys)),*]))),
+            ])
+        }
+    };
+
+    let mut unit_keys = Vec::new();
+    let mut alternatives = Vec::new();
+    for variant in variants {
+        let key = variant_key(variant, container);
+        let content = variant_content_schema(variant);
+
+        let alternative = match (&tagging, content) {
+            (Tagging::External, None) => {
+                unit_keys.push(key);
+                continue;
+            }
+            (Tagging::External, Some(content)) => quote! {
+                Value::from(vec![
+                    ("type", Value::from("object")),
+                    ("properties", Value::from(vec![(#key, #content)])),
+                    ("required", Value::Array(Array::from(vec![Value::from(#key)]))),
+                    ("additionalProperties", Value::Boolean(false)),
+                ])
+            },
+            (Tagging::Internal { tag }, content) => match &variant.fields {
+                Fields::Named(fields) => {
+                    object_schema(Some(fields), &fields_attrs, Some((tag, &key, None)))
+                }
+                Fields::Unit => object_schema(None, &fields_attrs, Some((tag, &key, None))),
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    object_schema(None, &fields_attrs, Some((tag, &key, content)))
+                }
+                Fields::Unnamed(_) => {
+                    panic!("Internally tagged enums cannot contain tuple variants")
+                }
+            },
+            (
+                Tagging::Adjacent {
+                    tag,
+                    content: content_key,
+                },
+                content,
+            ) => {
+                let tag_schema = quote! { Value::from(vec![("const", Value::from(#key))]) };
+                let (properties, required) = match content {
+                    Some(content) => (
+                        quote! { vec![(#tag, #tag_schema), (#content_key, #content)] },
+                        quote! { vec![Value::from(#tag), Value::from(#content_key)] },
+                    ),
+                    None => (
+                        quote! { vec![(#tag, #tag_schema)] },
+                        quote! { vec![Value::from(#tag)] },
+                    ),
+                };
+                quote! {
+                    Value::from(vec![
+                        ("type", Value::from("object")),
+                        ("properties", Value::from(#properties)),
+                        ("required", Value::Array(Array::from(#required))),
+                    ])
+                }
+            }
+            (Tagging::Untagged, None) => quote! {
+                Value::from(vec![("type", Value::from("null"))])
+            },
+            (Tagging::Untagged, Some(content)) => content,
+        };
+        alternatives.push(alternative);
+    }
+
+    if alternatives.is_empty() {
+        return string_enum(&unit_keys);
+    }
+    if !unit_keys.is_empty() {
+        alternatives.insert(0, string_enum(&unit_keys));
+    }
+
+    // Untagged variants may overlap, tagged ones are told apart by their names.
+    let combinator = match tagging {
+        Tagging::Untagged => "anyOf",
+        _ => "oneOf",
+    };
+    quote! {{
+        let alternatives: Vec<Value> = vec![#(#alternatives),*];
+        Value::from(vec![(#combinator, Value::Array(Array::from(alternatives)))])
+    }}
+}
+
+#[proc_macro_derive(Schema, attributes(valu3))]
+pub fn schema_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let container = ContainerAttrs::from_attrs(&input.attrs);
+    let name = &input.ident;
+    let generics = with_field_bounds(&input.generics, &input.data, |_, attrs| {
+        if attrs.skip {
+            return None;
+        }
+
+        let mut bounds = Vec::new();
+        if attrs.with.is_none() {
+            bounds.push(quote! { SchemaBehavior });
+            if attrs.default.is_some() {
+                bounds.push(quote! { ToValueBehavior });
+            }
+        }
+        if let Some(FieldDefault::Trait) = attrs.default {
+            bounds.push(quote! { ::std::default::Default });
+        }
+
+        if bounds.is_empty() {
+            None
+        } else {
+            Some(quote! { #(#bounds)+* })
+        }
+    });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) if container.transparent => {
+            let (_, field) = transparent_field(&data.fields);
+            field_schema(field, &FieldAttrs::from_attrs(&field.attrs))
+        }
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => object_schema(Some(fields), &container, None),
+            Fields::Unnamed(fields) => tuple_schema(fields),
+            Fields::Unit => quote! {
+                Value::from(vec![("type", Value::from("null"))])
+            },
+        },
+        Data::Enum(data) => enum_schema(&data.variants, &container),
+        Data::Union(_) => panic!("SchemaBehavior cannot be derived for unions"),
+    };
+
+    let expanded = quote! {
+        impl #impl_generics SchemaBehavior for #name #ty_generics #where_clause {
+            fn schema() -> Value {
+                #body
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}